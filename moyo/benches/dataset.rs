@@ -1,10 +1,11 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 
+use nalgebra::{matrix, vector};
 use serde_json;
 use std::fs;
 use std::path::Path;
 
-use moyo::base::{AngleTolerance, Cell};
+use moyo::base::{AngleTolerance, Cell, Lattice};
 use moyo::data::Setting;
 use moyo::MoyoDataset;
 
@@ -17,6 +18,42 @@ pub fn benchmark(c: &mut Criterion) {
     c.bench_function("dataset_clathrate_Si", |b| {
         b.iter(|| MoyoDataset::new(&cell, symprec, angle_tolerance, setting))
     });
+
+    // 5x5x5 supercell of conventional FCC with 500 atoms
+    let cell = fcc_supercell(5);
+    c.bench_function("dataset_fcc_supercell_500", |b| {
+        b.iter(|| MoyoDataset::new(&cell, symprec, angle_tolerance, setting))
+    });
+}
+
+fn fcc_supercell(n: usize) -> Cell {
+    let mut positions = vec![];
+    let mut numbers = vec![];
+    for i in 0..n {
+        for j in 0..n {
+            for k in 0..n {
+                for t in [
+                    vector![0.0, 0.0, 0.0],
+                    vector![0.0, 0.5, 0.5],
+                    vector![0.5, 0.0, 0.5],
+                    vector![0.5, 0.5, 0.0],
+                ] {
+                    positions.push((vector![i as f64, j as f64, k as f64] + t) / n as f64);
+                    numbers.push(0);
+                }
+            }
+        }
+    }
+
+    Cell::new(
+        Lattice::new(matrix![
+            n as f64, 0.0, 0.0;
+            0.0, n as f64, 0.0;
+            0.0, 0.0, n as f64;
+        ]),
+        positions,
+        numbers,
+    )
 }
 
 criterion_group!(benches, benchmark);
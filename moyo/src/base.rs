@@ -3,27 +3,28 @@ mod cell;
 mod error;
 mod lattice;
 mod magnetic_cell;
+mod mcif;
 mod operation;
 mod permutation;
 mod tolerance;
 mod transformation;
 
 pub use action::RotationMagneticMomentAction;
-pub use cell::{AtomicSpecie, Cell, Position};
+pub use cell::{detect_vacuum, periodicity_dimension, AtomicSpecie, Cell, Position};
 pub use error::MoyoError;
 pub use lattice::Lattice;
 pub use magnetic_cell::{Collinear, MagneticCell, MagneticMoment, NonCollinear};
 pub use operation::{
-    MagneticOperation, MagneticOperations, Operation, Operations, Rotation, Rotations,
-    TimeReversal, Translation,
+    checked_operations, snap_translations, sort_magnetic_operations, MagneticOperation,
+    MagneticOperations, Operation, Operations, Rotation, Rotations, TimeReversal, Translation,
 };
 pub use permutation::Permutation;
-pub use tolerance::AngleTolerance;
-pub use transformation::{Linear, OriginShift};
+pub use tolerance::{AngleTolerance, MagSymprec, Symprec};
+pub use transformation::{Linear, OriginShift, Transformation};
 
 pub(super) use cell::orbits_from_permutations;
-pub(super) use operation::project_rotations;
 #[allow(unused_imports)]
 pub(super) use operation::traverse;
+pub(super) use operation::{check_closure, multiplication_table, project_rotations};
 pub(super) use tolerance::{MagneticSymmetryTolerances, SymmetryTolerances, ToleranceHandler, EPS};
-pub(super) use transformation::{Transformation, UnimodularLinear, UnimodularTransformation};
+pub(super) use transformation::{UnimodularLinear, UnimodularTransformation};
@@ -1,17 +1,26 @@
 use std::collections::BTreeMap;
 
+use itertools::iproduct;
 use nalgebra::{Matrix3, Vector3};
 use serde::{Deserialize, Serialize};
 use union_find::{QuickFindUf, UnionByRank, UnionFind};
 
+use super::error::MoyoError;
 use super::lattice::Lattice;
 use super::permutation::Permutation;
+use super::tolerance::EPS;
+use super::transformation::{Transformation, UnimodularTransformation};
 
 /// Fractional coordinates
 pub type Position = Vector3<f64>;
 /// Atomic number
 pub type AtomicSpecie = i32;
 
+/// Fractional coordinates within this distance below 1.0 are snapped to 0.0 in [`Cell::wrap`]
+const WRAP_TOLERANCE: f64 = 1e-6;
+/// Sites closer than this factor times their nearest-neighbor distance are regarded as bonded in [`periodicity_dimension`]
+const BOND_LENGTH_FACTOR: f64 = 1.2;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Representing a crystal structure
 pub struct Cell {
@@ -21,6 +30,9 @@ pub struct Cell {
     pub positions: Vec<Position>,
     /// `numbers[i]` is an atomic number of the i-th site.
     pub numbers: Vec<AtomicSpecie>,
+    /// Table of element symbols. If present, `numbers[i]` is an index into this table instead of an atomic number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element_symbols: Option<Vec<String>>,
 }
 
 impl Cell {
@@ -32,22 +44,287 @@ impl Cell {
             lattice,
             positions,
             numbers,
+            element_symbols: None,
         }
     }
 
+    /// Create a cell from element symbols of sites.
+    /// Each distinct symbol is labeled by its order of first appearance in `elements`, which is stored in `numbers`.
+    pub fn with_elements(
+        lattice: Lattice,
+        positions: Vec<Position>,
+        elements: Vec<String>,
+    ) -> Self {
+        let mut element_symbols: Vec<String> = vec![];
+        let numbers = elements
+            .into_iter()
+            .map(|element| {
+                let label = match element_symbols.iter().position(|e| *e == element) {
+                    Some(label) => label,
+                    None => {
+                        element_symbols.push(element);
+                        element_symbols.len() - 1
+                    }
+                };
+                label as AtomicSpecie
+            })
+            .collect();
+
+        let mut cell = Self::new(lattice, positions, numbers);
+        cell.element_symbols = Some(element_symbols);
+        cell
+    }
+
+    /// Return [`MoyoError::InvalidInputError`] if the lattice is invalid (see [`Lattice::validate`]) or some positions contain non-finite values.
+    pub fn validate(&self) -> Result<(), MoyoError> {
+        self.lattice.validate()?;
+        if self
+            .positions
+            .iter()
+            .any(|position| position.iter().any(|e| !e.is_finite()))
+        {
+            return Err(MoyoError::InvalidInputError);
+        }
+        Ok(())
+    }
+
+    /// Return the table of element symbols if the cell is created with [`Cell::with_elements`].
+    /// The element of the i-th site is `element_symbols()[numbers[i]]`.
+    pub fn element_symbols(&self) -> Option<&[String]> {
+        self.element_symbols.as_deref()
+    }
+
+    /// Return a cell with the same element-symbol table as `self`, which is valid when `cell` keeps the labels in `numbers`.
+    pub(crate) fn with_element_symbols_of(&self, mut cell: Cell) -> Cell {
+        cell.element_symbols = self.element_symbols.clone();
+        cell
+    }
+
     /// Return the number of atoms in the cell.
     pub fn num_atoms(&self) -> usize {
         self.positions.len()
     }
 
+    /// Return cartesian coordinates of sites.
+    pub fn cartesian_positions(&self) -> Vec<Vector3<f64>> {
+        self.positions
+            .iter()
+            .map(|position| self.lattice.cartesian_coords(position))
+            .collect()
+    }
+
+    /// Return a supercell whose basis vectors are given by `self.lattice.basis * matrix`.
+    /// Sites are replicated at all lattice-translation images inside the supercell, and fractional coordinates are wrapped into [0, 1).
+    /// Return [`MoyoError::InvalidInputError`] if the determinant of `matrix` is not positive.
+    pub fn make_supercell(&self, matrix: &Matrix3<i32>) -> Result<Self, MoyoError> {
+        let det = matrix.map(|e| e as f64).determinant().round() as i32;
+        if det <= 0 {
+            return Err(MoyoError::InvalidInputError);
+        }
+
+        let (supercell, _) = Transformation::from_linear(*matrix).transform_cell(self);
+        Ok(self.with_element_symbols_of(Self::new(
+            supercell.lattice,
+            supercell
+                .positions
+                .iter()
+                .map(|position| position.map(|e| e.rem_euclid(1.0)))
+                .collect(),
+            supercell.numbers,
+        )))
+    }
+
+    /// Return a cell whose fractional coordinates are wrapped into [0, 1).
+    /// Coordinates slightly below 1.0 due to numerical noise are snapped to 0.0.
+    pub fn wrap(&self) -> Self {
+        let positions = self
+            .positions
+            .iter()
+            .map(|position| {
+                position.map(|e| {
+                    let wrapped = e.rem_euclid(1.0);
+                    if 1.0 - wrapped < WRAP_TOLERANCE {
+                        0.0
+                    } else {
+                        wrapped
+                    }
+                })
+            })
+            .collect();
+        self.with_element_symbols_of(Self::new(
+            self.lattice.clone(),
+            positions,
+            self.numbers.clone(),
+        ))
+    }
+
+    /// Return a cell whose lattice is scaled by `factor` with fractional coordinates kept, e.g. `factor = 1.0 / 0.529177` converts angstrom to bohr.
+    /// Note that `symprec` in symmetry search is in the same unit as the lattice and should be scaled together.
+    pub fn rescale_length(&self, factor: f64) -> Self {
+        self.with_element_symbols_of(Self::new(
+            Lattice {
+                basis: factor * self.lattice.basis,
+            },
+            self.positions.clone(),
+            self.numbers.clone(),
+        ))
+    }
+
+    /// Return pairs `(i, j)` with `i < j` of sites whose periodic distance is shorter than `tol` in the unit of the lattice.
+    /// Such overlapping sites are often due to errors in input structures and make symmetry search fail.
+    pub fn find_overlaps(&self, tol: f64) -> Vec<(usize, usize)> {
+        // Nearest periodic images are searched in the adjacent images of a reduced cell
+        let reduced_cell = match self.lattice.minkowski_reduce() {
+            Ok((_, trans_mat)) => {
+                UnimodularTransformation::from_linear(trans_mat).transform_cell(self)
+            }
+            Err(_) => self.clone(),
+        };
+        let mut overlaps = vec![];
+        for j in 0..self.num_atoms() {
+            for i in 0..j {
+                let diff =
+                    (reduced_cell.positions[j] - reduced_cell.positions[i]).map(|e| e - e.round());
+                let overlapped = iproduct!(-1..=1, -1..=1, -1..=1).any(|offset| {
+                    let offset = Vector3::new(offset.0 as f64, offset.1 as f64, offset.2 as f64);
+                    reduced_cell
+                        .lattice
+                        .cartesian_coords(&(diff + offset))
+                        .norm()
+                        < tol
+                });
+                if overlapped {
+                    overlaps.push((i, j));
+                }
+            }
+        }
+        overlaps
+    }
+
     /// Rotate the cell by the given rotation matrix.
     pub fn rotate(&self, rotation_matrix: &Matrix3<f64>) -> Self {
-        Self::new(
+        self.with_element_symbols_of(Self::new(
             self.lattice.rotate(rotation_matrix),
             self.positions.clone(),
             self.numbers.clone(),
-        )
+        ))
+    }
+}
+
+/// Return whether each basis vector of `cell` crosses a vacuum region, e.g. the out-of-plane direction of a slab model.
+/// The `i`th direction is regarded as vacuum if the largest gap between neighboring atomic planes parallel to the other two basis vectors exceeds `threshold` in the unit of the lattice.
+pub fn detect_vacuum(cell: &Cell, threshold: f64) -> [bool; 3] {
+    let mut vacuum = [false; 3];
+    if cell.num_atoms() == 0 {
+        return vacuum;
+    }
+
+    let volume = cell.lattice.volume();
+    for (i, vacuum_i) in vacuum.iter_mut().enumerate() {
+        // Interplanar distance of the `i`th lattice planes
+        let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+        let spacing = volume
+            / cell
+                .lattice
+                .basis
+                .column(j)
+                .cross(&cell.lattice.basis.column(k))
+                .norm();
+
+        let mut coords = cell
+            .positions
+            .iter()
+            .map(|position| position[i].rem_euclid(1.0))
+            .collect::<Vec<_>>();
+        coords.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let max_gap = coords
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .fold(coords[0] + 1.0 - coords[coords.len() - 1], f64::max);
+        *vacuum_i = max_gap * spacing > threshold;
+    }
+    vacuum
+}
+
+/// Return the number of lattice directions along which bonded sites are connected infinitely: 3 for frameworks, 2 for layered materials, 1 for rod-like structures, and 0 for isolated molecules.
+/// Two sites are regarded as bonded if their distance is shorter than `BOND_LENGTH_FACTOR` times the larger nearest-neighbor distance of them plus `symprec`, and than `max_bond_length`.
+/// `max_bond_length` is in the same unit as the lattice, e.g. 6.0 for a lattice in angstrom. It keeps an isolated atom in a vacuum box from being bonded to its own periodic images, which cannot be told apart from a simple cubic crystal without a length scale.
+/// The dimension of each connected component is the rank of lattice translations that connect a site to its own periodic images, and the largest one is returned.
+pub fn periodicity_dimension(cell: &Cell, symprec: f64, max_bond_length: f64) -> usize {
+    let num_atoms = cell.num_atoms();
+    if num_atoms == 0 {
+        return 0;
+    }
+
+    // Bonded neighbors are searched in the adjacent images of a reduced cell
+    let reduced_cell = match cell.lattice.minkowski_reduce() {
+        Ok((_, trans_mat)) => UnimodularTransformation::from_linear(trans_mat).transform_cell(cell),
+        Err(_) => cell.clone(),
+    };
+    let positions = reduced_cell
+        .positions
+        .iter()
+        .map(|position| position.map(|e| e.rem_euclid(1.0)))
+        .collect::<Vec<_>>();
+    let mut distances = vec![];
+    for i in 0..num_atoms {
+        for j in 0..num_atoms {
+            for offset in iproduct!(-1..=1, -1..=1, -1..=1) {
+                let offset = Vector3::new(offset.0, offset.1, offset.2);
+                if i == j && offset == Vector3::zeros() {
+                    continue;
+                }
+                let diff = positions[j] + offset.map(|e| e as f64) - positions[i];
+                let distance = reduced_cell.lattice.cartesian_coords(&diff).norm();
+                distances.push((i, j, offset, distance));
+            }
+        }
+    }
+    let mut nearest = vec![f64::INFINITY; num_atoms];
+    for &(i, _, _, distance) in distances.iter() {
+        nearest[i] = nearest[i].min(distance);
+    }
+
+    // Bonds from the `i`th site to the `j`th site in the `offset` image
+    let mut bonds = vec![vec![]; num_atoms];
+    for &(i, j, offset, distance) in distances.iter() {
+        if distance < BOND_LENGTH_FACTOR * nearest[i].max(nearest[j]) + symprec
+            && distance < max_bond_length
+        {
+            bonds[i].push((j, offset));
+        }
+    }
+
+    // Traverse each connected component with image offsets of visited sites
+    let mut images: Vec<Option<Vector3<i32>>> = vec![None; num_atoms];
+    let mut dimension = 0;
+    for start in 0..num_atoms {
+        if images[start].is_some() {
+            continue;
+        }
+        images[start] = Some(Vector3::zeros());
+        let mut stack = vec![start];
+        let mut loops = Matrix3::<f64>::zeros();
+        while let Some(i) = stack.pop() {
+            let image_i = images[i].unwrap();
+            for &(j, offset) in bonds[i].iter() {
+                let image_j = image_i + offset;
+                match images[j] {
+                    Some(visited) => {
+                        // A path connecting the `j`th site with its periodic image
+                        let translation = (image_j - visited).map(|e| e as f64);
+                        loops += translation * translation.transpose();
+                    }
+                    None => {
+                        images[j] = Some(image_j);
+                        stack.push(j);
+                    }
+                }
+            }
+        }
+        dimension = dimension.max(loops.rank(EPS));
     }
+    dimension
 }
 
 /// If and only if the `i`th and `j`th atoms are equivalent, `orbits[i] == orbits[j]`.
@@ -73,9 +350,10 @@ pub fn orbits_from_permutations(num_atoms: usize, permutations: &[Permutation])
 mod tests {
     use std::panic;
 
-    use nalgebra::{vector, Matrix3};
+    use nalgebra::{matrix, vector, Matrix3, Vector3};
 
-    use super::{orbits_from_permutations, Cell};
+    use super::{detect_vacuum, orbits_from_permutations, periodicity_dimension, Cell};
+    use crate::base::error::MoyoError;
     use crate::base::lattice::Lattice;
     use crate::base::permutation::Permutation;
 
@@ -108,4 +386,231 @@ mod tests {
         let result = panic::catch_unwind(|| Cell::new(lattice, positions, numbers));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_with_elements() {
+        let lattice = Lattice::new(Matrix3::<f64>::identity());
+        let positions = vec![
+            vector![0.0, 0.0, 0.0],
+            vector![0.5, 0.5, 0.5],
+            vector![0.5, 0.0, 0.0],
+            vector![0.0, 0.5, 0.0],
+            vector![0.0, 0.0, 0.5],
+        ];
+        let elements = vec!["Sr", "Ti", "O", "O", "O"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let cell = Cell::with_elements(lattice, positions, elements.clone());
+
+        assert_eq!(cell.numbers, vec![0, 1, 2, 2, 2]);
+        let element_symbols = cell.element_symbols().unwrap();
+        assert_eq!(element_symbols, ["Sr", "Ti", "O"]);
+        let actual = cell
+            .numbers
+            .iter()
+            .map(|&number| element_symbols[number as usize].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, elements);
+
+        // Serialization
+        let serialized = serde_json::to_string(&cell).unwrap();
+        let deserialized: Cell = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.numbers, cell.numbers);
+        assert_eq!(deserialized.element_symbols(), cell.element_symbols());
+
+        // Cells without element symbols keep the previous format
+        let cell = Cell::new(cell.lattice, cell.positions, cell.numbers);
+        assert!(cell.element_symbols().is_none());
+        assert!(!serde_json::to_string(&cell)
+            .unwrap()
+            .contains("element_symbols"));
+    }
+
+    #[test]
+    fn test_cartesian_positions() {
+        // Non-orthogonal hexagonal lattice
+        let lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            -1.5, 1.5 * 3.0_f64.sqrt(), 0.0;
+            0.0, 0.0, 5.0;
+        ]);
+        let positions = vec![
+            vector![1.0 / 3.0, 2.0 / 3.0, 0.25],
+            vector![2.0 / 3.0, 1.0 / 3.0, 0.75],
+        ];
+        let cell = Cell::new(lattice, positions, vec![0, 0]);
+
+        let cartesian_positions = cell.cartesian_positions();
+        for (cartesian, fractional) in cartesian_positions.iter().zip(cell.positions.iter()) {
+            assert_relative_eq!(
+                cell.lattice.fractional_coords(cartesian),
+                *fractional,
+                epsilon = 1e-8
+            );
+        }
+    }
+
+    #[test]
+    fn test_make_supercell() {
+        // Primitive fcc
+        let lattice = Lattice::new(matrix![
+            0.0, 0.5, 0.5;
+            0.5, 0.0, 0.5;
+            0.5, 0.5, 0.0;
+        ]);
+        let cell = Cell::new(lattice, vec![vector![0.0, 0.0, 0.0]], vec![0]);
+
+        let supercell = cell
+            .make_supercell(&Matrix3::from_diagonal(&vector![2, 2, 2]))
+            .unwrap();
+        assert_eq!(supercell.num_atoms(), 8 * cell.num_atoms());
+        assert_relative_eq!(
+            supercell.lattice.volume(),
+            8.0 * cell.lattice.volume(),
+            epsilon = 1e-8
+        );
+        for position in supercell.positions.iter() {
+            assert!(position.iter().all(|&e| (0.0..1.0).contains(&e)));
+        }
+
+        assert_eq!(
+            cell.make_supercell(&Matrix3::zeros()).err(),
+            Some(MoyoError::InvalidInputError)
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let lattice = Lattice::new(Matrix3::<f64>::identity());
+        let positions = vec![
+            vector![1.0000001, -0.0000001, 0.0],
+            vector![1.25, -0.75, 0.5],
+        ];
+        let cell = Cell::new(lattice, positions, vec![0, 1]);
+
+        let wrapped = cell.wrap();
+        assert_relative_eq!(wrapped.positions[0], vector![0.0, 0.0, 0.0], epsilon = 1e-6);
+        assert_relative_eq!(
+            wrapped.positions[1],
+            vector![0.25, 0.25, 0.5],
+            epsilon = 1e-8
+        );
+        assert_eq!(wrapped.numbers, cell.numbers);
+    }
+
+    #[test]
+    fn test_find_overlaps() {
+        let lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 3.0, 0.0;
+            0.0, 0.0, 3.0;
+        ]);
+        let cell = Cell::new(
+            lattice.clone(),
+            vec![
+                vector![0.0, 0.0, 0.0],
+                vector![0.5, 0.5, 0.5],
+                vector![0.9999, 0.0, 1.0],
+            ],
+            vec![0, 1, 0],
+        );
+        assert_eq!(cell.find_overlaps(1e-2), vec![(0, 2)]);
+
+        let clean_cell = Cell::new(
+            lattice,
+            vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+            vec![0, 1],
+        );
+        assert!(clean_cell.find_overlaps(1e-2).is_empty());
+    }
+
+    #[test]
+    fn test_periodicity_dimension() {
+        let symprec = 1e-4;
+        let max_bond_length = 6.0;
+        let cell = |a: f64, b: f64, c: f64, positions: Vec<Vector3<f64>>| {
+            let numbers = vec![0; positions.len()];
+            Cell::new(
+                Lattice::new(Matrix3::from_diagonal(&vector![a, b, c])),
+                positions,
+                numbers,
+            )
+        };
+
+        // Simple cubic framework
+        let framework = cell(3.0, 3.0, 3.0, vec![vector![0.0, 0.0, 0.0]]);
+        assert_eq!(
+            periodicity_dimension(&framework, symprec, max_bond_length),
+            3
+        );
+
+        // Square layers separated by a vacuum
+        let layer = cell(3.0, 3.0, 15.0, vec![vector![0.0, 0.0, 0.0]]);
+        assert_eq!(periodicity_dimension(&layer, symprec, max_bond_length), 2);
+
+        // Atomic chain along c
+        let chain = cell(15.0, 15.0, 3.0, vec![vector![0.5, 0.5, 0.0]]);
+        assert_eq!(periodicity_dimension(&chain, symprec, max_bond_length), 1);
+
+        // Dimer in a box
+        let molecule = cell(
+            10.0,
+            10.0,
+            10.0,
+            vec![vector![0.5, 0.5, 0.45], vector![0.5, 0.5, 0.55]],
+        );
+        assert_eq!(
+            periodicity_dimension(&molecule, symprec, max_bond_length),
+            0
+        );
+
+        // Single atom in a box
+        let atom = cell(10.0, 10.0, 10.0, vec![vector![0.5, 0.5, 0.5]]);
+        assert_eq!(periodicity_dimension(&atom, symprec, max_bond_length), 0);
+
+        // Atomic chain along c with two sites per period
+        let dimers = cell(
+            10.0,
+            10.0,
+            4.0,
+            vec![vector![0.5, 0.5, 0.0], vector![0.5, 0.5, 0.5]],
+        );
+        assert_eq!(periodicity_dimension(&dimers, symprec, max_bond_length), 1);
+
+        // Same results in bohr with the cutoff in bohr
+        let bohr = 1.0 / 0.529177;
+        for (cell, expected) in [(&layer, 2), (&chain, 1), (&atom, 0)] {
+            assert_eq!(
+                periodicity_dimension(
+                    &cell.rescale_length(bohr),
+                    symprec * bohr,
+                    max_bond_length * bohr
+                ),
+                expected
+            );
+        }
+
+        // Without a cutoff, a single atom in a box is a simple cubic crystal
+        assert_eq!(periodicity_dimension(&atom, symprec, f64::INFINITY), 3);
+    }
+
+    #[test]
+    fn test_detect_vacuum() {
+        // Two-layer slab of a simple square lattice with a 15 angstrom vacuum along c
+        let a = 3.0;
+        let thickness = a;
+        let c = thickness + 15.0;
+        let lattice = Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, c;
+        ]);
+        let positions = vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, thickness / c]];
+        let numbers = vec![0, 0];
+        let cell = Cell::new(lattice, positions, numbers);
+
+        assert_eq!(detect_vacuum(&cell, 10.0), [false, false, true]);
+        assert_eq!(detect_vacuum(&cell, 20.0), [false, false, false]);
+    }
 }
@@ -1,18 +1,24 @@
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Error, Debug, PartialEq, Clone, Copy)]
 /// Error types for the **moyo** library
 pub enum MoyoError {
     #[error("Minkowski reduction failed")]
     MinkowskiReductionError,
-    #[error("Niggli reduction failed")]
+    #[error("Niggli reduction failed: the lattice may be degenerate or too skewed")]
     NiggliReductionError,
     #[error("Delaunay reduction failed")]
     DelaunayReductionError,
-    #[error("Too small tolerance")]
-    TooSmallToleranceError,
-    #[error("Too large tolerance")]
-    TooLargeToleranceError,
+    #[error(
+        "Too small tolerance (symprec={symprec}): consider increasing symprec or angle_tolerance"
+    )]
+    TooSmallToleranceError { symprec: f64 },
+    #[error(
+        "Too large tolerance (symprec={symprec}): consider reducing symprec or angle_tolerance"
+    )]
+    TooLargeToleranceError { symprec: f64 },
+    #[error("Found symmetry operations do not form a group (symprec={symprec}): consider reducing symprec or angle_tolerance")]
+    ClosureError { symprec: f64 },
     #[error("Primitive cell search failed")]
     PrimitiveCellError,
     #[error("Primitive symmetry search failed")]
@@ -27,15 +33,22 @@ pub enum MoyoError {
     ArithmeticCrystalClassIdentificationError,
     #[error("Space group type identification failed")]
     SpaceGroupTypeIdentificationError,
-    #[error("Construct type identification failed")]
-    ConstructTypeIdentificationError,
+    #[error("Construct type identification failed (|MSG|={order_msg}, |XSG|={order_xsg}, |FSG|={order_fsg}): |MSG|/|XSG| and |FSG|/|XSG| should be 1 or 2, and the magnetic operations may be incomplete")]
+    ConstructTypeIdentificationError {
+        /// Number of the given magnetic operations
+        order_msg: usize,
+        /// Number of operations without time reversal
+        order_xsg: usize,
+        /// Number of operations ignoring time reversal
+        order_fsg: usize,
+    },
     #[error("Magnetic space group type identification failed")]
     MagneticSpaceGroupTypeIdentificationError,
     #[error("Standardization failed")]
     StandardizationError,
     #[error("Magnetic standardization failed")]
     MagneticStandardizationError,
-    #[error("Wyckoff position assignment failed")]
+    #[error("Wyckoff position assignment failed: consider increasing symprec or symmetrizing the input cell")]
     WyckoffPositionAssignmentError,
     #[error("Hall symbol parsing failed")]
     HallSymbolParsingError,
@@ -43,4 +56,16 @@ pub enum MoyoError {
     UnknownHallNumberError,
     #[error("Unknown number")]
     UnknownNumberError,
+    #[error("Magnetic CIF parsing failed")]
+    MagneticCifParsingError,
+    #[error("Unknown setting name")]
+    SettingParsingError,
+    #[error("Operations do not form a group")]
+    InvalidOperationsError,
+    #[error("No site of the selected species")]
+    EmptySublatticeError,
+    #[error("Invalid input, e.g., non-finite values or a singular lattice")]
+    InvalidInputError,
+    #[error("Number of symmetry operations exceeds the given cap: {num_operations}")]
+    TooManyOperationsError { num_operations: usize },
 }
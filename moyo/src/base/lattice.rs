@@ -1,11 +1,16 @@
+use std::f64::consts::PI;
+
 use nalgebra::base::{Matrix3, Vector3};
+use nalgebra::SymmetricEigen;
 use serde::{Deserialize, Serialize};
 
 use crate::math::{
-    delaunay_reduce, is_minkowski_reduced, is_niggli_reduced, minkowski_reduce, niggli_reduce,
+    delaunay_reduce, is_minkowski_reduced, is_niggli_reduced, lll_reduce, minkowski_reduce,
+    niggli_reduce,
 };
 
 use super::error::MoyoError;
+use super::tolerance::EPS;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Representing basis vectors of a lattice
@@ -22,7 +27,29 @@ impl Lattice {
         }
     }
 
-    /// Return Minkowski reduced lattice and transformation matrix to it
+    /// Return Minkowski reduced lattice and transformation matrix to it.
+    /// The returned transformation matrix `trans_mat` is unimodular and satisfies `self.basis * trans_mat == reduced.basis`.
+    /// This is the same reduction used in the symmetry search.
+    /// If the reduced basis does not satisfy the Minkowski conditions due to numerical errors, [`MoyoError::MinkowskiReductionError`] is returned.
+    ///
+    /// ```
+    /// use approx::assert_relative_eq;
+    /// use nalgebra::matrix;
+    /// use moyo::base::Lattice;
+    ///
+    /// // Skewed primitive basis of a bcc lattice
+    /// let lattice = Lattice::new(matrix![
+    ///     -0.5, 0.5, 0.5;
+    ///     0.5, -0.5, 0.5;
+    ///     0.5, 0.5, 2.5;
+    /// ]);
+    /// let (reduced, trans_mat) = lattice.minkowski_reduce().unwrap();
+    /// assert_relative_eq!(lattice.basis * trans_mat.map(|e| e as f64), reduced.basis);
+    /// assert_eq!(trans_mat.map(|e| e as f64).determinant().abs().round(), 1.0);
+    /// for i in 0..3 {
+    ///     assert_relative_eq!(reduced.basis.column(i).norm(), 3.0_f64.sqrt() / 2.0);
+    /// }
+    /// ```
     pub fn minkowski_reduce(&self) -> Result<(Self, Matrix3<i32>), MoyoError> {
         let (reduced_basis, trans_mat) = minkowski_reduce(&self.basis);
         let reduced_lattice = Self {
@@ -52,7 +79,25 @@ impl Lattice {
         Ok((reduced_lattice, trans_mat))
     }
 
-    /// Return Niggli reduced lattice and transformation matrix to it without checking reduction condition
+    /// Return Niggli reduced lattice and transformation matrix to it without checking reduction condition.
+    /// The returned transformation matrix `trans_mat` is unimodular and satisfies `self.basis * trans_mat == reduced.basis`.
+    /// The reduced basis may slightly violate the Niggli conditions due to numerical errors; use [`Lattice::niggli_reduce`] to check them.
+    ///
+    /// ```
+    /// use approx::assert_relative_eq;
+    /// use nalgebra::matrix;
+    /// use moyo::base::Lattice;
+    ///
+    /// // Skewed primitive basis of a bcc lattice
+    /// let lattice = Lattice::new(matrix![
+    ///     -0.5, 0.5, 0.5;
+    ///     0.5, -0.5, 0.5;
+    ///     0.5, 0.5, 2.5;
+    /// ]);
+    /// let (reduced, trans_mat) = lattice.unchecked_niggli_reduce();
+    /// assert_relative_eq!(lattice.basis * trans_mat.map(|e| e as f64), reduced.basis);
+    /// assert!(reduced.is_niggli_reduced());
+    /// ```
     pub fn unchecked_niggli_reduce(&self) -> (Self, Matrix3<i32>) {
         let (reduced_basis, trans_mat) = niggli_reduce(&self.basis);
         let reduced_lattice = Self {
@@ -76,6 +121,46 @@ impl Lattice {
         Ok((reduced_lattice, trans_mat))
     }
 
+    /// Return LLL reduced lattice and transformation matrix to it.
+    /// The returned transformation matrix `trans_mat` is unimodular and satisfies `self.basis * trans_mat == reduced.basis`.
+    /// `delta` is the parameter in the Lovasz condition and should be in (1/4, 1]; 3/4 is a conventional choice.
+    /// LLL reduction does not give the shortest basis in general, but is numerically stable for highly skewed lattices.
+    ///
+    /// ```
+    /// use approx::assert_relative_eq;
+    /// use nalgebra::matrix;
+    /// use moyo::base::Lattice;
+    ///
+    /// let lattice = Lattice::new(matrix![
+    ///     1.0, 0.0, 0.0;
+    ///     0.0, 1.0, 0.0;
+    ///     5.0, 7.0, 1.0;
+    /// ]);
+    /// let (reduced, trans_mat) = lattice.lll_reduce(0.75);
+    /// assert_relative_eq!(lattice.basis * trans_mat.map(|e| e as f64), reduced.basis);
+    /// assert_relative_eq!(reduced.volume(), lattice.volume());
+    /// ```
+    pub fn lll_reduce(&self, delta: f64) -> (Self, Matrix3<i32>) {
+        let (reduced_basis, trans_mat) = lll_reduce(&self.basis, delta);
+        let reduced_lattice = Self {
+            basis: reduced_basis,
+        };
+        (reduced_lattice, trans_mat)
+    }
+
+    /// Return [`MoyoError::InvalidInputError`] if the basis contains non-finite values or is singular.
+    /// The lattice is regarded as singular if its volume is negligible compared to the product of the lengths of the basis vectors.
+    pub fn validate(&self) -> Result<(), MoyoError> {
+        if self.basis.iter().any(|e| !e.is_finite()) {
+            return Err(MoyoError::InvalidInputError);
+        }
+        let scale = (0..3).map(|i| self.basis.column(i).norm()).product::<f64>();
+        if self.volume() <= EPS * scale {
+            return Err(MoyoError::InvalidInputError);
+        }
+        Ok(())
+    }
+
     /// Return metric tensor of the basis vectors
     pub fn metric_tensor(&self) -> Matrix3<f64> {
         self.basis.transpose() * self.basis
@@ -86,22 +171,66 @@ impl Lattice {
         self.basis * fractional_coords
     }
 
+    /// Return fractional coordinates from the given cartesian coordinates
+    pub fn fractional_coords(&self, cartesian_coords: &Vector3<f64>) -> Vector3<f64> {
+        self.basis.try_inverse().unwrap() * cartesian_coords
+    }
+
     /// Return volume of the cell
     pub fn volume(&self) -> f64 {
         self.basis.determinant().abs()
     }
 
+    /// Return the reciprocal lattice with the physicist's convention, `a_i . b_j = 2 pi delta_ij`
+    pub fn reciprocal(&self) -> Self {
+        Self {
+            basis: 2.0 * PI * self.reciprocal_basis(),
+        }
+    }
+
+    /// Return the reciprocal lattice with the crystallographer's convention, `a_i . b_j = delta_ij`
+    pub fn crystallographic_reciprocal(&self) -> Self {
+        Self {
+            basis: self.reciprocal_basis(),
+        }
+    }
+
+    /// Return volume of the reciprocal cell with the physicist's convention, which is `(2 pi)^3 / self.volume()`
+    pub fn volume_reciprocal(&self) -> f64 {
+        self.reciprocal().volume()
+    }
+
+    fn reciprocal_basis(&self) -> Matrix3<f64> {
+        self.basis.try_inverse().unwrap().transpose()
+    }
+
     /// Rotate the lattice by the given rotation matrix
     pub fn rotate(&self, rotation_matrix: &Matrix3<f64>) -> Self {
         Self {
             basis: rotation_matrix * self.basis,
         }
     }
+
+    /// Decompose the deformation from `self` to `target` as `target.basis = rotation_matrix * stretch * self.basis`.
+    /// `rotation_matrix` is orthogonal and `stretch` is the symmetric right stretch tensor in the cartesian coordinates of `self`.
+    /// `target` should have the same handedness as `self`.
+    pub fn polar_decomposition(&self, target: &Lattice) -> (Matrix3<f64>, Matrix3<f64>) {
+        let deformation = target.basis * self.basis.try_inverse().unwrap();
+        // stretch = sqrt(deformation^T * deformation)
+        let eigen = SymmetricEigen::new(deformation.transpose() * deformation);
+        let stretch = eigen.eigenvectors
+            * Matrix3::from_diagonal(&eigen.eigenvalues.map(|e| e.sqrt()))
+            * eigen.eigenvectors.transpose();
+        let rotation_matrix = deformation * stretch.try_inverse().unwrap();
+        (rotation_matrix, stretch)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use nalgebra::matrix;
+    use std::f64::consts::PI;
+
+    use nalgebra::{matrix, vector, Matrix3};
 
     use super::Lattice;
 
@@ -122,4 +251,113 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_fractional_coords() {
+        // Hexagonal lattice
+        let a = 3.0;
+        let c = 5.0;
+        let lattice = Lattice::new(matrix![
+            a, 0.0, 0.0;
+            -a / 2.0, a * 3.0_f64.sqrt() / 2.0, 0.0;
+            0.0, 0.0, c;
+        ]);
+        let fractional_coords = vector![1.0 / 3.0, 2.0 / 3.0, 0.25];
+        let cartesian_coords = lattice.cartesian_coords(&fractional_coords);
+        assert_relative_eq!(
+            cartesian_coords,
+            vector![0.0, a / 3.0_f64.sqrt(), c / 4.0],
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(
+            lattice.fractional_coords(&cartesian_coords),
+            fractional_coords,
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        // Monoclinic lattice
+        let lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            -1.0, 0.0, 5.0;
+        ]);
+
+        let reciprocal = lattice.reciprocal();
+        assert_relative_eq!(
+            lattice.basis.transpose() * reciprocal.basis,
+            2.0 * PI * Matrix3::identity(),
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(reciprocal.reciprocal().basis, lattice.basis, epsilon = 1e-8);
+        assert_relative_eq!(
+            lattice.volume_reciprocal(),
+            (2.0 * PI).powi(3) / lattice.volume(),
+            epsilon = 1e-8
+        );
+
+        let crystallographic_reciprocal = lattice.crystallographic_reciprocal();
+        assert_relative_eq!(
+            crystallographic_reciprocal.basis,
+            reciprocal.basis / (2.0 * PI),
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(
+            crystallographic_reciprocal
+                .crystallographic_reciprocal()
+                .basis,
+            lattice.basis,
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(
+            crystallographic_reciprocal.volume() * lattice.volume(),
+            1.0,
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_polar_decomposition() {
+        let lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 5.0;
+        ]);
+        // Shear followed by a rotation around the z axis
+        let shear = matrix![
+            1.0, 0.1, 0.0;
+            0.0, 1.0, 0.0;
+            0.0, 0.0, 1.0;
+        ];
+        let angle = PI / 6.0;
+        let rotation = matrix![
+            angle.cos(), -angle.sin(), 0.0;
+            angle.sin(), angle.cos(), 0.0;
+            0.0, 0.0, 1.0;
+        ];
+        let target = Lattice {
+            basis: rotation * shear * lattice.basis,
+        };
+
+        let (rotation_matrix, stretch) = lattice.polar_decomposition(&target);
+        assert_relative_eq!(
+            rotation_matrix.transpose() * rotation_matrix,
+            Matrix3::identity(),
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(rotation_matrix.determinant(), 1.0, epsilon = 1e-8);
+        assert_relative_eq!(stretch, stretch.transpose(), epsilon = 1e-8);
+        assert_relative_eq!(
+            rotation_matrix * stretch * lattice.basis,
+            target.basis,
+            epsilon = 1e-8
+        );
+
+        // Rigid rotation only
+        let (rotation_matrix, stretch) = lattice.polar_decomposition(&lattice.rotate(&rotation));
+        assert_relative_eq!(rotation_matrix, rotation, epsilon = 1e-8);
+        assert_relative_eq!(stretch, Matrix3::identity(), epsilon = 1e-8);
+    }
 }
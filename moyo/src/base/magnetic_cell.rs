@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use super::action::RotationMagneticMomentAction;
 use super::cell::{AtomicSpecie, Cell, Position};
+use super::error::MoyoError;
 use super::lattice::Lattice;
 use super::operation::{CartesianRotation, TimeReversal};
 
@@ -19,6 +20,21 @@ pub trait MagneticMoment: Sized + Clone {
 
     fn average(magnetic_moments: &[Self]) -> Self;
 
+    /// Return the magnitude of the magnetic moment.
+    fn magnitude(&self) -> f64;
+
+    /// Return the magnetic moment with components smaller than `tol` in magnitude set to zero.
+    /// The default implementation returns the magnetic moment as it is.
+    fn canonicalize(&self, _tol: f64) -> Self {
+        self.clone()
+    }
+
+    /// Return the zero magnetic moment of the same kind as `self`, which is used for nonmagnetic sites.
+    /// The default implementation returns the average of the magnetic moment and its time reversal.
+    fn zero_like(&self) -> Self {
+        Self::average(&[self.clone(), self.act_time_reversal(true)])
+    }
+
     fn act_magnetic_operation(
         &self,
         cartesian_rotation: &CartesianRotation,
@@ -30,7 +46,7 @@ pub trait MagneticMoment: Sized + Clone {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Collinear(pub f64);
 
 impl MagneticMoment for Collinear {
@@ -64,9 +80,21 @@ impl MagneticMoment for Collinear {
         let sum = magnetic_moments.iter().map(|m| m.0).sum::<f64>();
         Collinear(sum / magnetic_moments.len() as f64)
     }
+
+    fn magnitude(&self) -> f64 {
+        self.0.abs()
+    }
+
+    fn canonicalize(&self, tol: f64) -> Self {
+        if self.0.abs() < tol {
+            Self(0.0)
+        } else {
+            Self(self.0)
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NonCollinear(pub Vector3<f64>);
 
 impl MagneticMoment for NonCollinear {
@@ -104,6 +132,17 @@ impl MagneticMoment for NonCollinear {
             .fold(Vector3::zeros(), |acc, x| acc + x);
         NonCollinear(sum / magnetic_moments.len() as f64)
     }
+
+    fn magnitude(&self) -> f64 {
+        self.0.norm()
+    }
+
+    fn canonicalize(&self, tol: f64) -> Self {
+        if self.0.norm() < tol {
+            return Self(Vector3::zeros());
+        }
+        Self(self.0.map(|e| if e.abs() < tol { 0.0 } else { e }))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,7 +173,48 @@ impl<M: MagneticMoment> MagneticCell<M> {
         }
     }
 
+    /// Create a magnetic cell where `None` in `magnetic_moments` marks a nonmagnetic site.
+    /// Nonmagnetic sites carry the zero magnetic moment and are skipped in comparing magnetic moments in symmetry search.
+    /// Return [`MoyoError::InvalidInputError`] if no site has a magnetic moment, because the zero magnetic moment is determined from one of them.
+    pub fn from_optional_moments(
+        cell: Cell,
+        magnetic_moments: Vec<Option<M>>,
+    ) -> Result<Self, MoyoError> {
+        let zero = magnetic_moments
+            .iter()
+            .flatten()
+            .next()
+            .ok_or(MoyoError::InvalidInputError)?
+            .zero_like();
+        let magnetic_moments = magnetic_moments
+            .into_iter()
+            .map(|m| m.unwrap_or_else(|| zero.clone()))
+            .collect();
+        Ok(Self::from_cell(cell, magnetic_moments))
+    }
+
     pub fn num_atoms(&self) -> usize {
         self.cell.num_atoms()
     }
+
+    /// Return indices of sites with nonzero magnetic moments.
+    pub fn magnetic_sites(&self) -> Vec<usize> {
+        self.magnetic_moments
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.magnitude() > 0.0)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Return the magnetic cell with tiny magnetic moments and their tiny components smaller than `tol` set to zero.
+    /// This is useful to remove numerical noise in magnetic moments, e.g. from DFT calculations, before symmetry search.
+    pub fn canonicalize_moments(&self, tol: f64) -> Self {
+        let magnetic_moments = self
+            .magnetic_moments
+            .iter()
+            .map(|m| m.canonicalize(tol))
+            .collect();
+        Self::from_cell(self.cell.clone(), magnetic_moments)
+    }
 }
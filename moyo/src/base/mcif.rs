@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use nalgebra::{matrix, Vector3};
+
+use super::cell::{Cell, Position};
+use super::error::MoyoError;
+use super::lattice::Lattice;
+use super::magnetic_cell::{MagneticCell, NonCollinear};
+
+impl MagneticCell<NonCollinear> {
+    /// Create a magnetic cell from a magnetic CIF (mCIF) string.
+    /// Only the cell parameters, the `_atom_site` loop, and the `_atom_site_moment` loop are read, and symmetry loops are ignored.
+    /// Thus, all sites in the cell should be listed in the `_atom_site` loop.
+    /// Magnetic moments given along crystal axes are converted to cartesian coordinates, and sites without moments are regarded as nonmagnetic.
+    pub fn from_mcif(text: &str) -> Result<Self, MoyoError> {
+        let cif = CifBlock::parse(text)?;
+
+        // Lattice
+        let a = cif.value("_cell_length_a")?;
+        let b = cif.value("_cell_length_b")?;
+        let c = cif.value("_cell_length_c")?;
+        let alpha = cif.value("_cell_angle_alpha")?.to_radians();
+        let beta = cif.value("_cell_angle_beta")?.to_radians();
+        let gamma = cif.value("_cell_angle_gamma")?.to_radians();
+        let cx = c * beta.cos();
+        let cy = c * (alpha.cos() - beta.cos() * gamma.cos()) / gamma.sin();
+        let cz = (c * c - cx * cx - cy * cy).sqrt();
+        let lattice = Lattice::new(matrix![
+            a, 0.0, 0.0;
+            b * gamma.cos(), b * gamma.sin(), 0.0;
+            cx, cy, cz;
+        ]);
+
+        // Sites
+        let atom_site = cif.find_loop("_atom_site_fract_x")?;
+        let labels = atom_site.column("_atom_site_label")?;
+        let elements = match atom_site.column("_atom_site_type_symbol") {
+            Ok(symbols) => symbols,
+            Err(_) => labels.clone(),
+        }
+        .iter()
+        .map(|symbol| element_symbol(symbol))
+        .collect::<Vec<_>>();
+        let xs = atom_site.float_column("_atom_site_fract_x")?;
+        let ys = atom_site.float_column("_atom_site_fract_y")?;
+        let zs = atom_site.float_column("_atom_site_fract_z")?;
+        let positions = (0..labels.len())
+            .map(|i| Position::new(xs[i], ys[i], zs[i]))
+            .collect::<Vec<_>>();
+
+        // Magnetic moments along unit vectors parallel to the basis vectors
+        let mut moments = HashMap::new();
+        if let Ok(atom_site_moment) = cif.find_loop("_atom_site_moment_label") {
+            let moment_labels = atom_site_moment.column("_atom_site_moment_label")?;
+            let mxs = atom_site_moment.float_column("_atom_site_moment_crystalaxis_x")?;
+            let mys = atom_site_moment.float_column("_atom_site_moment_crystalaxis_y")?;
+            let mzs = atom_site_moment.float_column("_atom_site_moment_crystalaxis_z")?;
+            for (i, label) in moment_labels.into_iter().enumerate() {
+                let moment = (0..3)
+                    .map(|j| {
+                        let axis = lattice.basis.column(j);
+                        [mxs[i], mys[i], mzs[i]][j] * axis / axis.norm()
+                    })
+                    .sum::<Vector3<f64>>();
+                moments.insert(label, moment);
+            }
+        }
+        let magnetic_moments = labels
+            .iter()
+            .map(|label| NonCollinear(moments.get(label).copied().unwrap_or_else(Vector3::zeros)))
+            .collect();
+
+        let cell = Cell::with_elements(lattice, positions, elements);
+        Ok(Self::from_cell(cell, magnetic_moments))
+    }
+}
+
+/// Strip a charge or a site index from a type symbol or a label, e.g. "Mn2+" -> "Mn"
+fn element_symbol(symbol: &str) -> String {
+    symbol
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect()
+}
+
+/// Parse a CIF number with an optional standard uncertainty, e.g. "4.873(2)"
+fn parse_number(value: &str) -> Result<f64, MoyoError> {
+    let value = value.split('(').next().unwrap();
+    value
+        .parse::<f64>()
+        .map_err(|_| MoyoError::MagneticCifParsingError)
+}
+
+/// Normalize DDL1 and DDL2 style tags, e.g. "_atom_site_moment.crystalaxis_x" -> "_atom_site_moment_crystalaxis_x"
+fn normalize_tag(tag: &str) -> String {
+    tag.to_lowercase().replace('.', "_")
+}
+
+#[derive(Debug)]
+struct CifLoop {
+    tags: Vec<String>,
+    values: Vec<String>,
+}
+
+impl CifLoop {
+    fn column(&self, tag: &str) -> Result<Vec<String>, MoyoError> {
+        let index = self
+            .tags
+            .iter()
+            .position(|t| t == tag)
+            .ok_or(MoyoError::MagneticCifParsingError)?;
+        Ok(self
+            .values
+            .chunks(self.tags.len())
+            .map(|row| row[index].clone())
+            .collect())
+    }
+
+    fn float_column(&self, tag: &str) -> Result<Vec<f64>, MoyoError> {
+        self.column(tag)?
+            .iter()
+            .map(|value| parse_number(value))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct CifBlock {
+    items: HashMap<String, String>,
+    loops: Vec<CifLoop>,
+}
+
+impl CifBlock {
+    fn parse(text: &str) -> Result<Self, MoyoError> {
+        let tokens = tokenize(text);
+        let mut items = HashMap::new();
+        let mut loops = vec![];
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            if token.to_lowercase() == "loop_" {
+                i += 1;
+                let mut tags = vec![];
+                while i < tokens.len() && tokens[i].starts_with('_') {
+                    tags.push(normalize_tag(&tokens[i]));
+                    i += 1;
+                }
+                let mut values = vec![];
+                while i < tokens.len() && !is_reserved(&tokens[i]) {
+                    values.push(tokens[i].clone());
+                    i += 1;
+                }
+                if tags.is_empty() || values.len() % tags.len() != 0 {
+                    return Err(MoyoError::MagneticCifParsingError);
+                }
+                loops.push(CifLoop { tags, values });
+            } else if token.starts_with('_') {
+                let value = tokens
+                    .get(i + 1)
+                    .ok_or(MoyoError::MagneticCifParsingError)?;
+                items.insert(normalize_tag(token), value.clone());
+                i += 2;
+            } else {
+                // e.g. data block header
+                i += 1;
+            }
+        }
+
+        Ok(Self { items, loops })
+    }
+
+    fn value(&self, tag: &str) -> Result<f64, MoyoError> {
+        let value = self
+            .items
+            .get(tag)
+            .ok_or(MoyoError::MagneticCifParsingError)?;
+        parse_number(value)
+    }
+
+    fn find_loop(&self, tag: &str) -> Result<&CifLoop, MoyoError> {
+        self.loops
+            .iter()
+            .find(|cif_loop| cif_loop.tags.iter().any(|t| t == tag))
+            .ok_or(MoyoError::MagneticCifParsingError)
+    }
+}
+
+fn is_reserved(token: &str) -> bool {
+    let token = token.to_lowercase();
+    token.starts_with('_') || token == "loop_" || token.starts_with("data_")
+}
+
+/// Split CIF text into tokens. Comments and semicolon-delimited text fields are skipped.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut in_text_field = false;
+    for line in text.lines() {
+        if line.starts_with(';') {
+            in_text_field = !in_text_field;
+            continue;
+        }
+        if in_text_field {
+            continue;
+        }
+
+        let mut chars = line.trim().chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '#' {
+                break;
+            } else if c == '\'' || c == '"' {
+                chars.next();
+                let token: String = chars.by_ref().take_while(|&e| e != c).collect();
+                tokens.push(token);
+            } else {
+                let mut token = String::new();
+                while let Some(&e) = chars.peek() {
+                    if e.is_whitespace() {
+                        break;
+                    }
+                    token.push(e);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_number, tokenize};
+
+    #[test]
+    fn test_tokenize() {
+        let text = "_space_group.name_H-M_alt 'P 42/m n m' # comment\n;\ntext field\n;\n_cell_length_a 4.873(2)";
+        let tokens = tokenize(text);
+        assert_eq!(
+            tokens,
+            vec![
+                "_space_group.name_H-M_alt",
+                "P 42/m n m",
+                "_cell_length_a",
+                "4.873(2)"
+            ]
+        );
+        assert_eq!(parse_number(&tokens[3]).unwrap(), 4.873);
+    }
+}
@@ -1,9 +1,11 @@
-use std::collections::{HashSet, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::ops::Mul;
 
 use nalgebra::base::{Matrix3, Vector3};
 
+use super::error::MoyoError;
 use super::lattice::Lattice;
 
 /// Rotation matrix in a crystallographic basis
@@ -37,6 +39,38 @@ impl Operation {
     pub fn identity() -> Self {
         Self::new(Rotation::identity(), Translation::zeros())
     }
+
+    /// Return `self * other` with its translation wrapped into [0, 1).
+    /// Note that `self.clone() * other.clone()` keeps the translation as it is.
+    pub fn compose_mod1(&self, other: &Operation) -> Operation {
+        let mut composed = self.clone() * other.clone();
+        composed.translation = composed.translation.map(|e| e.rem_euclid(1.));
+        composed
+    }
+
+    /// Decompose the translation into the intrinsic part and the location part, `translation = intrinsic + location`.
+    /// The intrinsic part is the screw or glide component, `(1/n) * sum_{k=0}^{n-1} rotation^k * translation`, where `n` is the order of the rotation.
+    /// The location part is the remaining component, which determines the position of the symmetry element.
+    /// Panics if the rotation is not crystallographic.
+    pub fn decompose_translation(&self) -> (Translation, Translation) {
+        // Crystallographic rotations have order at most 6
+        let mut power = Rotation::identity();
+        let mut sum = Rotation::zeros();
+        let mut order = 0;
+        loop {
+            sum += power;
+            order += 1;
+            power = self.rotation * power;
+            if power == Rotation::identity() {
+                break;
+            }
+            if order >= 6 {
+                panic!("Rotation should have finite order");
+            }
+        }
+        let intrinsic = sum.map(|e| e as f64) * self.translation / (order as f64);
+        (intrinsic, self.translation - intrinsic)
+    }
 }
 
 impl fmt::Debug for Operation {
@@ -113,6 +147,14 @@ impl MagneticOperation {
     pub fn identity() -> Self {
         Self::from_operation(Operation::identity(), false)
     }
+
+    /// Return `self * other` with its translation wrapped into [0, 1).
+    pub fn compose_mod1(&self, other: &MagneticOperation) -> MagneticOperation {
+        Self::from_operation(
+            self.operation.compose_mod1(&other.operation),
+            self.time_reversal ^ other.time_reversal,
+        )
+    }
 }
 
 impl fmt::Debug for MagneticOperation {
@@ -139,10 +181,118 @@ pub type Rotations = Vec<Rotation>;
 pub type Operations = Vec<Operation>;
 pub type MagneticOperations = Vec<MagneticOperation>;
 
+/// Sort magnetic operations by rotation, time reversal, and then translation.
+pub fn sort_magnetic_operations(magnetic_operations: &mut MagneticOperations) {
+    magnetic_operations.sort_by(|lhs, rhs| {
+        lhs.operation
+            .rotation
+            .iter()
+            .cmp(rhs.operation.rotation.iter())
+            .then(lhs.time_reversal.cmp(&rhs.time_reversal))
+            .then_with(|| {
+                lhs.operation
+                    .translation
+                    .iter()
+                    .zip(rhs.operation.translation.iter())
+                    .map(|(a, b)| a.total_cmp(b))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+    });
+}
+
 pub fn project_rotations(operations: &Operations) -> Rotations {
     operations.iter().map(|ops| ops.rotation).collect()
 }
 
+/// Return operations whose translations are snapped to the nearest fractions with denominators up to `max_denominator` and wrapped into [0, 1).
+/// This is meaningful for operations in a standardized setting, where translations are crystallographic fractions such as 1/3 and 1/6.
+pub fn snap_translations(operations: &Operations, max_denominator: i32) -> Operations {
+    operations
+        .iter()
+        .map(|ops| {
+            let translation = ops.translation.map(|e| {
+                // Denominators are tried in ascending order so that ties are snapped to the simpler fraction
+                (1..=max_denominator)
+                    .map(|denominator| (e * denominator as f64).round() / denominator as f64)
+                    .min_by(|a, b| (a - e).abs().total_cmp(&(b - e).abs()))
+                    .unwrap_or(e)
+                    .rem_euclid(1.0)
+            });
+            Operation::new(ops.rotation, translation)
+        })
+        .collect()
+}
+
+/// Create operations from `rotations` and `translations` after checking that they form a group modulo lattice translations.
+/// [`MoyoError::InvalidOperationsError`] is returned if `rotations` and `translations` have different lengths, the identity operation is missing, or the operations are not closed under multiplication within `symprec`.
+pub fn checked_operations(
+    rotations: &[Rotation],
+    translations: &[Translation],
+    lattice: &Lattice,
+    symprec: f64,
+) -> Result<Operations, MoyoError> {
+    if rotations.len() != translations.len() {
+        return Err(MoyoError::InvalidOperationsError);
+    }
+    let operations = rotations
+        .iter()
+        .zip(translations.iter())
+        .map(|(rotation, translation)| Operation::new(*rotation, *translation))
+        .collect::<Vec<_>>();
+
+    let has_identity = operations.iter().any(|ops| {
+        ops.rotation == Rotation::identity()
+            && lattice
+                .cartesian_coords(&ops.translation.map(|e| e - e.round()))
+                .norm()
+                < symprec
+    });
+    if !has_identity || !check_closure(&operations, lattice, symprec) {
+        return Err(MoyoError::InvalidOperationsError);
+    }
+    Ok(operations)
+}
+
+/// Return true if the product of any two operations is contained in `operations` modulo lattice translations.
+pub(crate) fn check_closure(operations: &Operations, lattice: &Lattice, symprec: f64) -> bool {
+    multiplication_table(operations, lattice, symprec).is_some()
+}
+
+/// Return `table` such that `operations[i] * operations[j]` is equal to `operations[table[i][j]]` modulo lattice translations.
+/// The closest translation is chosen among operations with the same rotation, and `None` is returned if it is farther than `symprec`.
+pub(crate) fn multiplication_table(
+    operations: &Operations,
+    lattice: &Lattice,
+    symprec: f64,
+) -> Option<Vec<Vec<usize>>> {
+    let mut indices_map: HashMap<Rotation, Vec<usize>> = HashMap::new();
+    for (i, operation) in operations.iter().enumerate() {
+        indices_map.entry(operation.rotation).or_default().push(i);
+    }
+    operations
+        .iter()
+        .map(|ops1| {
+            operations
+                .iter()
+                .map(|ops2| {
+                    let ops12 = ops1.clone() * ops2.clone();
+                    let (k, distance) = indices_map
+                        .get(&ops12.rotation)?
+                        .iter()
+                        .map(|&k| {
+                            let diff = (operations[k].translation - ops12.translation)
+                                .map(|e| e - e.round());
+                            (k, lattice.cartesian_coords(&diff).norm())
+                        })
+                        .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))?;
+                    (distance <= symprec).then_some(k)
+                })
+                .collect()
+        })
+        .collect()
+}
+
 #[allow(dead_code)]
 /// Used for testing
 pub fn traverse(generators: &Rotations) -> Rotations {
@@ -202,6 +352,133 @@ mod tests {
         assert_relative_eq!(actual, expect);
     }
 
+    #[test]
+    fn test_checked_operations() {
+        let lattice = Lattice::new(Matrix3::identity());
+        let symprec = 1e-4;
+        // P4
+        let rotations = vec![
+            Rotation::identity(),
+            matrix![
+                0, -1, 0;
+                1, 0, 0;
+                0, 0, 1;
+            ],
+            matrix![
+                -1, 0, 0;
+                0, -1, 0;
+                0, 0, 1;
+            ],
+            matrix![
+                0, 1, 0;
+                -1, 0, 0;
+                0, 0, 1;
+            ],
+        ];
+        let translations = vec![Translation::zeros(); 4];
+        let operations = checked_operations(&rotations, &translations, &lattice, symprec).unwrap();
+        assert_eq!(operations.len(), 4);
+
+        // Missing 4^-1
+        assert_eq!(
+            checked_operations(&rotations[..3], &translations[..3], &lattice, symprec).unwrap_err(),
+            MoyoError::InvalidOperationsError
+        );
+        // Missing identity
+        assert!(
+            checked_operations(&rotations[1..], &translations[1..], &lattice, symprec).is_err()
+        );
+        // Mismatched lengths
+        assert_eq!(
+            checked_operations(&rotations, &translations[..3], &lattice, symprec).unwrap_err(),
+            MoyoError::InvalidOperationsError
+        );
+    }
+
+    #[test]
+    fn test_compose_mod1() {
+        // c-glide perpendicular to b with (1/2, 0, 1/2) and c-glide perpendicular to a with (0, 1/2, 1/2)
+        let glide1 = Operation::new(
+            matrix![
+                1, 0, 0;
+                0, -1, 0;
+                0, 0, 1;
+            ],
+            vector![0.5, 0.0, 0.5],
+        );
+        let glide2 = Operation::new(
+            matrix![
+                -1, 0, 0;
+                0, 1, 0;
+                0, 0, 1;
+            ],
+            vector![0.0, 0.5, 0.5],
+        );
+
+        let actual = glide1.compose_mod1(&glide2);
+        assert_eq!(
+            actual.rotation,
+            matrix![
+                -1, 0, 0;
+                0, -1, 0;
+                0, 0, 1;
+            ]
+        );
+        // (1/2, -1/2, 1) -> (1/2, 1/2, 0)
+        assert_relative_eq!(actual.translation, vector![0.5, 0.5, 0.0]);
+        assert_relative_eq!(
+            (glide1.clone() * glide2.clone()).translation,
+            vector![0.5, -0.5, 1.0]
+        );
+
+        let actual = MagneticOperation::from_operation(glide1, true)
+            .compose_mod1(&MagneticOperation::from_operation(glide2, false));
+        assert!(actual.time_reversal);
+        assert_relative_eq!(actual.operation.translation, vector![0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_decompose_translation() {
+        // 2_1 screw axis along c at x = 1/4
+        let screw = Operation::new(
+            matrix![
+                -1, 0, 0;
+                0, -1, 0;
+                0, 0, 1;
+            ],
+            vector![0.5, 0.0, 0.5],
+        );
+        let (intrinsic, location) = screw.decompose_translation();
+        assert_relative_eq!(intrinsic, vector![0.0, 0.0, 0.5]);
+        assert_relative_eq!(location, vector![0.5, 0.0, 0.0]);
+
+        // c-glide perpendicular to b at y = 1/4
+        let glide = Operation::new(
+            matrix![
+                1, 0, 0;
+                0, -1, 0;
+                0, 0, 1;
+            ],
+            vector![0.0, 0.5, 0.5],
+        );
+        let (intrinsic, location) = glide.decompose_translation();
+        assert_relative_eq!(intrinsic, vector![0.0, 0.0, 0.5]);
+        assert_relative_eq!(location, vector![0.0, 0.5, 0.0]);
+
+        // 3_1 screw axis along c in hexagonal axes
+        let screw = Operation::new(
+            matrix![
+                0, -1, 0;
+                1, -1, 0;
+                0, 0, 1;
+            ],
+            vector![0.0, 0.0, 1.0 / 3.0],
+        );
+        let (intrinsic, location) = screw.decompose_translation();
+        assert_relative_eq!(intrinsic, vector![0.0, 0.0, 1.0 / 3.0]);
+        assert_relative_eq!(location, Translation::zeros());
+    }
+
     #[test]
     fn test_operation_format() {
         let operation = Operation::new(
@@ -228,4 +505,26 @@ mod tests {
         );
         assert_eq!(format!("{:?}", magnetic_operation), "+x,+x-y+0.25,+z-0.75'")
     }
+
+    #[test]
+    fn test_snap_translations() {
+        // 6_1 screw and 3_1 screw along c in a hexagonal group
+        let six = matrix![
+            1, -1, 0;
+            1, 0, 0;
+            0, 0, 1;
+        ];
+        let operations = vec![
+            Operation::new(six, vector![0.0, 0.0, 0.16667]),
+            Operation::new(six * six, vector![1e-6, -1e-6, 0.33333]),
+        ];
+        let snapped = snap_translations(&operations, 12);
+        assert_eq!(snapped[0].rotation, six);
+        assert_eq!(snapped[0].translation, vector![0.0, 0.0, 1.0 / 6.0]);
+        assert_eq!(snapped[1].translation, vector![0.0, 0.0, 1.0 / 3.0]);
+
+        // 1/6 is not available with denominators up to 4
+        let snapped = snap_translations(&operations, 4);
+        assert_eq!(snapped[0].translation, vector![0.0, 0.0, 0.25]);
+    }
 }
@@ -1,13 +1,16 @@
 use log::debug;
 use std::fmt::Debug;
+use std::mem::{discriminant, Discriminant};
 
 use super::error::MoyoError;
+use super::lattice::Lattice;
+use super::magnetic_cell::MagneticMoment;
 
 pub const EPS: f64 = 1e-8;
 
 const INITIAL_SYMMETRY_SEARCH_STRIDE: f64 = 2.0;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 /// Tolerance for angle in comparing basis vectors in symmetry search.
 pub enum AngleTolerance {
     /// Tolerance in radian.
@@ -16,6 +19,68 @@ pub enum AngleTolerance {
     Default,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Tolerance for positions in symmetry search.
+pub enum Symprec {
+    /// Tolerance in the same length unit as the lattice, e.g. angstrom for a lattice in angstrom and bohr for a lattice in bohr.
+    Absolute(f64),
+    /// Tolerance relative to the shortest length of basis vectors of the lattice.
+    Relative(f64),
+}
+
+impl Symprec {
+    /// Return the absolute tolerance for the given lattice.
+    pub fn absolute(&self, lattice: &Lattice) -> f64 {
+        match *self {
+            Symprec::Absolute(symprec) => symprec,
+            Symprec::Relative(ratio) => {
+                let min_norm = lattice
+                    .basis
+                    .column_iter()
+                    .map(|v| v.norm())
+                    .fold(f64::INFINITY, f64::min);
+                ratio * min_norm
+            }
+        }
+    }
+}
+
+impl From<f64> for Symprec {
+    fn from(symprec: f64) -> Self {
+        Symprec::Absolute(symprec)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Tolerance for magnetic moments in magnetic symmetry search.
+pub enum MagSymprec {
+    /// Tolerance in the unit of magnetic moments.
+    Absolute(f64),
+    /// Tolerance relative to the largest magnitude of magnetic moments in the cell.
+    Relative(f64),
+}
+
+impl MagSymprec {
+    /// Return the absolute tolerance for the given magnetic moments.
+    /// If all magnetic moments vanish, a relative tolerance is regarded as absolute.
+    pub fn absolute<M: MagneticMoment>(&self, magnetic_moments: &[M]) -> f64 {
+        match *self {
+            MagSymprec::Absolute(mag_symprec) => mag_symprec,
+            MagSymprec::Relative(ratio) => {
+                let max_magnitude = magnetic_moments
+                    .iter()
+                    .map(|m| m.magnitude())
+                    .fold(0.0, f64::max);
+                if max_magnitude > EPS {
+                    ratio * max_magnitude
+                } else {
+                    ratio
+                }
+            }
+        }
+    }
+}
+
 pub trait Tolerances {
     fn increase_tolerances(&self, stride: f64) -> Self;
     fn reduce_tolerances(&self, stride: f64) -> Self;
@@ -97,7 +162,8 @@ impl Tolerances for MagneticSymmetryTolerances {
 pub struct ToleranceHandler<T: Tolerances> {
     pub tolerances: T,
     stride: f64,
-    prev_error: Option<MoyoError>,
+    /// Kind of the previous error. Errors are compared by their variants so that their context, e.g. `symprec`, is ignored.
+    prev_error: Option<Discriminant<MoyoError>>,
 }
 
 impl<T: Tolerances + Debug> ToleranceHandler<T> {
@@ -111,14 +177,17 @@ impl<T: Tolerances + Debug> ToleranceHandler<T> {
 
     pub fn update(&mut self, err: MoyoError) {
         // Update stride
-        if self.prev_error.is_some() && self.prev_error != Some(err) {
+        if self
+            .prev_error
+            .is_some_and(|prev_err| prev_err != discriminant(&err))
+        {
             self.stride = self.stride.sqrt()
         }
-        self.prev_error = Some(err);
+        self.prev_error = Some(discriminant(&err));
 
         // Update tolerances
         self.tolerances = match err {
-            MoyoError::TooSmallToleranceError => {
+            MoyoError::TooSmallToleranceError { .. } => {
                 let new_tolerances = self.tolerances.increase_tolerances(self.stride);
                 debug!("Increase tolerances: {:?}", new_tolerances);
                 new_tolerances
@@ -131,3 +200,33 @@ impl<T: Tolerances + Debug> ToleranceHandler<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AngleTolerance, MoyoError, SymmetryTolerances, ToleranceHandler,
+        INITIAL_SYMMETRY_SEARCH_STRIDE,
+    };
+
+    #[test]
+    fn test_tolerance_handler_stride() {
+        let mut handler = ToleranceHandler::new(SymmetryTolerances {
+            symprec: 1e-4,
+            angle_tolerance: AngleTolerance::Default,
+        });
+
+        // Errors of the same kind keep the stride even if their context differs
+        handler.update(MoyoError::TooLargeToleranceError { symprec: 1e-4 });
+        handler.update(MoyoError::TooLargeToleranceError { symprec: 5e-5 });
+        assert_eq!(handler.stride, INITIAL_SYMMETRY_SEARCH_STRIDE);
+        assert_relative_eq!(handler.tolerances.symprec, 2.5e-5);
+
+        // A different kind of error shrinks the stride
+        handler.update(MoyoError::PrimitiveSymmetrySearchError);
+        assert_relative_eq!(handler.stride, INITIAL_SYMMETRY_SEARCH_STRIDE.sqrt());
+        assert_relative_eq!(
+            handler.tolerances.symprec,
+            2.5e-5 / INITIAL_SYMMETRY_SEARCH_STRIDE.sqrt()
+        );
+    }
+}
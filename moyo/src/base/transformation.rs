@@ -2,6 +2,7 @@ use std::ops::Mul;
 
 use itertools::iproduct;
 use nalgebra::base::{Matrix3, Vector3};
+use serde_json::{json, Value};
 
 use super::cell::Cell;
 use super::lattice::Lattice;
@@ -185,6 +186,53 @@ impl Transformation {
         self.linear.map(|e| e as f64)
     }
 
+    /// Serialize in Spglib's convention as `{"transformation_matrix": P, "origin_shift": p}`.
+    ///
+    /// In moyo, `(linear, origin_shift)` changes a basis as `(a', b', c') = (a, b, c) * linear` and fractional coordinates as `x' = linear^-1 * (x - origin_shift)`.
+    /// In Spglib, `(P, p)` changes fractional coordinates as `x' = P * x + p`, so `P = linear^-1` and `p = -linear^-1 * origin_shift`.
+    /// `P` is written as a list of rows.
+    pub fn to_spglib_json(&self) -> Value {
+        let origin_shift = -self.linear_inv * self.origin_shift;
+        json!({
+            "transformation_matrix": (0..3)
+                .map(|i| (0..3).map(|j| self.linear_inv[(i, j)]).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            "origin_shift": origin_shift.iter().collect::<Vec<_>>(),
+        })
+    }
+
+    /// Deserialize from Spglib's convention described in [`Transformation::to_spglib_json`].
+    /// Return `None` if `value` is malformed or `P^-1` is not an integer matrix with a positive determinant.
+    pub fn from_spglib_json(value: &Value) -> Option<Self> {
+        let rows = value.get("transformation_matrix")?.as_array()?;
+        let shift = value.get("origin_shift")?.as_array()?;
+        if rows.len() != 3 || shift.len() != 3 {
+            return None;
+        }
+        let mut spglib_linear = Matrix3::<f64>::zeros();
+        for (i, row) in rows.iter().enumerate() {
+            let row = row.as_array()?;
+            if row.len() != 3 {
+                return None;
+            }
+            for (j, e) in row.iter().enumerate() {
+                spglib_linear[(i, j)] = e.as_f64()?;
+            }
+        }
+        let spglib_origin_shift =
+            Vector3::new(shift[0].as_f64()?, shift[1].as_f64()?, shift[2].as_f64()?);
+
+        // linear = P^-1, origin_shift = -P^-1 * p
+        let linear_f64 = spglib_linear.try_inverse()?;
+        let linear = linear_f64.map(|e| e.round() as i32);
+        if (linear_f64 - linear.map(|e| e as f64)).abs().max() > 1e-8
+            || linear.map(|e| e as f64).determinant() < 0.5
+        {
+            return None;
+        }
+        Some(Self::new(linear, -linear_f64 * spglib_origin_shift))
+    }
+
     pub fn transform_lattice(&self, lattice: &Lattice) -> Lattice {
         self.transform_lattice_with_linear(lattice, &self.linear_as_f64())
     }
@@ -369,7 +417,7 @@ fn transform_operation_as_f64(
 
 #[cfg(test)]
 mod tests {
-    use nalgebra::matrix;
+    use nalgebra::{matrix, vector};
 
     use super::Transformation;
     use crate::base::operation::{Operation, Translation};
@@ -392,4 +440,43 @@ mod tests {
         );
         assert!(transformation.transform_operation(&operation).is_none());
     }
+
+    #[test]
+    fn test_spglib_json() {
+        // Primitive to conventional cells of face-centered lattice with an origin shift
+        let transformation = Transformation::new(
+            matrix![
+                -1, 1, 1;
+                1, -1, 1;
+                1, 1, -1;
+            ],
+            vector![0.25, 0.5, 0.0],
+        );
+
+        let value = transformation.to_spglib_json();
+        // Spglib's transformation matrix maps coordinates in the primitive cell to the conventional one
+        let expect = [[0.0, 0.5, 0.5], [0.5, 0.0, 0.5], [0.5, 0.5, 0.0]];
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_relative_eq!(
+                    value["transformation_matrix"][i][j].as_f64().unwrap(),
+                    expect[i][j]
+                );
+            }
+        }
+        // p = -P * origin_shift
+        let p = -transformation.linear_inv * transformation.origin_shift;
+        for i in 0..3 {
+            assert_relative_eq!(value["origin_shift"][i].as_f64().unwrap(), p[i]);
+        }
+
+        let restored = Transformation::from_spglib_json(&value).unwrap();
+        assert_eq!(restored.linear, transformation.linear);
+        assert_relative_eq!(restored.origin_shift, transformation.origin_shift);
+
+        // Non-integral inverse
+        let mut invalid = value.clone();
+        invalid["transformation_matrix"][0][0] = 0.3.into();
+        assert!(Transformation::from_spglib_json(&invalid).is_none());
+    }
 }
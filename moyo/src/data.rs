@@ -5,6 +5,7 @@ mod hall_symbol;
 mod hall_symbol_database;
 mod magnetic_hall_symbol_database;
 mod magnetic_space_group;
+mod maximal_subgroup;
 mod point_group;
 mod setting;
 mod wyckoff;
@@ -12,17 +13,24 @@ mod wyckoff;
 pub use arithmetic_crystal_class::ArithmeticNumber;
 pub use centering::Centering;
 pub use hall_symbol::{HallSymbol, MagneticHallSymbol};
-pub use hall_symbol_database::{hall_symbol_entry, HallNumber, HallSymbolEntry, Number};
+pub use hall_symbol_database::{
+    hall_symbol_entry, space_groups_for_arithmetic_class, HallNumber, HallSymbolEntry, Number,
+};
 pub use magnetic_hall_symbol_database::{magnetic_hall_symbol_entry, MagneticHallSymbolEntry};
 pub use magnetic_space_group::{
-    get_magnetic_space_group_type, ConstructType, UNINumber, NUM_MAGNETIC_SPACE_GROUP_TYPES,
+    get_magnetic_space_group_type, space_group_number_from_uni_number, uni_number_from_bns,
+    uni_numbers_for_space_group, ConstructType, UNINumber, NUM_MAGNETIC_SPACE_GROUP_TYPES,
 };
-pub use setting::Setting;
+pub use maximal_subgroup::maximal_subgroup_numbers;
+pub use setting::{OriginChoice, RhombohedralSetting, Setting};
 
 pub(super) use arithmetic_crystal_class::{
     arithmetic_crystal_class_entry, iter_arithmetic_crystal_entry,
 };
-pub(super) use classification::{CrystalSystem, GeometricCrystalClass, LatticeSystem};
+pub use classification::{space_group_range, CrystalSystem, GeometricCrystalClass};
+
+pub(super) use classification::LatticeSystem;
 pub(super) use magnetic_space_group::uni_number_range;
 pub(super) use point_group::PointGroupRepresentative;
-pub(super) use wyckoff::{iter_wyckoff_positions, WyckoffPosition, WyckoffPositionSpace};
+pub(super) use wyckoff::{iter_wyckoff_positions, WyckoffPositionSpace};
+pub use wyckoff::{wyckoff_of, WyckoffPosition};
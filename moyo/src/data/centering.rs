@@ -1,7 +1,8 @@
 use nalgebra::{Matrix3, Vector3};
 use strum_macros::EnumIter;
 
-use crate::base::{Linear, Translation};
+use super::setting::RhombohedralSetting;
+use crate::base::{Cell, Linear, Transformation, Translation};
 
 #[derive(Debug, Copy, Clone, PartialEq, EnumIter)]
 pub enum Centering {
@@ -10,7 +11,7 @@ pub enum Centering {
     B, // B-face centered
     C, // C-face centered
     I, // Body centered
-    R, // Rhombohedral (obverse setting)
+    R, // Rhombohedral (obverse setting by default)
     F, // Face centered
 }
 
@@ -70,6 +71,32 @@ impl Centering {
         self.linear().map(|e| e as f64).try_inverse().unwrap()
     }
 
+    /// Return the conventional cell whose basis is `prim_cell.lattice.basis * self.linear()`, with sites of `prim_cell` replicated at the lattice points of the centering.
+    /// `prim_cell` should be related to the conventional cell by this centering, e.g. `prim_std_cell` of [`crate::MoyoDataset`].
+    /// Fractional coordinates are wrapped into [0, 1).
+    pub fn to_conventional(&self, prim_cell: &Cell) -> Cell {
+        let (conv_cell, _) = Transformation::from_linear(self.linear()).transform_cell(prim_cell);
+        prim_cell.with_element_symbols_of(conv_cell.wrap())
+    }
+
+    /// Same as [`Centering::lattice_points`] but rhombohedral lattice points are given in `rhombohedral_setting`.
+    /// Other centerings do not depend on `rhombohedral_setting`.
+    pub fn lattice_points_in(
+        &self,
+        rhombohedral_setting: RhombohedralSetting,
+    ) -> Vec<Vector3<f64>> {
+        if *self != Centering::R {
+            return self.lattice_points();
+        }
+        // x_reverse = linear^-1 * x_obverse, where linear is its own inverse
+        let linear = rhombohedral_setting.linear().map(|e| e as f64);
+        self.lattice_points()
+            .iter()
+            .map(|point| (linear * point).map(|e| e.rem_euclid(1.0)))
+            .collect()
+    }
+
+    /// Lattice points of the centering in the conventional cell. Rhombohedral lattice points are in the obverse setting.
     pub fn lattice_points(&self) -> Vec<Vector3<f64>> {
         match self {
             Centering::P => {
@@ -130,8 +157,58 @@ impl Centering {
 mod tests {
     use strum::IntoEnumIterator;
 
+    use nalgebra::{matrix, vector};
+
     use super::*;
-    use crate::base::Transformation;
+    use crate::base::Lattice;
+
+    #[test]
+    fn test_to_conventional() {
+        // Primitive cell of bcc
+        let conv_lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 3.0, 0.0;
+            0.0, 0.0, 3.0;
+        ]);
+        let prim_lattice = Lattice {
+            basis: conv_lattice.basis * Centering::I.inverse(),
+        };
+        let prim_cell = Cell::new(prim_lattice, vec![vector![0.0, 0.0, 0.0]], vec![0]);
+
+        let conv_cell = Centering::I.to_conventional(&prim_cell);
+        assert_relative_eq!(conv_cell.lattice.basis, conv_lattice.basis, epsilon = 1e-8);
+        assert_eq!(conv_cell.numbers, vec![0, 0]);
+        for lattice_point in Centering::I.lattice_points() {
+            assert!(conv_cell
+                .positions
+                .iter()
+                .any(|position| (position - lattice_point).norm() < 1e-8));
+        }
+    }
+
+    #[test]
+    fn test_reverse_lattice_points() {
+        let obverse = Centering::R.lattice_points_in(RhombohedralSetting::Obverse);
+        let reverse = Centering::R.lattice_points_in(RhombohedralSetting::Reverse);
+        assert_eq!(obverse, Centering::R.lattice_points());
+        assert_eq!(reverse.len(), 3);
+        // Components along the a and b axes are negated
+        for (t_obverse, t_reverse) in obverse.iter().zip(reverse.iter()) {
+            let sum = t_obverse + t_reverse;
+            assert_relative_eq!(sum[0] - sum[0].round(), 0.0);
+            assert_relative_eq!(sum[1] - sum[1].round(), 0.0);
+            assert_relative_eq!(t_obverse[2], t_reverse[2]);
+        }
+        assert_relative_eq!(
+            reverse[1],
+            Translation::new(1.0 / 3.0, 2.0 / 3.0, 1.0 / 3.0),
+            epsilon = 1e-8
+        );
+        assert_eq!(
+            Centering::F.lattice_points_in(RhombohedralSetting::Reverse),
+            Centering::F.lattice_points()
+        );
+    }
 
     #[test]
     fn test_conventional_transformation_matrix() {
@@ -1,5 +1,10 @@
+use std::ops::RangeInclusive;
+
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+use super::hall_symbol_database::Number;
+
 /// ===========================================================================
 /// Classification based on point group
 /// ===========================================================================
@@ -47,6 +52,89 @@ pub enum GeometricCrystalClass {
     Oh, // m-3m
 }
 
+impl GeometricCrystalClass {
+    /// Hermann-Mauguin symbol of the point group
+    pub fn hermann_mauguin(&self) -> &'static str {
+        match self {
+            // Triclinic
+            GeometricCrystalClass::C1 => "1",
+            GeometricCrystalClass::Ci => "-1",
+            // Monoclinic
+            GeometricCrystalClass::C2 => "2",
+            GeometricCrystalClass::C1h => "m",
+            GeometricCrystalClass::C2h => "2/m",
+            // Orthorhombic
+            GeometricCrystalClass::D2 => "222",
+            GeometricCrystalClass::C2v => "mm2",
+            GeometricCrystalClass::D2h => "mmm",
+            // Tetragonal
+            GeometricCrystalClass::C4 => "4",
+            GeometricCrystalClass::S4 => "-4",
+            GeometricCrystalClass::C4h => "4/m",
+            GeometricCrystalClass::D4 => "422",
+            GeometricCrystalClass::C4v => "4mm",
+            GeometricCrystalClass::D2d => "-42m",
+            GeometricCrystalClass::D4h => "4/mmm",
+            // Trigonal
+            GeometricCrystalClass::C3 => "3",
+            GeometricCrystalClass::C3i => "-3",
+            GeometricCrystalClass::D3 => "32",
+            GeometricCrystalClass::C3v => "3m",
+            GeometricCrystalClass::D3d => "-3m",
+            // Hexagonal
+            GeometricCrystalClass::C6 => "6",
+            GeometricCrystalClass::C3h => "-6",
+            GeometricCrystalClass::C6h => "6/m",
+            GeometricCrystalClass::D6 => "622",
+            GeometricCrystalClass::C6v => "6mm",
+            GeometricCrystalClass::D3h => "-6m2",
+            GeometricCrystalClass::D6h => "6/mmm",
+            // Cubic
+            GeometricCrystalClass::T => "23",
+            GeometricCrystalClass::Th => "m-3",
+            GeometricCrystalClass::O => "432",
+            GeometricCrystalClass::Td => "-43m",
+            GeometricCrystalClass::Oh => "m-3m",
+        }
+    }
+
+    /// Number of rotations in the point group
+    pub fn order(&self) -> usize {
+        match self {
+            GeometricCrystalClass::C1 => 1,
+            GeometricCrystalClass::Ci | GeometricCrystalClass::C2 | GeometricCrystalClass::C1h => 2,
+            GeometricCrystalClass::C3 => 3,
+            GeometricCrystalClass::C2h
+            | GeometricCrystalClass::D2
+            | GeometricCrystalClass::C2v
+            | GeometricCrystalClass::C4
+            | GeometricCrystalClass::S4 => 4,
+            GeometricCrystalClass::C3i
+            | GeometricCrystalClass::D3
+            | GeometricCrystalClass::C3v
+            | GeometricCrystalClass::C6
+            | GeometricCrystalClass::C3h => 6,
+            GeometricCrystalClass::D2h
+            | GeometricCrystalClass::C4h
+            | GeometricCrystalClass::D4
+            | GeometricCrystalClass::C4v
+            | GeometricCrystalClass::D2d => 8,
+            GeometricCrystalClass::D3d
+            | GeometricCrystalClass::C6h
+            | GeometricCrystalClass::D6
+            | GeometricCrystalClass::C6v
+            | GeometricCrystalClass::D3h
+            | GeometricCrystalClass::T => 12,
+            GeometricCrystalClass::D4h => 16,
+            GeometricCrystalClass::D6h
+            | GeometricCrystalClass::Th
+            | GeometricCrystalClass::O
+            | GeometricCrystalClass::Td => 24,
+            GeometricCrystalClass::Oh => 48,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
 pub enum LaueClass {
     Ci,  // -1
@@ -153,6 +241,27 @@ impl CrystalSystem {
             | GeometricCrystalClass::Oh => CrystalSystem::Cubic,
         }
     }
+
+    /// Return the crystal system of the space-group type with ITA number `number`.
+    /// Panics if `number` is not in 1..=230.
+    pub fn from_space_group_number(number: Number) -> Self {
+        CrystalSystem::iter()
+            .find(|crystal_system| space_group_range(*crystal_system).contains(&number))
+            .expect("Space-group number should be in 1..=230")
+    }
+}
+
+/// Return inclusive range of ITA numbers of space-group types in the given crystal system.
+pub fn space_group_range(crystal_system: CrystalSystem) -> RangeInclusive<Number> {
+    match crystal_system {
+        CrystalSystem::Triclinic => 1..=2,
+        CrystalSystem::Monoclinic => 3..=15,
+        CrystalSystem::Orthorhombic => 16..=74,
+        CrystalSystem::Tetragonal => 75..=142,
+        CrystalSystem::Trigonal => 143..=167,
+        CrystalSystem::Hexagonal => 168..=194,
+        CrystalSystem::Cubic => 195..=230,
+    }
 }
 
 /// ===========================================================================
@@ -245,3 +354,34 @@ impl CrystalFamily {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::arithmetic_crystal_class::arithmetic_crystal_class_entry;
+    use crate::data::hall_symbol_database::hall_symbol_entry;
+
+    #[test]
+    fn test_space_group_range() {
+        assert_eq!(
+            CrystalSystem::from_space_group_number(136),
+            CrystalSystem::Tetragonal
+        );
+        assert_eq!(
+            CrystalSystem::from_space_group_number(230),
+            CrystalSystem::Cubic
+        );
+        assert_eq!(space_group_range(CrystalSystem::Cubic), 195..=230);
+
+        // Consistent with geometric crystal classes in the database
+        for hall_number in 1..=530 {
+            let entry = hall_symbol_entry(hall_number).unwrap();
+            let geometric_crystal_class =
+                arithmetic_crystal_class_entry(entry.arithmetic_number).geometric_crystal_class;
+            assert_eq!(
+                CrystalSystem::from_space_group_number(entry.number),
+                CrystalSystem::from_geometric_crystal_class(geometric_crystal_class)
+            );
+        }
+    }
+}
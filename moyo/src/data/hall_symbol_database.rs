@@ -58,6 +58,17 @@ pub fn hall_symbol_entry(hall_number: HallNumber) -> Option<HallSymbolEntry> {
         .cloned()
 }
 
+/// Return space-group numbers belonging to the arithmetic crystal class `arithmetic_number` in ascending order.
+pub fn space_groups_for_arithmetic_class(arithmetic_number: ArithmeticNumber) -> Vec<Number> {
+    let mut numbers = HALL_SYMBOL_DATABASE
+        .iter()
+        .filter(|entry| entry.arithmetic_number == arithmetic_number)
+        .map(|entry| entry.number)
+        .collect::<Vec<_>>();
+    numbers.dedup();
+    numbers
+}
+
 const HALL_SYMBOL_DATABASE: [HallSymbolEntry; 530] = [
     HallSymbolEntry::new(1, 1, 1, "", "P 1", "P 1", "P 1", Centering::P),
     HallSymbolEntry::new(2, 2, 2, "", "-P 1", "P -1", "P -1", Centering::P),
@@ -4346,7 +4357,7 @@ const HALL_SYMBOL_DATABASE: [HallSymbolEntry; 530] = [
 
 #[cfg(test)]
 mod tests {
-    use super::{HallSymbolEntry, HALL_SYMBOL_DATABASE};
+    use super::{space_groups_for_arithmetic_class, HallSymbolEntry, HALL_SYMBOL_DATABASE};
     use crate::data::hall_symbol::HallSymbol;
 
     fn iter_hall_symbol_entry() -> impl Iterator<Item = &'static HallSymbolEntry> {
@@ -4360,4 +4371,19 @@ mod tests {
             assert_eq!(48 % hs.traverse().len(), 0);
         }
     }
+
+    #[test]
+    fn test_space_groups_for_arithmetic_class() {
+        // 4/mmmP
+        assert_eq!(
+            space_groups_for_arithmetic_class(36),
+            (123..=138).collect::<Vec<_>>()
+        );
+        // 4/mmmI
+        assert_eq!(
+            space_groups_for_arithmetic_class(37),
+            (139..=142).collect::<Vec<_>>()
+        );
+        assert!(space_groups_for_arithmetic_class(0).is_empty());
+    }
 }
@@ -77,6 +77,33 @@ pub fn get_magnetic_space_group_type(uni_number: UNINumber) -> Option<MagneticSp
         .cloned()
 }
 
+/// Return ITA number of the reference space group in BNS setting for the given UNI number.
+pub fn space_group_number_from_uni_number(uni_number: UNINumber) -> Option<Number> {
+    if uni_number < 1 {
+        return None;
+    }
+    get_magnetic_space_group_type(uni_number).map(|entry| entry.number)
+}
+
+/// Return UNI numbers whose reference space group in BNS setting is `number`.
+pub fn uni_numbers_for_space_group(number: Number) -> Vec<UNINumber> {
+    if number < 1 {
+        return vec![];
+    }
+    uni_number_range(number)
+        .map(|range| range.collect())
+        .unwrap_or_default()
+}
+
+/// Return UNI number for the given BNS number like "136.499".
+pub fn uni_number_from_bns(bns_number: &str) -> Option<UNINumber> {
+    let bns_number = bns_number.trim();
+    MAGNETIC_SPACE_GROUP_TYPES
+        .iter()
+        .find(|entry| entry.bns_number == bns_number)
+        .map(|entry| entry.uni_number)
+}
+
 const MAGNETIC_SPACE_GROUP_TYPES: [MagneticSpaceGroupType; NUM_MAGNETIC_SPACE_GROUP_TYPES] = [
     MagneticSpaceGroupType::new(1, 1, "1.1", "1.1.1", 1, ConstructType::Type1),
     MagneticSpaceGroupType::new(2, 2, "1.2", "1.2.2", 1, ConstructType::Type2),
@@ -6168,3 +6195,27 @@ const MAGNETIC_SPACE_GROUP_TYPES: [MagneticSpaceGroupType; NUM_MAGNETIC_SPACE_GR
         ConstructType::Type3,
     ),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_magnetic_space_group_type, space_group_number_from_uni_number, uni_number_from_bns,
+        uni_numbers_for_space_group,
+    };
+
+    #[test]
+    fn test_uni_number_conversion() {
+        assert_eq!(uni_number_from_bns("136.499"), Some(1159));
+        assert_eq!(
+            get_magnetic_space_group_type(1159).unwrap().bns_number,
+            "136.499"
+        );
+        assert_eq!(space_group_number_from_uni_number(1159), Some(136));
+        assert!(uni_numbers_for_space_group(136).contains(&1159));
+        assert_eq!(uni_numbers_for_space_group(1), vec![1, 2, 3]);
+
+        assert_eq!(uni_number_from_bns("999.999"), None);
+        assert_eq!(space_group_number_from_uni_number(0), None);
+        assert!(uni_numbers_for_space_group(231).is_empty());
+    }
+}
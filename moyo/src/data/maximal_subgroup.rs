@@ -0,0 +1,482 @@
+use super::hall_symbol_database::Number;
+
+/// Return space-group numbers of the types of maximal subgroups of the space-group type `number` in ascending order, or `None` for an unknown `number`.
+/// The list contains translationengleiche subgroups, non-isomorphic klassengleiche subgroups, whose indices are at most 4, and isomorphic subgroups, which are of the same type as `number` or its enantiomorphic partner.
+pub fn maximal_subgroup_numbers(number: Number) -> Option<&'static [Number]> {
+    let index = usize::try_from(number).ok()?.checked_sub(1)?;
+    MAXIMAL_SUBGROUP_DATABASE.get(index).copied()
+}
+
+// Generated by enumerating subgroups of the default Hall symbol of each space-group type, which is cross-checked in `identify::subgroup`
+const MAXIMAL_SUBGROUP_DATABASE: [&[Number]; 230] = [
+    // 1: P1
+    &[1],
+    // 2: P-1
+    &[1, 2],
+    // 3: P2
+    &[1, 3, 4, 5],
+    // 4: P2_1
+    &[1, 4],
+    // 5: C2
+    &[1, 3, 4, 5],
+    // 6: Pm
+    &[1, 6, 7, 8],
+    // 7: Pc
+    &[1, 7, 9],
+    // 8: Cm
+    &[1, 6, 7, 8, 9],
+    // 9: Cc
+    &[1, 7, 9],
+    // 10: P2/m
+    &[2, 3, 6, 10, 11, 12, 13],
+    // 11: P2_1/m
+    &[2, 4, 6, 11, 14],
+    // 12: C2/m
+    &[2, 5, 8, 10, 11, 12, 13, 14, 15],
+    // 13: P2/c
+    &[2, 3, 7, 13, 14, 15],
+    // 14: P2_1/c
+    &[2, 4, 7, 14],
+    // 15: C2/c
+    &[2, 5, 9, 13, 14, 15],
+    // 16: P222
+    &[3, 16, 17, 21, 22],
+    // 17: P222_1
+    &[3, 4, 17, 18, 20],
+    // 18: P2_12_12
+    &[3, 4, 18, 19],
+    // 19: P2_12_12_1
+    &[4, 19],
+    // 20: C222_1
+    &[4, 5, 17, 18, 19, 20],
+    // 21: C222
+    &[3, 5, 16, 17, 18, 20, 21, 23, 24],
+    // 22: F222
+    &[5, 20, 21, 22],
+    // 23: I222
+    &[5, 16, 18, 23],
+    // 24: I2_12_12_1
+    &[5, 17, 19, 24],
+    // 25: Pmm2
+    &[3, 6, 25, 26, 27, 28, 35, 38, 39, 42],
+    // 26: Pmc2_1
+    &[4, 6, 7, 26, 29, 31, 36],
+    // 27: Pcc2
+    &[3, 7, 27, 30, 37],
+    // 28: Pma2
+    &[3, 6, 7, 28, 29, 30, 31, 32, 40, 41],
+    // 29: Pca2_1
+    &[4, 7, 29, 33],
+    // 30: Pnc2
+    &[3, 7, 30, 34],
+    // 31: Pmn2_1
+    &[4, 6, 7, 31, 33],
+    // 32: Pba2
+    &[3, 7, 32, 33, 34],
+    // 33: Pna2_1
+    &[4, 7, 33],
+    // 34: Pnn2
+    &[3, 7, 34, 43],
+    // 35: Cmm2
+    &[3, 8, 25, 28, 32, 35, 36, 37, 44, 45, 46],
+    // 36: Cmc2_1
+    &[4, 8, 9, 26, 29, 31, 33, 36],
+    // 37: Ccc2
+    &[3, 9, 27, 30, 34, 37],
+    // 38: Amm2
+    &[5, 6, 8, 25, 26, 30, 31, 38, 40, 44, 46],
+    // 39: Aem2
+    &[5, 7, 8, 26, 27, 28, 29, 39, 41, 45, 46],
+    // 40: Ama2
+    &[5, 6, 9, 28, 31, 33, 34, 40],
+    // 41: Aea2
+    &[5, 7, 9, 29, 30, 32, 33, 41],
+    // 42: Fmm2
+    &[5, 8, 35, 36, 37, 38, 39, 40, 41, 42],
+    // 43: Fdd2
+    &[5, 9, 43],
+    // 44: Imm2
+    &[5, 8, 25, 31, 34, 44],
+    // 45: Iba2
+    &[5, 9, 27, 29, 32, 45],
+    // 46: Ima2
+    &[5, 8, 9, 26, 28, 30, 33, 46],
+    // 47: Pmmm
+    &[10, 16, 25, 47, 49, 51, 65, 67, 69],
+    // 48: Pnnn
+    &[13, 16, 34, 48, 70],
+    // 49: Pccm
+    &[10, 13, 16, 27, 28, 49, 50, 53, 54, 66, 68],
+    // 50: Pban
+    &[13, 16, 30, 32, 48, 50, 52],
+    // 51: Pmma
+    &[10, 11, 13, 17, 25, 26, 28, 51, 53, 54, 55, 57, 59, 63, 64],
+    // 52: Pnna
+    &[13, 14, 17, 30, 33, 34, 52],
+    // 53: Pmna
+    &[10, 13, 14, 17, 28, 30, 31, 52, 53, 58, 60],
+    // 54: Pcca
+    &[13, 14, 17, 27, 29, 32, 52, 54, 56, 60],
+    // 55: Pbam
+    &[10, 14, 18, 26, 32, 55, 58, 62],
+    // 56: Pccn
+    &[13, 14, 18, 27, 33, 56],
+    // 57: Pbcm
+    &[11, 13, 14, 18, 26, 28, 29, 57, 60, 61, 62],
+    // 58: Pnnm
+    &[10, 14, 18, 31, 34, 58],
+    // 59: Pmmn
+    &[11, 13, 18, 25, 31, 56, 59, 62],
+    // 60: Pbcn
+    &[13, 14, 18, 29, 30, 33, 60],
+    // 61: Pbca
+    &[14, 19, 29, 61],
+    // 62: Pnma
+    &[11, 14, 19, 26, 31, 33, 62],
+    // 63: Cmcm
+    &[11, 12, 15, 20, 36, 38, 40, 51, 52, 57, 58, 59, 60, 62, 63],
+    // 64: Cmce
+    &[
+        12, 14, 15, 20, 36, 39, 41, 53, 54, 55, 56, 57, 60, 61, 62, 64,
+    ],
+    // 65: Cmmm
+    &[
+        10, 12, 21, 35, 38, 47, 50, 51, 53, 55, 59, 63, 65, 66, 71, 72, 74,
+    ],
+    // 66: Cccm
+    &[10, 15, 21, 37, 40, 48, 49, 52, 53, 56, 58, 66],
+    // 67: Cmme
+    &[12, 13, 21, 35, 39, 49, 51, 54, 57, 64, 67, 68, 72, 73, 74],
+    // 68: Ccce
+    &[13, 15, 21, 37, 41, 50, 52, 54, 60, 68],
+    // 69: Fmmm
+    &[12, 22, 42, 63, 64, 65, 66, 67, 68, 69],
+    // 70: Fddd
+    &[15, 22, 43, 70],
+    // 71: Immm
+    &[12, 23, 44, 47, 48, 58, 59, 71],
+    // 72: Ibam
+    &[12, 15, 23, 45, 46, 49, 50, 55, 56, 57, 60, 72],
+    // 73: Ibca
+    &[15, 24, 45, 54, 61, 73],
+    // 74: Imma
+    &[12, 15, 24, 44, 46, 51, 52, 53, 62, 74],
+    // 75: P4
+    &[3, 75, 77, 79],
+    // 76: P4_1
+    &[4, 76, 78],
+    // 77: P4_2
+    &[3, 76, 77, 78, 80],
+    // 78: P4_3
+    &[4, 76, 78],
+    // 79: I4
+    &[5, 75, 77, 79],
+    // 80: I4_1
+    &[5, 76, 78, 80],
+    // 81: P-4
+    &[3, 81, 82],
+    // 82: I-4
+    &[5, 81, 82],
+    // 83: P4/m
+    &[10, 75, 81, 83, 84, 85, 87],
+    // 84: P4_2/m
+    &[10, 77, 81, 84, 86],
+    // 85: P4/n
+    &[13, 75, 81, 85, 86],
+    // 86: P4_2/n
+    &[13, 77, 81, 86, 88],
+    // 87: I4/m
+    &[12, 79, 82, 83, 84, 85, 86, 87],
+    // 88: I4_1/a
+    &[15, 80, 82, 88],
+    // 89: P422
+    &[16, 21, 75, 89, 90, 93, 97],
+    // 90: P42_12
+    &[18, 21, 75, 90, 94],
+    // 91: P4_122
+    &[17, 20, 76, 91, 92, 95],
+    // 92: P4_12_12
+    &[19, 20, 76, 92, 96],
+    // 93: P4_222
+    &[16, 21, 77, 91, 93, 94, 95, 98],
+    // 94: P4_22_12
+    &[18, 21, 77, 92, 94, 96],
+    // 95: P4_322
+    &[17, 20, 78, 91, 95, 96],
+    // 96: P4_32_12
+    &[19, 20, 78, 92, 96],
+    // 97: I422
+    &[22, 23, 79, 89, 90, 93, 94, 97],
+    // 98: I4_122
+    &[22, 24, 80, 91, 92, 95, 96, 98],
+    // 99: P4mm
+    &[25, 35, 75, 99, 100, 101, 103, 105, 107, 108],
+    // 100: P4bm
+    &[32, 35, 75, 100, 102, 104, 106],
+    // 101: P4_2cm
+    &[27, 35, 77, 101, 105, 106],
+    // 102: P4_2nm
+    &[34, 35, 77, 102, 109, 110],
+    // 103: P4cc
+    &[27, 37, 75, 103, 104],
+    // 104: P4nc
+    &[34, 37, 75, 104],
+    // 105: P4_2mc
+    &[25, 37, 77, 101, 102, 105],
+    // 106: P4_2bc
+    &[32, 37, 77, 106],
+    // 107: I4mm
+    &[42, 44, 79, 99, 102, 104, 105, 107],
+    // 108: I4cm
+    &[42, 45, 79, 100, 101, 103, 106, 108],
+    // 109: I4_1md
+    &[43, 44, 80, 109],
+    // 110: I4_1cd
+    &[43, 45, 80, 110],
+    // 111: P-42m
+    &[16, 35, 81, 111, 112, 115, 117, 119, 120],
+    // 112: P-42c
+    &[16, 37, 81, 112, 116, 118],
+    // 113: P-42_1m
+    &[18, 35, 81, 113, 114],
+    // 114: P-42_1c
+    &[18, 37, 81, 114],
+    // 115: P-4m2
+    &[21, 25, 81, 111, 113, 115, 116, 121],
+    // 116: P-4c2
+    &[21, 27, 81, 112, 114, 116],
+    // 117: P-4b2
+    &[21, 32, 81, 117, 118],
+    // 118: P-4n2
+    &[21, 34, 81, 118, 122],
+    // 119: I-4m2
+    &[22, 44, 82, 115, 118, 119],
+    // 120: I-4c2
+    &[22, 45, 82, 116, 117, 120],
+    // 121: I-42m
+    &[23, 42, 82, 111, 112, 113, 114, 121],
+    // 122: I-42d
+    &[24, 43, 82, 122],
+    // 123: P4/mmm
+    &[
+        47, 65, 83, 89, 99, 111, 115, 123, 124, 125, 127, 129, 131, 132, 139, 140,
+    ],
+    // 124: P4/mcc
+    &[49, 66, 83, 89, 103, 112, 116, 124, 126, 128, 130],
+    // 125: P4/nbm
+    &[50, 67, 85, 89, 100, 111, 117, 125, 126, 133, 134],
+    // 126: P4/nnc
+    &[48, 68, 85, 89, 104, 112, 118, 126],
+    // 127: P4/mbm
+    &[55, 65, 83, 90, 100, 113, 117, 127, 128, 135, 136],
+    // 128: P4/mnc
+    &[58, 66, 83, 90, 104, 114, 118, 128],
+    // 129: P4/nmm
+    &[59, 67, 85, 90, 99, 113, 115, 129, 130, 137, 138],
+    // 130: P4/ncc
+    &[56, 68, 85, 90, 103, 114, 116, 130],
+    // 131: P4_2/mmc
+    &[47, 66, 84, 93, 105, 112, 115, 131, 132, 134, 136, 138],
+    // 132: P4_2/mcm
+    &[49, 65, 84, 93, 101, 111, 116, 131, 132, 133, 135, 137],
+    // 133: P4_2/nbc
+    &[50, 68, 86, 93, 106, 112, 117, 133],
+    // 134: P4_2/nnm
+    &[48, 67, 86, 93, 102, 111, 118, 134, 141, 142],
+    // 135: P4_2/mbc
+    &[55, 66, 84, 94, 106, 114, 117, 135],
+    // 136: P4_2/mnm
+    &[58, 65, 84, 94, 102, 113, 118, 136],
+    // 137: P4_2/nmc
+    &[59, 68, 86, 94, 105, 114, 115, 137],
+    // 138: P4_2/ncm
+    &[56, 67, 86, 94, 101, 113, 116, 138],
+    // 139: I4/mmm
+    &[
+        69, 71, 87, 97, 107, 119, 121, 123, 126, 128, 129, 131, 134, 136, 137, 139,
+    ],
+    // 140: I4/mcm
+    &[
+        69, 72, 87, 97, 108, 120, 121, 124, 125, 127, 130, 132, 133, 135, 138, 140,
+    ],
+    // 141: I4_1/amd
+    &[70, 74, 88, 98, 109, 119, 122, 141],
+    // 142: I4_1/acd
+    &[70, 73, 88, 98, 110, 120, 122, 142],
+    // 143: P3
+    &[1, 143, 144, 145, 146],
+    // 144: P3_1
+    &[1, 144, 145],
+    // 145: P3_2
+    &[1, 144, 145],
+    // 146: R3
+    &[1, 143, 144, 145, 146],
+    // 147: P-3
+    &[2, 143, 147, 148],
+    // 148: R-3
+    &[2, 146, 147, 148],
+    // 149: P312
+    &[5, 143, 149, 150, 151, 153, 155],
+    // 150: P321
+    &[5, 143, 149, 150, 152, 154],
+    // 151: P3_112
+    &[5, 144, 151, 152, 153],
+    // 152: P3_121
+    &[5, 144, 151, 152, 154],
+    // 153: P3_212
+    &[5, 145, 151, 153, 154],
+    // 154: P3_221
+    &[5, 145, 152, 153, 154],
+    // 155: R32
+    &[5, 146, 150, 152, 154, 155],
+    // 156: P3m1
+    &[8, 143, 156, 157, 158],
+    // 157: P31m
+    &[8, 143, 156, 157, 159, 160],
+    // 158: P3c1
+    &[9, 143, 158, 159],
+    // 159: P31c
+    &[9, 143, 158, 159, 161],
+    // 160: R3m
+    &[8, 146, 156, 160, 161],
+    // 161: R3c
+    &[9, 146, 158, 161],
+    // 162: P-31m
+    &[12, 147, 149, 157, 162, 163, 164, 166],
+    // 163: P-31c
+    &[15, 147, 149, 159, 163, 165, 167],
+    // 164: P-3m1
+    &[12, 147, 150, 156, 162, 164, 165],
+    // 165: P-3c1
+    &[15, 147, 150, 158, 163, 165],
+    // 166: R-3m
+    &[12, 148, 155, 160, 164, 166, 167],
+    // 167: R-3c
+    &[15, 148, 155, 161, 165, 167],
+    // 168: P6
+    &[3, 143, 168, 171, 172, 173],
+    // 169: P6_1
+    &[4, 144, 169, 170],
+    // 170: P6_5
+    &[4, 145, 169, 170],
+    // 171: P6_2
+    &[3, 145, 169, 171, 172],
+    // 172: P6_4
+    &[3, 144, 170, 171, 172],
+    // 173: P6_3
+    &[4, 143, 169, 170, 173],
+    // 174: P-6
+    &[6, 143, 174],
+    // 175: P6/m
+    &[10, 147, 168, 174, 175, 176],
+    // 176: P6_3/m
+    &[11, 147, 173, 174, 176],
+    // 177: P622
+    &[21, 149, 150, 168, 177, 180, 181, 182],
+    // 178: P6_122
+    &[20, 151, 152, 169, 178, 179],
+    // 179: P6_522
+    &[20, 153, 154, 170, 178, 179],
+    // 180: P6_222
+    &[21, 153, 154, 171, 178, 180, 181],
+    // 181: P6_422
+    &[21, 151, 152, 172, 179, 180, 181],
+    // 182: P6_322
+    &[20, 149, 150, 173, 178, 179, 182],
+    // 183: P6mm
+    &[35, 156, 157, 168, 183, 184, 185, 186],
+    // 184: P6cc
+    &[37, 158, 159, 168, 184],
+    // 185: P6_3cm
+    &[36, 157, 158, 173, 185, 186],
+    // 186: P6_3mc
+    &[36, 156, 159, 173, 185, 186],
+    // 187: P-6m2
+    &[38, 149, 156, 174, 187, 188, 189],
+    // 188: P-6c2
+    &[40, 149, 158, 174, 188, 190],
+    // 189: P-62m
+    &[38, 150, 157, 174, 187, 189, 190],
+    // 190: P-62c
+    &[40, 150, 159, 174, 188, 190],
+    // 191: P6/mmm
+    &[65, 162, 164, 175, 177, 183, 187, 189, 191, 192, 193, 194],
+    // 192: P6/mcc
+    &[66, 163, 165, 175, 177, 184, 188, 190, 192],
+    // 193: P6_3/mcm
+    &[63, 162, 165, 176, 182, 185, 188, 189, 193, 194],
+    // 194: P6_3/mmc
+    &[63, 163, 164, 176, 182, 186, 187, 190, 193, 194],
+    // 195: P23
+    &[16, 146, 195, 196, 197, 199],
+    // 196: F23
+    &[22, 146, 195, 196, 198],
+    // 197: I23
+    &[23, 146, 195, 197],
+    // 198: P2_13
+    &[19, 146, 198],
+    // 199: I2_13
+    &[24, 146, 198, 199],
+    // 200: Pm-3
+    &[47, 148, 195, 200, 202, 204, 206],
+    // 201: Pn-3
+    &[48, 148, 195, 201, 203],
+    // 202: Fm-3
+    &[69, 148, 196, 200, 201, 202, 205],
+    // 203: Fd-3
+    &[70, 148, 196, 203],
+    // 204: Im-3
+    &[71, 148, 197, 200, 201, 204],
+    // 205: Pa-3
+    &[61, 148, 198, 205],
+    // 206: Ia-3
+    &[73, 148, 199, 205, 206],
+    // 207: P432
+    &[89, 155, 195, 207, 209, 211],
+    // 208: P4_232
+    &[93, 155, 195, 208, 210, 214],
+    // 209: F432
+    &[97, 155, 196, 207, 208, 209],
+    // 210: F4_132
+    &[98, 155, 196, 210, 212, 213],
+    // 211: I432
+    &[97, 155, 197, 207, 208, 211],
+    // 212: P4_332
+    &[96, 155, 198, 212, 213],
+    // 213: P4_132
+    &[92, 155, 198, 212, 213],
+    // 214: I4_132
+    &[98, 155, 199, 212, 213, 214],
+    // 215: P-43m
+    &[111, 160, 195, 215, 216, 217, 219],
+    // 216: F-43m
+    &[119, 160, 196, 215, 216],
+    // 217: I-43m
+    &[121, 160, 197, 215, 217, 218],
+    // 218: P-43n
+    &[112, 161, 195, 218, 220],
+    // 219: F-43c
+    &[120, 161, 196, 218, 219],
+    // 220: I-43d
+    &[122, 161, 199, 220],
+    // 221: Pm-3m
+    &[123, 166, 200, 207, 215, 221, 225, 226, 229],
+    // 222: Pn-3n
+    &[126, 167, 201, 207, 218, 222],
+    // 223: Pm-3n
+    &[131, 167, 200, 208, 218, 223, 230],
+    // 224: Pn-3m
+    &[134, 166, 201, 208, 215, 224, 227, 228],
+    // 225: Fm-3m
+    &[139, 166, 202, 209, 216, 221, 224, 225],
+    // 226: Fm-3c
+    &[140, 167, 202, 209, 219, 222, 223, 226],
+    // 227: Fd-3m
+    &[141, 166, 203, 210, 216, 227],
+    // 228: Fd-3c
+    &[142, 167, 203, 210, 219, 228],
+    // 229: Im-3m
+    &[139, 166, 204, 211, 217, 221, 222, 223, 224, 229],
+    // 230: Ia-3d
+    &[142, 167, 206, 214, 220, 230],
+];
@@ -1,4 +1,7 @@
-use super::hall_symbol_database::{HallNumber, Number};
+use std::str::FromStr;
+
+use super::hall_symbol_database::{hall_symbol_entry, HallNumber, Number};
+use crate::base::{Linear, MoyoError};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// Preference for the setting of the space group.
@@ -7,7 +10,8 @@ pub enum Setting {
     HallNumber(HallNumber),
     /// The setting of the smallest Hall number
     Spglib,
-    /// Unique axis b, cell choice 1 for monoclinic, hexagonal axes for rhombohedral, and origin choice 2 for centrosymmetric space groups
+    /// Unique axis b, cell choice 1 for monoclinic, hexagonal axes for rhombohedral, and origin choice 2 for centrosymmetric space groups.
+    /// These are the ITA standard settings, which are also used by the Bilbao Crystallographic Server.
     Standard,
 }
 
@@ -57,3 +61,135 @@ impl Setting {
         }
     }
 }
+
+impl FromStr for Setting {
+    type Err = MoyoError;
+
+    /// Parse a case-insensitive setting name: "spglib" or "standard".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "spglib" => Ok(Setting::Spglib),
+            "standard" => Ok(Setting::Standard),
+            _ => Err(MoyoError::SettingParsingError),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Origin choice for space groups with two origins in ITA
+pub enum OriginChoice {
+    /// Origin choice 1
+    One,
+    /// Origin choice 2 (inversion center at the origin)
+    Two,
+}
+
+impl OriginChoice {
+    /// Return the Hall number with the same space-group type and axes as `hall_number` but with this origin choice.
+    /// If the space group has only one origin choice, `hall_number` itself is returned.
+    pub fn hall_number(&self, hall_number: HallNumber) -> Option<HallNumber> {
+        let entry = hall_symbol_entry(hall_number)?;
+        let axes = match entry.setting.strip_prefix(['1', '2']) {
+            Some(axes) => axes,
+            None => return Some(hall_number),
+        };
+        let label = match self {
+            OriginChoice::One => '1',
+            OriginChoice::Two => '2',
+        };
+        let setting = format!("{}{}", label, axes);
+
+        (1..=530)
+            .filter_map(hall_symbol_entry)
+            .find(|other| other.number == entry.number && other.setting == setting)
+            .map(|other| other.hall_number)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Setting of rhombohedral lattice points in hexagonal axes
+pub enum RhombohedralSetting {
+    /// Obverse setting with lattice points (2/3, 1/3, 1/3) and (1/3, 2/3, 2/3), which is the ITA standard
+    Obverse,
+    /// Reverse setting with lattice points (1/3, 2/3, 1/3) and (2/3, 1/3, 2/3)
+    Reverse,
+}
+
+impl RhombohedralSetting {
+    /// Transformation matrix from hexagonal axes in the obverse setting to those in this setting.
+    /// The reverse setting is obtained by rotating basis vectors by 180 degrees around the c axis.
+    pub fn linear(&self) -> Linear {
+        match self {
+            RhombohedralSetting::Obverse => Linear::identity(),
+            RhombohedralSetting::Reverse => Linear::new(
+                -1, 0, 0, //
+                0, -1, 0, //
+                0, 0, 1, //
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_setting() {
+        for number in 1..=230 {
+            let hall_number = Setting::Standard.hall_number(number).unwrap();
+            let entry = hall_symbol_entry(hall_number).unwrap();
+            assert_eq!(entry.number, number);
+
+            let settings = (1..=530)
+                .filter_map(hall_symbol_entry)
+                .filter(|other| other.number == number)
+                .map(|other| other.setting)
+                .collect::<Vec<_>>();
+            if (3..=15).contains(&number) {
+                // Unique axis b, cell choice 1
+                assert!(entry.setting == "b" || entry.setting == "b1");
+            } else if settings.contains(&"H") {
+                assert_eq!(entry.setting, "H");
+            } else if settings.iter().any(|setting| setting.starts_with('2')) {
+                assert!(entry.setting.starts_with('2'));
+            }
+        }
+
+        // Standard settings tabulated by the Bilbao Crystallographic Server
+        for (number, hall_symbol) in [
+            (5, "C 2y"),
+            (7, "P -2yc"),
+            (9, "C -2yc"),
+            (14, "-P 2ybc"),
+            (15, "-C 2yc"),
+            (48, "-P 2ab 2bc"),
+            (68, "-C 2a 2ac"),
+            (167, "-R 3 2=c"),
+        ] {
+            let hall_number = Setting::Standard.hall_number(number).unwrap();
+            let entry = hall_symbol_entry(hall_number).unwrap();
+            assert_eq!(entry.hall_symbol, hall_symbol);
+        }
+    }
+
+    #[test]
+    fn test_parse_setting() {
+        assert_eq!("Standard".parse::<Setting>(), Ok(Setting::Standard));
+        assert_eq!("spglib".parse::<Setting>(), Ok(Setting::Spglib));
+        assert_eq!(
+            "bcs".parse::<Setting>(),
+            Err(MoyoError::SettingParsingError)
+        );
+    }
+
+    #[test]
+    fn test_origin_choice() {
+        // Pnnn
+        assert_eq!(OriginChoice::One.hall_number(228), Some(228));
+        assert_eq!(OriginChoice::Two.hall_number(228), Some(229));
+        assert_eq!(OriginChoice::One.hall_number(229), Some(228));
+        // P2_1/c (No. 14) has only one origin choice
+        assert_eq!(OriginChoice::Two.hall_number(81), Some(81));
+    }
+}
@@ -1,6 +1,10 @@
+use itertools::iproduct;
 use nalgebra::{Matrix3, Vector3};
 
+use super::hall_symbol::HallSymbol;
 use super::hall_symbol_database::HallNumber;
+use crate::base::{Lattice, Position};
+use crate::math::SNF;
 
 pub struct WyckoffPositionSpace {
     pub linear: Matrix3<i32>,
@@ -108,6 +112,91 @@ impl WyckoffPosition {
             coordinates,
         }
     }
+
+    /// Return variables `y` of the coordinates such that `position` is on this Wyckoff position up to lattice translations within `symprec`.
+    /// Only the representative coordinates are compared, and other sites in the orbit are not.
+    pub(crate) fn variables(
+        &self,
+        position: &Position,
+        lattice: &Lattice,
+        symprec: f64,
+    ) -> Option<Vector3<f64>> {
+        // Find variable `y` and integers offset `offset` such that
+        //    | lattice * (space.linear * y + space.origin - position - offset) | < symprec.
+        // Let SNF decomposition of space.linear be
+        //    D = L * space.linear * R.
+        // lattice * (space.linear * y + space.origin - position - offset)
+        //    = lattice * (L^-1 * D * R^-1 * y + space.origin - position - offset)
+        //    = lattice * L^-1 * (D * R^-1 * y + L * (space.origin - position - offset))
+
+        //    = lattice * (D * R^-1 * y - L * (offset + position - space.origin)
+        let space = WyckoffPositionSpace::new(self.coordinates);
+        let snf = SNF::new(&space.linear);
+        for offset in iproduct!(-1..=1, -1..=1, -1..=1) {
+            let offset = Vector3::new(offset.0 as f64, offset.1 as f64, offset.2 as f64);
+            let b = snf.l.map(|e| e as f64) * (offset + position - space.origin);
+            let mut rinvy = Vector3::zeros();
+            for i in 0..3 {
+                if snf.d[(i, i)] != 0 {
+                    rinvy[i] = b[i] / snf.d[(i, i)] as f64;
+                }
+            }
+
+            let y = snf.r.map(|e| e as f64) * rinvy;
+            let diff = space.linear.map(|e| e as f64) * y + space.origin - position - offset;
+            if lattice.cartesian_coords(&diff).norm() < symprec {
+                return Some(y);
+            }
+        }
+        None
+    }
+}
+
+/// Return the Wyckoff position of a single `position` in the conventional cell of the space group with `hall_number`.
+/// `lattice` is the conventional lattice used to measure distances with `symprec`.
+/// All the symmetry-equivalent positions are compared with the Wyckoff positions, and the one with the smallest multiplicity is returned.
+/// Return `None` if `hall_number` is invalid.
+///
+/// ```
+/// use nalgebra::{matrix, vector};
+/// use moyo::base::Lattice;
+/// use moyo::data::wyckoff_of;
+///
+/// // Fm-3m
+/// let lattice = Lattice::new(matrix![4.0, 0.0, 0.0; 0.0, 4.0, 0.0; 0.0, 0.0, 4.0]);
+/// let wyckoff = wyckoff_of(523, &vector![0.5, 0.5, 0.5], &lattice, 1e-4).unwrap();
+/// assert_eq!(wyckoff.multiplicity, 4);
+/// assert_eq!(wyckoff.letter, 'b');
+/// ```
+pub fn wyckoff_of(
+    hall_number: HallNumber,
+    position: &Position,
+    lattice: &Lattice,
+    symprec: f64,
+) -> Option<WyckoffPosition> {
+    let hall_symbol = HallSymbol::from_hall_number(hall_number)?;
+    let coset = hall_symbol.traverse();
+    // Wrap images into [0, 1) because `WyckoffPosition::variables` only searches adjacent lattice translations
+    let images = iproduct!(hall_symbol.centering.lattice_points(), coset.iter())
+        .map(|(centering, operation)| {
+            (operation.rotation.map(|e| e as f64) * position + operation.translation + centering)
+                .map(|e| e.rem_euclid(1.0))
+        })
+        .collect::<Vec<_>>();
+
+    let mut wyckoffs = WYCKOFF_DATABASE
+        .iter()
+        .filter(|wp| wp.hall_number == hall_number)
+        .collect::<Vec<_>>();
+    wyckoffs.sort_by_key(|wp| wp.multiplicity);
+    wyckoffs
+        .into_iter()
+        .find(|wp| {
+            images
+                .iter()
+                .any(|image| wp.variables(image, lattice, symprec).is_some())
+        })
+        .cloned()
 }
 
 pub fn iter_wyckoff_positions(
@@ -3593,7 +3682,8 @@ const WYCKOFF_DATABASE: [WyckoffPosition; 3467] = [
 mod tests {
     use rstest::rstest;
 
-    use super::WyckoffPositionSpace;
+    use super::{wyckoff_of, WyckoffPositionSpace};
+    use crate::base::Lattice;
     use nalgebra::{matrix, vector};
 
     #[rstest]
@@ -3610,4 +3700,32 @@ mod tests {
         assert_eq!(space.linear, linear);
         assert_relative_eq!(space.origin, origin);
     }
+
+    #[test]
+    fn test_wyckoff_of() {
+        // Fm-3m
+        let hall_number = 523;
+        let lattice = Lattice::new(matrix![
+            4.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 4.0;
+        ]);
+        let wyckoff = wyckoff_of(hall_number, &vector![0.0, 0.0, 0.0], &lattice, 1e-4).unwrap();
+        assert_eq!(wyckoff.multiplicity, 4);
+        assert_eq!(wyckoff.letter, 'a');
+
+        // Equivalent to (0, 0, 0) by the centering translation
+        let wyckoff = wyckoff_of(hall_number, &vector![0.5, 0.5, 0.0], &lattice, 1e-4).unwrap();
+        assert_eq!(wyckoff.letter, 'a');
+
+        // 8c at (1/4, 1/4, 1/4) and the general position
+        let wyckoff = wyckoff_of(hall_number, &vector![0.75, 0.75, 0.75], &lattice, 1e-4).unwrap();
+        assert_eq!(wyckoff.letter, 'c');
+        let wyckoff = wyckoff_of(hall_number, &vector![0.11, 0.23, 0.37], &lattice, 1e-4).unwrap();
+        assert_eq!(wyckoff.multiplicity, 192);
+
+        // Equivalent to 4b at (1/2, 1/2, 1/2) by lattice translations beyond adjacent cells
+        let wyckoff = wyckoff_of(hall_number, &vector![5.5, -3.5, 7.5], &lattice, 1e-4).unwrap();
+        assert_eq!(wyckoff.letter, 'b');
+    }
 }
@@ -3,9 +3,15 @@ mod normalizer;
 mod point_group;
 mod rotation_type;
 mod space_group;
+#[cfg(test)]
+mod subgroup;
+
+pub use point_group::PointGroup;
 
 pub(super) use magnetic_space_group::{
     family_space_group_from_magnetic_space_group,
     primitive_maximal_space_subgroup_from_magnetic_space_group, MagneticSpaceGroup,
 };
+pub(super) use point_group::geometric_crystal_class_from_rotations;
+pub(super) use rotation_type::{identify_rotation_type, RotationType};
 pub(super) use space_group::SpaceGroup;
@@ -27,8 +27,7 @@ impl MagneticSpaceGroup {
     /// epsilon: tolerance for comparing translation parts
     pub fn new(prim_mag_operations: &MagneticOperations, epsilon: f64) -> Result<Self, MoyoError> {
         let (ref_spg, construct_type) =
-            identify_reference_space_group(prim_mag_operations, epsilon)
-                .ok_or(MoyoError::ConstructTypeIdentificationError)?;
+            identify_reference_space_group(prim_mag_operations, epsilon)?;
         debug!("Construct type: {:?}", construct_type);
         let setting = Setting::Standard;
         // std_ref_spg.transformation: primitive input -> primitive BNS setting
@@ -198,17 +197,23 @@ impl MagneticSpaceGroup {
 fn identify_reference_space_group(
     prim_mag_operations: &MagneticOperations,
     epsilon: f64,
-) -> Option<(Operations, ConstructType)> {
+) -> Result<(Operations, ConstructType), MoyoError> {
     let (prim_xsg, _) =
         primitive_maximal_space_subgroup_from_magnetic_space_group(prim_mag_operations);
     let (fsg, is_type2, _) =
         family_space_group_from_magnetic_space_group(prim_mag_operations, epsilon);
+    let error = MoyoError::ConstructTypeIdentificationError {
+        order_msg: prim_mag_operations.len(),
+        order_xsg: prim_xsg.len(),
+        order_fsg: fsg.len(),
+    };
 
-    if (prim_mag_operations.len() % prim_xsg.len() != 0)
+    if prim_xsg.is_empty()
+        || (prim_mag_operations.len() % prim_xsg.len() != 0)
         || (prim_mag_operations.len() % fsg.len() != 0)
     {
         debug!("Input magnetic operations are incomplete.");
-        return None;
+        return Err(error);
     }
 
     let construct_type = match (prim_mag_operations.len() / prim_xsg.len(), is_type2) {
@@ -233,7 +238,7 @@ fn identify_reference_space_group(
                 prim_mag_operations.len() / prim_xsg.len(),
                 fsg.len() / prim_xsg.len(),
             );
-            return None;
+            return Err(error);
         }
     };
 
@@ -244,7 +249,7 @@ fn identify_reference_space_group(
         // For type I, II, III, `fsg` is in primitive
         fsg
     };
-    Some((ref_spg, construct_type))
+    Ok((ref_spg, construct_type))
 }
 
 /// XSG: take only operations without time-reversal
@@ -396,6 +401,27 @@ mod tests {
         assert_eq!(construct_type_actual, construct_type);
     }
 
+    #[test]
+    fn test_incomplete_magnetic_operations() {
+        // Drop one operation with time reversal from a type-III group
+        let mut prim_mag_operations = get_prim_mag_operations(1596);
+        let index = prim_mag_operations
+            .iter()
+            .position(|mops| mops.time_reversal)
+            .unwrap();
+        prim_mag_operations.remove(index);
+
+        let err = MagneticSpaceGroup::new(&prim_mag_operations, 1e-8).unwrap_err();
+        assert_eq!(
+            err,
+            MoyoError::ConstructTypeIdentificationError {
+                order_msg: 47,
+                order_xsg: 24,
+                order_fsg: 47,
+            }
+        );
+    }
+
     // Check generators of reference space group by two methods:
     // 1. From the magnetic Hall symbol
     // 2. From the Hall symbol with the corresponding Hall number
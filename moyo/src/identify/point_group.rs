@@ -7,8 +7,8 @@ use nalgebra::Matrix3;
 use super::rotation_type::{identify_rotation_type, RotationType};
 use crate::base::{MoyoError, Rotations, UnimodularLinear};
 use crate::data::{
-    iter_arithmetic_crystal_entry, ArithmeticNumber, Centering, CrystalSystem,
-    GeometricCrystalClass, PointGroupRepresentative,
+    arithmetic_crystal_class_entry, iter_arithmetic_crystal_entry, ArithmeticNumber, Centering,
+    CrystalSystem, GeometricCrystalClass, PointGroupRepresentative,
 };
 use crate::math::sylvester3;
 
@@ -50,6 +50,28 @@ impl PointGroup {
             _ => match_with_point_group(prim_rotations, &rotation_types, geometric_crystal_class),
         }
     }
+
+    /// Hermann-Mauguin symbol of the geometric crystal class
+    pub fn hermann_mauguin(&self) -> String {
+        self.geometric_crystal_class().hermann_mauguin().to_string()
+    }
+
+    /// Number of rotations in the point group
+    pub fn order(&self) -> usize {
+        self.geometric_crystal_class().order()
+    }
+
+    fn geometric_crystal_class(&self) -> GeometricCrystalClass {
+        arithmetic_crystal_class_entry(self.arithmetic_number).geometric_crystal_class
+    }
+}
+
+/// Identify the geometric crystal class of the given rotations in any basis
+pub fn geometric_crystal_class_from_rotations(
+    rotations: &Rotations,
+) -> Result<GeometricCrystalClass, MoyoError> {
+    let rotation_types = rotations.iter().map(identify_rotation_type).collect();
+    identify_geometric_crystal_class(&rotation_types)
 }
 
 /// Faster matching algorithm for cubic point groups
@@ -320,6 +342,20 @@ mod tests {
             for rotation in prim_rotations_actual {
                 assert!(prim_rotations_set.contains(&rotation));
             }
+
+            assert_eq!(point_group.order(), prim_rotations.len());
         }
     }
+
+    #[test]
+    fn test_point_group_symbol_and_order() {
+        // m-3m
+        let point_group_db =
+            PointGroupRepresentative::from_geometric_crystal_class(GeometricCrystalClass::Oh);
+        let prim_rotations = traverse(&point_group_db.primitive_generators());
+        let point_group = PointGroup::new(&prim_rotations).unwrap();
+
+        assert_eq!(point_group.hermann_mauguin(), "m-3m");
+        assert_eq!(point_group.order(), 48);
+    }
 }
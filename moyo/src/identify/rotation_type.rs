@@ -1,4 +1,7 @@
-use crate::base::Rotation;
+use nalgebra::Vector3;
+
+use crate::base::{Operation, Rotation};
+use crate::math::{gcd, Rational};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum RotationType {
@@ -32,3 +35,147 @@ pub fn identify_rotation_type(rotation: &Rotation) -> RotationType {
         _ => unreachable!("Unknown rotation type"),
     }
 }
+
+impl Operation {
+    /// Return the Seitz symbol of the operation, e.g. "{4+ 001 | 0 0 1/2}".
+    /// The axis is the rotation axis, or the normal of the mirror plane, normalized so that its first nonzero component is positive.
+    /// The sense of rotation is given with respect to the axis for operations of order larger than two.
+    pub fn to_seitz_string(&self) -> String {
+        let rotation_type = identify_rotation_type(&self.rotation);
+        let symbol = match rotation_type {
+            RotationType::Rotation1 => "1",
+            RotationType::Rotation2 => "2",
+            RotationType::Rotation3 => "3",
+            RotationType::Rotation4 => "4",
+            RotationType::Rotation6 => "6",
+            RotationType::RotoInversion1 => "-1",
+            RotationType::RotoInversion2 => "m",
+            RotationType::RotoInversion3 => "-3",
+            RotationType::RotoInversion4 => "-4",
+            RotationType::RotoInversion6 => "-6",
+        };
+
+        let translation = self
+            .translation
+            .iter()
+            .map(|&e| Rational::approximate(e, 24).to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        match rotation_axis(&self.rotation) {
+            Some(axis) => {
+                let sense = match rotation_type {
+                    RotationType::Rotation2 | RotationType::RotoInversion2 => "",
+                    _ => {
+                        if rotation_sense(&self.rotation, &axis) > 0 {
+                            "+"
+                        } else {
+                            "-"
+                        }
+                    }
+                };
+                let axis = axis
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .concat();
+                format!("{{{}{} {} | {}}}", symbol, sense, axis, translation)
+            }
+            None => format!("{{{} | {}}}", symbol, translation),
+        }
+    }
+}
+
+/// Return the proper part of `rotation`, i.e. `rotation` multiplied by its determinant
+fn proper_rotation(rotation: &Rotation) -> Rotation {
+    let det = rotation.map(|e| e as f64).determinant().round() as i32;
+    rotation * det
+}
+
+/// Return the primitive integer vector along the axis of the proper part of `rotation`.
+/// Return `None` for 1 and -1.
+fn rotation_axis(rotation: &Rotation) -> Option<Vector3<i32>> {
+    let proper = proper_rotation(rotation);
+    if proper == Rotation::identity() {
+        return None;
+    }
+
+    // Sum of powers of `proper` projects vectors onto the axis
+    let mut sum = Rotation::zeros();
+    let mut power = Rotation::identity();
+    loop {
+        sum += power;
+        power *= proper;
+        if power == Rotation::identity() {
+            break;
+        }
+    }
+
+    let column = sum
+        .column_iter()
+        .map(|column| column.into_owned())
+        .max_by_key(|column| column.abs().sum())
+        .unwrap();
+    let divisor = column.iter().fold(0, |acc, &e| gcd(acc, e));
+    let sign = column.iter().find(|&&e| e != 0).unwrap().signum();
+    Some(column / (divisor * sign))
+}
+
+/// Return the sign of the sense of rotation of the proper part of `rotation` around `axis`
+fn rotation_sense(rotation: &Rotation, axis: &Vector3<i32>) -> i32 {
+    let proper = proper_rotation(rotation);
+    (0..3)
+        .map(|i| {
+            let x = Vector3::<i32>::ith(i, 1);
+            let y = proper * x;
+            Rotation::from_columns(&[*axis, x, y])
+                .map(|e| e as f64)
+                .determinant()
+                .round() as i32
+        })
+        .find(|&det| det != 0)
+        .unwrap()
+        .signum()
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{matrix, vector};
+
+    use crate::base::{Operation, Translation};
+
+    #[test]
+    fn test_seitz_string() {
+        assert_eq!(Operation::identity().to_seitz_string(), "{1 | 0 0 0}");
+        // -4+ along [001]: y,-x,-z
+        let operation = Operation::new(
+            matrix![
+                0, 1, 0;
+                -1, 0, 0;
+                0, 0, -1;
+            ],
+            Translation::zeros(),
+        );
+        assert_eq!(operation.to_seitz_string(), "{-4+ 001 | 0 0 0}");
+        // 3+ along [111]: z,x,y
+        let operation = Operation::new(
+            matrix![
+                0, 0, 1;
+                1, 0, 0;
+                0, 1, 0;
+            ],
+            Translation::zeros(),
+        );
+        assert_eq!(operation.to_seitz_string(), "{3+ 111 | 0 0 0}");
+        // c-glide perpendicular to [1-10]: y,x,z+1/2
+        let operation = Operation::new(
+            matrix![
+                0, 1, 0;
+                1, 0, 0;
+                0, 0, 1;
+            ],
+            vector![0.0, 0.0, 0.5],
+        );
+        assert_eq!(operation.to_seitz_string(), "{m 1-10 | 0 0 1/2}");
+    }
+}
@@ -0,0 +1,336 @@
+//! Enumeration of maximal subgroups, which generates and cross-checks the precomputed table of [`crate::data::maximal_subgroup_numbers`]
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use itertools::{iproduct, Itertools};
+use nalgebra::{Matrix3, Vector3};
+
+use super::space_group::SpaceGroup;
+use crate::base::{MoyoError, Operation, Operations, Rotation, Translation, EPS};
+use crate::data::{HallNumber, HallSymbol, Number, Setting};
+
+/// Maximal index of non-isomorphic klassengleiche subgroups, which is 2, 3, or 4 for three-dimensional space groups
+const MAX_KLASSENGLEICHE_INDEX: i32 = 4;
+/// Translations of Hall symbols are multiples of 1/`TRANSLATION_DENOMINATOR`
+const TRANSLATION_DENOMINATOR: i32 = 12;
+
+/// Return space-group numbers of translationengleiche and klassengleiche maximal subgroups of the space group with `hall_number`.
+/// Klassengleiche subgroups are searched up to index [`MAX_KLASSENGLEICHE_INDEX`], which covers all non-isomorphic ones but only some isomorphic ones.
+fn maximal_subgroups(
+    hall_number: HallNumber,
+) -> Result<(BTreeSet<Number>, BTreeSet<Number>), MoyoError> {
+    let hall_symbol =
+        HallSymbol::from_hall_number(hall_number).ok_or(MoyoError::UnknownHallNumberError)?;
+    let prim_operations = hall_symbol.primitive_traverse();
+    let prim_generators = hall_symbol.primitive_generators();
+
+    let numbers = |subgroups: Vec<Operations>| {
+        subgroups
+            .into_iter()
+            .filter_map(|operations| {
+                SpaceGroup::new(&operations, Setting::Spglib, EPS)
+                    .ok()
+                    .map(|space_group| space_group.number)
+            })
+            .collect::<BTreeSet<_>>()
+    };
+    Ok((
+        numbers(maximal_translationengleiche_subgroups(&prim_operations)),
+        numbers(maximal_klassengleiche_subgroups(
+            &prim_operations,
+            &prim_generators,
+        )),
+    ))
+}
+
+/// Return maximal subgroups of `prim_operations` with the same translations.
+/// Since each coset of the translation subgroup has a distinct rotation, subgroups are enumerated as sets of rotations.
+fn maximal_translationengleiche_subgroups(prim_operations: &Operations) -> Vec<Operations> {
+    let order = prim_operations.len();
+    let indices = prim_operations
+        .iter()
+        .enumerate()
+        .map(|(i, operation)| (operation.rotation, i))
+        .collect::<HashMap<_, _>>();
+    let table = prim_operations
+        .iter()
+        .map(|lhs| {
+            prim_operations
+                .iter()
+                .map(|rhs| indices[&(lhs.rotation * rhs.rotation)])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Subgroups are represented as bitsets of indices of `prim_operations`
+    let closure = |mut elements: u64| loop {
+        let mut new_elements = elements;
+        for (i, j) in iproduct!(0..order, 0..order) {
+            if (elements >> i) & 1 == 1 && (elements >> j) & 1 == 1 {
+                new_elements |= 1 << table[i][j];
+            }
+        }
+        if new_elements == elements {
+            return elements;
+        }
+        elements = new_elements;
+    };
+    let whole = (1_u64 << order) - 1;
+
+    let identity = closure(1 << indices[&Rotation::identity()]);
+    if identity == whole {
+        // The point group is trivial and has no proper subgroup
+        return vec![];
+    }
+    let mut subgroups = BTreeSet::from([identity]);
+    let mut queue = VecDeque::from([identity]);
+    while let Some(subgroup) = queue.pop_front() {
+        for i in 0..order {
+            if (subgroup >> i) & 1 == 1 {
+                continue;
+            }
+            let new_subgroup = closure(subgroup | (1 << i));
+            if new_subgroup != whole && subgroups.insert(new_subgroup) {
+                queue.push_back(new_subgroup);
+            }
+        }
+    }
+
+    subgroups
+        .iter()
+        .filter(|&&subgroup| {
+            !subgroups
+                .iter()
+                .any(|&other| other != subgroup && other & subgroup == subgroup)
+        })
+        .map(|&subgroup| {
+            (0..order)
+                .filter(|&i| (subgroup >> i) & 1 == 1)
+                .map(|i| prim_operations[i].clone())
+                .collect()
+        })
+        .collect()
+}
+
+/// Return distinct maximal subgroups of `prim_operations` with the same rotations and sublattices of index up to [`MAX_KLASSENGLEICHE_INDEX`].
+/// Returned operations are in the basis of the sublattices.
+fn maximal_klassengleiche_subgroups(
+    prim_operations: &Operations,
+    prim_generators: &Operations,
+) -> Vec<Operations> {
+    let rotations = prim_operations
+        .iter()
+        .map(|operation| operation.rotation)
+        .collect::<Vec<_>>();
+    let sublattices = (2..=MAX_KLASSENGLEICHE_INDEX)
+        .map(|index| (index, invariant_sublattices(index, &rotations)))
+        .collect::<Vec<_>>();
+
+    let mut subgroups = vec![];
+    for (index, linears) in sublattices.iter() {
+        for (linear, linear_inv) in linears.iter() {
+            // The subgroup is not maximal if an invariant lattice lies strictly between
+            let has_intermediate = sublattices
+                .iter()
+                .filter(|(other_index, _)| other_index < index && index % other_index == 0)
+                .flat_map(|(_, others)| others.iter())
+                .any(|(_, other_inv)| is_integer(&(other_inv * linear)));
+            if has_intermediate {
+                continue;
+            }
+
+            // Coset representatives of the lattice modulo the sublattice
+            let mut shifts: Vec<Translation> = vec![];
+            for shift in iproduct!(0..*index, 0..*index, 0..*index) {
+                let shift = Vector3::new(shift.0 as f64, shift.1 as f64, shift.2 as f64);
+                if !shifts
+                    .iter()
+                    .any(|other| is_integer_vector(&(linear_inv * (shift - other))))
+                {
+                    shifts.push(shift);
+                }
+            }
+
+            // Different shifts of generators often give the same subgroup, which is kept only once.
+            // A subgroup is identified by translations for rotations in a fixed order, as multiples of 1 / (`TRANSLATION_DENOMINATOR` * index).
+            let sub_rotations = rotations
+                .iter()
+                .map(|rotation| {
+                    (linear_inv * rotation.map(|e| e as f64) * linear).map(|e| e.round() as i32)
+                })
+                .collect::<Vec<_>>();
+            let denominator = (TRANSLATION_DENOMINATOR * index) as f64;
+            let mut visited = HashSet::new();
+
+            // Shift translations of generators by the coset representatives
+            for choice in prim_generators
+                .iter()
+                .map(|_| shifts.iter())
+                .multi_cartesian_product()
+            {
+                let generators = prim_generators
+                    .iter()
+                    .zip(choice)
+                    .map(|(generator, shift)| {
+                        let rotation = (linear_inv * generator.rotation.map(|e| e as f64) * linear)
+                            .map(|e| e.round() as i32);
+                        let translation = linear_inv * (generator.translation + shift);
+                        Operation::new(rotation, translation)
+                    })
+                    .collect::<Vec<_>>();
+                let Some(operations) = generate_without_translations(&generators) else {
+                    continue;
+                };
+                if operations.len() != prim_operations.len() {
+                    continue;
+                }
+                let translations = operations
+                    .iter()
+                    .map(|operation| (operation.rotation, operation.translation))
+                    .collect::<HashMap<_, _>>();
+                let key = sub_rotations
+                    .iter()
+                    .map(|rotation| {
+                        translations[rotation].map(|e| {
+                            ((e * denominator).round() as i32).rem_euclid(denominator as i32)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                if visited.insert(key) {
+                    subgroups.push(operations);
+                }
+            }
+        }
+    }
+    subgroups
+}
+
+/// Return sublattices of index `index` invariant under `rotations` as transformation matrices to them and their inverses
+fn invariant_sublattices(index: i32, rotations: &[Rotation]) -> Vec<(Matrix3<f64>, Matrix3<f64>)> {
+    let mut sublattices = vec![];
+    // Hermite normal forms with determinant `index`
+    for (a, c) in iproduct!(1..=index, 1..=index) {
+        if index % (a * c) != 0 {
+            continue;
+        }
+        let f = index / (a * c);
+        for (b, d, e) in iproduct!(0..c, 0..f, 0..f) {
+            let linear = Matrix3::new(
+                a as f64, 0.0, 0.0, //
+                b as f64, c as f64, 0.0, //
+                d as f64, e as f64, f as f64,
+            );
+            // Lower triangular with positive diagonal elements
+            let linear_inv = linear
+                .try_inverse()
+                .expect("Hermite normal forms should be nonsingular");
+            if rotations
+                .iter()
+                .all(|rotation| is_integer(&(linear_inv * rotation.map(|e| e as f64) * linear)))
+            {
+                sublattices.push((linear, linear_inv));
+            }
+        }
+    }
+    sublattices
+}
+
+/// Generate operations from `generators` modulo lattice translations.
+/// Return `None` if the generated group contains a nontrivial pure translation.
+fn generate_without_translations(generators: &[Operation]) -> Option<Operations> {
+    let mut translations = HashMap::new();
+    let mut operations = vec![];
+    let mut queue = VecDeque::from([Operation::identity()]);
+    translations.insert(Rotation::identity(), Translation::zeros());
+    operations.push(Operation::identity());
+    while let Some(lhs) = queue.pop_front() {
+        for rhs in generators.iter() {
+            let product = lhs.clone() * rhs.clone();
+            let translation = product.translation.map(|e| e.rem_euclid(1.0));
+            match translations.get(&product.rotation) {
+                Some(other) => {
+                    if !is_integer_vector(&(translation - other)) {
+                        return None;
+                    }
+                }
+                None => {
+                    translations.insert(product.rotation, translation);
+                    let operation = Operation::new(product.rotation, translation);
+                    operations.push(operation.clone());
+                    queue.push_back(operation);
+                }
+            }
+        }
+    }
+    Some(operations)
+}
+
+fn is_integer(matrix: &Matrix3<f64>) -> bool {
+    matrix.iter().all(|e| (e - e.round()).abs() < EPS)
+}
+
+fn is_integer_vector(vector: &Vector3<f64>) -> bool {
+    vector.iter().all(|e| (e - e.round()).abs() < EPS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::maximal_subgroups;
+    use crate::data::{maximal_subgroup_numbers, Number, Setting};
+    use crate::ENANTIOMORPHIC_PAIRS;
+
+    #[test]
+    fn test_maximal_subgroups_of_p1() {
+        // P1 has only isomorphic maximal subgroups
+        let (translationengleiche, klassengleiche) = maximal_subgroups(1).unwrap();
+        assert!(translationengleiche.is_empty());
+        assert_eq!(klassengleiche, BTreeSet::from([1]));
+        // P-1 (hall_number=2)
+        let (translationengleiche, klassengleiche) = maximal_subgroups(2).unwrap();
+        assert_eq!(translationengleiche, BTreeSet::from([1]));
+        assert_eq!(klassengleiche, BTreeSet::from([2]));
+    }
+
+    #[test]
+    fn test_maximal_subgroups_of_fm3m() {
+        // Fm-3m (hall_number=523)
+        let (translationengleiche, klassengleiche) = maximal_subgroups(523).unwrap();
+        // I4/mmm, R-3m, Fm-3, F432, and F-43m
+        assert_eq!(
+            translationengleiche,
+            BTreeSet::from([139, 166, 202, 209, 216])
+        );
+        // Pm-3m and Pn-3m by removing the face-centering translations
+        assert_eq!(klassengleiche, BTreeSet::from([221, 224]));
+    }
+
+    #[test]
+    fn test_maximal_subgroup_database() {
+        for number in 1..=230 {
+            let hall_number = Setting::Spglib.hall_number(number).unwrap();
+            let (translationengleiche, klassengleiche) = maximal_subgroups(hall_number).unwrap();
+            let mut expected = &translationengleiche | &klassengleiche;
+            // Isomorphic subgroups of large indices
+            expected.insert(number);
+            for (number1, number2) in ENANTIOMORPHIC_PAIRS {
+                if number == number1 {
+                    expected.insert(number2);
+                } else if number == number2 {
+                    expected.insert(number1);
+                }
+            }
+            assert_eq!(
+                maximal_subgroup_numbers(number)
+                    .unwrap()
+                    .iter()
+                    .copied()
+                    .collect::<BTreeSet<Number>>(),
+                expected,
+                "number={}",
+                number
+            );
+        }
+    }
+}
@@ -65,25 +65,52 @@ extern crate approx;
 
 pub mod base;
 pub mod data;
+pub mod identify;
 pub mod math;
 pub mod search; // Public for benchmarking
 
-mod identify;
 mod symmetrize;
+#[cfg(feature = "serde_yaml")]
+mod yaml;
+
+pub use crate::base::periodicity_dimension;
+pub use crate::search::internal_translations;
 
 use crate::base::{
-    AngleTolerance, Cell, MagneticCell, MagneticMoment, MagneticOperations, MoyoError, Operations,
-    OriginShift, RotationMagneticMomentAction,
+    multiplication_table, project_rotations, snap_translations, sort_magnetic_operations,
+    AngleTolerance, AtomicSpecie, Cell, Lattice, MagSymprec, MagneticCell, MagneticMoment,
+    MagneticOperations, MoyoError, Operation, Operations, OriginShift, Permutation, Position,
+    Rotation, RotationMagneticMomentAction, Rotations, Symprec, Translation,
+    UnimodularTransformation, EPS,
+};
+use crate::data::{
+    arithmetic_crystal_class_entry, get_magnetic_space_group_type, hall_symbol_entry,
+    maximal_subgroup_numbers, ArithmeticNumber, Centering, ConstructType, GeometricCrystalClass,
+    HallNumber, HallSymbol, Number, OriginChoice, RhombohedralSetting, Setting, UNINumber,
+    WyckoffPosition, WyckoffPositionSpace,
+};
+use crate::identify::{
+    geometric_crystal_class_from_rotations, identify_rotation_type,
+    primitive_maximal_space_subgroup_from_magnetic_space_group, MagneticSpaceGroup, RotationType,
+    SpaceGroup,
 };
-use crate::data::{HallNumber, Number, Setting, UNINumber};
-use crate::identify::{MagneticSpaceGroup, SpaceGroup};
+use crate::math::Rational;
 use crate::search::{
     iterative_magnetic_symmetry_search, iterative_symmetry_search,
-    magnetic_operations_in_magnetic_cell, operations_in_cell,
+    magnetic_operations_in_magnetic_cell, operations_in_cell, retry_with_delaunay,
+    search_bravais_group, solve_correspondence, LatticeCache, PeriodicKdTree, PrimitiveCell,
+    PrimitiveSymmetrySearch,
 };
-use crate::symmetrize::{orbits_in_cell, StandardizedCell, StandardizedMagneticCell};
+use crate::symmetrize::{
+    assign_wyckoff_position, orbits_in_cell, StandardizedCell, StandardizedMagneticCell,
+};
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
 
-use nalgebra::Matrix3;
+use itertools::{iproduct, Itertools};
+use nalgebra::{Matrix3, Vector3};
+use union_find::{QuickFindUf, UnionByRank, UnionFind};
 
 #[derive(Debug)]
 /// A dataset containing symmetry information of the input crystal structure.
@@ -95,6 +122,10 @@ pub struct MoyoDataset {
     pub number: Number,
     /// Hall symbol number.
     pub hall_number: HallNumber,
+    /// Arithmetic crystal class number.
+    pub arithmetic_number: ArithmeticNumber,
+    /// Symbol of the arithmetic crystal class, e.g. "4/mmmP".
+    pub arithmetic_symbol: String,
     // ------------------------------------------------------------------------
     // Symmetry operations in the input cell
     // ------------------------------------------------------------------------
@@ -121,8 +152,13 @@ pub struct MoyoDataset {
     pub std_linear: Matrix3<f64>,
     /// Origin shift of transformation from the input cell to the standardized cell.
     pub std_origin_shift: OriginShift,
+    /// Determinant of `std_linear`, which is the ratio of the volume of the standardized cell to that of the input cell.
+    pub std_volume_ratio: f64,
     /// Rigid rotation
     pub std_rotation_matrix: Matrix3<f64>,
+    /// Ideal lattice of `std_cell` symmetrized by the detected point group, in the same orientation as `std_cell.lattice`.
+    /// `std_cell.lattice` itself is only rotated and keeps a distortion of the input lattice.
+    pub ideal_std_lattice: Lattice,
     // ------------------------------------------------------------------------
     // Primitive standardized cell
     // ------------------------------------------------------------------------
@@ -135,6 +171,9 @@ pub struct MoyoDataset {
     /// Mapping sites in the input cell to those in the primitive standardized cell.
     /// The `i`th atom in the input cell is mapped to the `mapping_to_std_prim[i]`th atom in the primitive standardized cell.
     pub mapping_std_prim: Vec<usize>,
+    /// Mapping sites in the standardized cell to those in the primitive standardized cell.
+    /// The `i`th atom in `std_cell` is mapped to the `std_to_prim_std[i]`th atom in `prim_std_cell`.
+    pub std_to_prim_std: Vec<usize>,
     // ------------------------------------------------------------------------
     // Final parameters
     // ------------------------------------------------------------------------
@@ -142,56 +181,1505 @@ pub struct MoyoDataset {
     pub symprec: f64,
     /// Actually used `angle_tolerance` in iterative symmetry search.
     pub angle_tolerance: AngleTolerance,
+    /// True if `symprec` or `angle_tolerance` differs from the given one.
+    pub tolerance_adjusted: bool,
+    /// Number of tolerance adjustments in iterative symmetry search.
+    pub iterations: usize,
+    transformation_chain: TransformationChain,
+    /// Multiplication table of `operations`, computed on demand by [`MoyoDataset::cayley_table`]
+    cayley_table: OnceLock<Vec<Vec<usize>>>,
+}
+
+/// Individual transformations composed into [`MoyoDataset::std_linear`] and [`MoyoDataset::std_origin_shift`].
+/// Each step `(P, p)` changes the basis by `basis' = basis * P` and fractional coordinates by `x = P * x' + p`.
+/// The steps are applied in the order of the fields.
+#[derive(Debug, Clone)]
+pub struct TransformationChain {
+    /// Lattice reduction of the input cell
+    pub reduction_linear: Matrix3<f64>,
+    /// From the reduced cell to a primitive cell, whose determinant is the inverse of the number of lattice points in the input cell
+    pub primitive_linear: Matrix3<f64>,
+    /// Lattice reduction of the primitive cell
+    pub prim_reduction_linear: Matrix3<f64>,
+    /// Linear part from the reduced primitive cell to the primitive standardized cell
+    pub standardization_linear: Matrix3<f64>,
+    /// Origin shift from the reduced primitive cell to the primitive standardized cell.
+    /// This is the only step with a nonzero origin shift.
+    pub standardization_origin_shift: OriginShift,
+    /// From the primitive standardized cell to the standardized cell
+    pub centering_linear: Matrix3<f64>,
+}
+
+impl TransformationChain {
+    /// Return the composed transformation from the input cell to the primitive standardized cell, which equals ([`MoyoDataset::prim_std_linear`], [`MoyoDataset::prim_std_origin_shift`]).
+    pub fn prim_std_transformation(&self) -> (Matrix3<f64>, OriginShift) {
+        let linear = self.reduction_linear * self.primitive_linear * self.prim_reduction_linear;
+        (
+            linear * self.standardization_linear,
+            linear * self.standardization_origin_shift,
+        )
+    }
+
+    /// Return the composed transformation from the input cell to the standardized cell, which equals ([`MoyoDataset::std_linear`], [`MoyoDataset::std_origin_shift`]).
+    pub fn std_transformation(&self) -> (Matrix3<f64>, OriginShift) {
+        let (prim_std_linear, prim_std_origin_shift) = self.prim_std_transformation();
+        (
+            prim_std_linear * self.centering_linear,
+            prim_std_origin_shift,
+        )
+    }
+}
+
+impl MoyoDataset {
+    /// Create a new [`MoyoDataset`] from the input cell, `cell`.
+    /// `symprec` and `angle_tolerance` control the tolerances for searching symmetry operations, and `symprec` is in the same length unit as the lattice of `cell`.
+    /// `setting` determines the preference for the "standardized" setting of a detected space-group type.
+    /// If the search fails, [`MoyoError`] is returned.
+    pub fn new(
+        cell: &Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        setting: Setting,
+    ) -> Result<Self, MoyoError> {
+        MoyoDatasetBuilder::new()
+            .symprec(symprec)
+            .angle_tolerance(angle_tolerance)
+            .setting(setting)
+            .build(cell)
+    }
+
+    /// Return the number of symmetry operations in the input cell.
+    pub fn num_operations(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Return the Green-Lagrange strain tensor in the cartesian coordinates of the input cell, which deforms the input lattice to the one symmetrized by the detected point group.
+    pub fn lattice_strain(&self) -> Matrix3<f64> {
+        // Deformation from `std_cell.lattice` to `ideal_std_lattice`, which share the orientation
+        let deformation = self.ideal_std_lattice.basis
+            * self
+                .std_cell
+                .lattice
+                .basis
+                .try_inverse()
+                .expect("Lattice of std_cell should be nonsingular");
+        let std_lattice_strain =
+            (deformation.transpose() * deformation - Matrix3::identity()) / 2.0;
+        // Rotate back to the cartesian coordinates of the input cell
+        self.std_rotation_matrix.transpose() * std_lattice_strain * self.std_rotation_matrix
+    }
+
+    /// Return the individual transformations composed into `std_linear` and `std_origin_shift`.
+    pub fn transformation_chain(&self) -> &TransformationChain {
+        &self.transformation_chain
+    }
+
+    /// Convert to a dataset with the same field names and conventions as Spglib's `SpglibDataset`.
+    pub fn to_spglib_like(&self) -> SpglibDataset {
+        let entry = hall_symbol_entry(self.hall_number).unwrap();
+        let pointgroup_symbol = arithmetic_crystal_class_entry(entry.arithmetic_number)
+            .geometric_crystal_class
+            .hermann_mauguin()
+            .to_string();
+
+        // Spglib's transformation (P, p) maps the input cell to the standardized cell as x_std = P * x + p
+        let transformation_matrix = self.std_linear.try_inverse().unwrap();
+        let origin_shift = -transformation_matrix * self.std_origin_shift;
+
+        SpglibDataset {
+            spacegroup_number: self.number,
+            hall_number: self.hall_number,
+            international_symbol: entry.hm_short.replace(' ', ""),
+            hall_symbol: entry.hall_symbol.to_string(),
+            choice: entry.setting.to_string(),
+            transformation_matrix,
+            origin_shift,
+            rotations: self.operations.iter().map(|ops| ops.rotation).collect(),
+            translations: self.operations.iter().map(|ops| ops.translation).collect(),
+            wyckoffs: self.wyckoffs.clone(),
+            site_symmetry_symbols: self.site_symmetry_symbols.clone(),
+            equivalent_atoms: self.orbits.clone(),
+            crystallographic_orbits: self.orbits.clone(),
+            std_lattice: self.std_cell.lattice.clone(),
+            std_types: self.std_cell.numbers.clone(),
+            std_positions: self.std_cell.positions.clone(),
+            std_rotation_matrix: self.std_rotation_matrix,
+            pointgroup_symbol,
+        }
+    }
+
+    /// Return representative sites of crystallographic orbits in the input cell, sorted in ascending order.
+    pub fn orbit_representatives(&self) -> Vec<usize> {
+        self.orbits.iter().copied().sorted().dedup().collect()
+    }
+
+    /// Return true if `std_linear` is an integer matrix, i.e., the standardized cell is a supercell of the input cell and `std_linear` can be safely rounded.
+    /// This does not hold, for example, when the input cell is a supercell of the standardized cell.
+    /// Note that the inverse of `prim_std_linear` is always an integer matrix.
+    pub fn std_linear_is_integer(&self) -> bool {
+        self.std_linear.iter().all(|e| (e - e.round()).abs() < EPS)
+    }
+
+    /// Return the transformation to the standardized cell as the pair `(P, p)` in the convention of ITA, `(a', b', c') = (a, b, c) P` and `x' = P^-1 (x - p)`.
+    /// `P` is [`MoyoDataset::std_linear`] as integers and `p` is [`MoyoDataset::std_origin_shift`] as fractions with denominators up to `MAX_ITA_DENOMINATOR`.
+    /// Return `None` if [`MoyoDataset::std_linear_is_integer`] does not hold, e.g. for a supercell input, or if `p` is farther than `symprec` from the fractions.
+    pub fn ita_transformation(&self) -> Option<(Matrix3<i32>, [Rational; 3])> {
+        if !self.std_linear_is_integer() {
+            return None;
+        }
+        let origin_shift =
+            [0, 1, 2].map(|i| Rational::approximate(self.std_origin_shift[i], MAX_ITA_DENOMINATOR));
+        let diff = self.std_origin_shift - Vector3::from(origin_shift.map(|e| e.to_f64()));
+        if self.input_lattice().cartesian_coords(&diff).norm() > self.symprec {
+            return None;
+        }
+        Some((self.std_linear.map(|e| e.round() as i32), origin_shift))
+    }
+
+    /// Return [`MoyoDataset::operations`] with translations snapped to the nearest fractions with denominators up to `max_denominator`.
+    /// See [`snap_translations`] for details.
+    pub fn snapped_operations(&self, max_denominator: i32) -> Operations {
+        snap_translations(&self.operations, max_denominator)
+    }
+
+    /// Return the number of occupied Wyckoff orbits, i.e., independent sites needed to describe the structure.
+    /// Orbits with the same Wyckoff letter are counted separately.
+    pub fn num_wyckoff_orbits(&self) -> usize {
+        self.orbits
+            .iter()
+            .zip(self.wyckoffs.iter())
+            .map(|(&orbit, &wyckoff)| (wyckoff, orbit))
+            .unique()
+            .count()
+    }
+
+    /// Return the number of sites in the input cell for each orbit of [`MoyoDataset::orbit_representatives`].
+    pub fn orbit_sizes(&self) -> Vec<usize> {
+        self.orbit_representatives()
+            .iter()
+            .map(|&representative| self.orbits.iter().filter(|&&i| i == representative).count())
+            .collect()
+    }
+
+    /// Return symmetrized positions of sites in the input cell, `cell`, in fractional coordinates of the input cell.
+    /// Unlike `std_cell`, the order of sites is kept and positions are not wrapped.
+    pub fn symmetrized_positions(&self, cell: &Cell) -> Vec<Position> {
+        self.symmetrized_sites(cell)
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Return the input cell, `cell`, with symmetrized positions and lattice, keeping its basis vectors, the order of sites, and the orientation.
+    /// The lattice is symmetrized by the detected point group, [`MoyoDataset::ideal_std_lattice`], and positions are [`MoyoDataset::symmetrized_positions`].
+    pub fn refined_input_cell(&self, cell: &Cell) -> Cell {
+        // std_rotation_matrix * input_basis * std_linear = std_lattice.basis
+        let lattice = input_lattice(
+            &self.ideal_std_lattice,
+            &self.std_linear,
+            &self.std_rotation_matrix,
+        );
+        let numbers = self
+            .mapping_std_prim
+            .iter()
+            .map(|&i| self.prim_std_cell.numbers[i])
+            .collect();
+        self.std_cell.with_element_symbols_of(Cell::new(
+            lattice,
+            self.symmetrized_positions(cell),
+            numbers,
+        ))
+    }
+
+    /// Return the distance of each site in the input cell, `cell`, from its symmetrized position in `std_cell`.
+    pub fn site_displacements(&self, cell: &Cell) -> Vec<f64> {
+        self.symmetrized_sites(cell)
+            .into_iter()
+            .map(|(_, distance)| distance)
+            .collect()
+    }
+
+    /// Return the root-mean-square displacement of sites from their symmetrized positions for each orbit of [`MoyoDataset::orbit_representatives`], where `cell` is the input cell.
+    /// The mean displacement over all sites, which is an acoustic translation, is subtracted beforehand, so a rigid shift of the symmetrized structure does not contribute.
+    /// This quantifies a distortion of the input cell from the structure with the detected symmetry, e.g. a polar displacement within `symprec`.
+    pub fn symmetry_mode_amplitudes(&self, cell: &Cell) -> Vec<f64> {
+        let displacements = cell
+            .positions
+            .iter()
+            .zip(self.symmetrized_sites(cell))
+            .map(|(position, (symmetrized, _))| {
+                cell.lattice
+                    .cartesian_coords(&(position - symmetrized).map(|e| e - e.round()))
+            })
+            .collect::<Vec<_>>();
+        let acoustic = displacements.iter().sum::<Vector3<f64>>() / displacements.len() as f64;
+
+        self.orbit_representatives()
+            .iter()
+            .map(|&representative| {
+                let squares = self
+                    .orbits
+                    .iter()
+                    .zip(displacements.iter())
+                    .filter(|(&orbit, _)| orbit == representative)
+                    .map(|(_, displacement)| (displacement - acoustic).norm_squared())
+                    .collect::<Vec<_>>();
+                (squares.iter().sum::<f64>() / squares.len() as f64).sqrt()
+            })
+            .collect()
+    }
+
+    /// Return space-group numbers of the types of maximal subgroups of the detected space group in ascending order.
+    /// See [`maximal_subgroup_numbers`] for the subgroups listed in the precomputed table.
+    pub fn maximal_subgroup_space_groups(&self) -> Vec<Number> {
+        maximal_subgroup_numbers(self.number)
+            .map(|numbers| numbers.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Return symmetry operations in the standardized cell, `std_cell`, including centering translations.
+    /// The order of operations is the same as coset representatives from the Hall symbol for `hall_number`.
+    pub fn conventional_symmetry_operations(&self) -> Operations {
+        let hall_symbol = HallSymbol::from_hall_number(self.hall_number).unwrap();
+        let coset = hall_symbol.traverse();
+
+        let mut operations = vec![];
+        for t1 in hall_symbol.centering.lattice_points().iter() {
+            for operation2 in coset.iter() {
+                // (E, t1) (r2, t2) = (r2, t1 + t2)
+                let t12 = (t1 + operation2.translation).map(|e| e % 1.);
+                operations.push(Operation::new(operation2.rotation, t12));
+            }
+        }
+        operations
+    }
+
+    /// Return distinct rotation parts of `operations`, which form the point group.
+    pub fn point_group_operations(&self) -> Rotations {
+        project_rotations(&self.operations)
+            .into_iter()
+            .unique()
+            .collect()
+    }
+
+    /// Return true if the space group contains the inversion.
+    pub fn is_centrosymmetric(&self) -> bool {
+        self.operations
+            .iter()
+            .any(|ops| identify_rotation_type(&ops.rotation) == RotationType::RotoInversion1)
+    }
+
+    /// Return true if the point group belongs to one of the ten polar crystal classes, which leave some direction invariant.
+    pub fn is_polar(&self) -> bool {
+        let entry = hall_symbol_entry(self.hall_number).unwrap();
+        matches!(
+            arithmetic_crystal_class_entry(entry.arithmetic_number).geometric_crystal_class,
+            GeometricCrystalClass::C1
+                | GeometricCrystalClass::C2
+                | GeometricCrystalClass::C1h
+                | GeometricCrystalClass::C2v
+                | GeometricCrystalClass::C4
+                | GeometricCrystalClass::C4v
+                | GeometricCrystalClass::C3
+                | GeometricCrystalClass::C3v
+                | GeometricCrystalClass::C6
+                | GeometricCrystalClass::C6v
+        )
+    }
+
+    /// Return true if the space group consists only of proper rotations, i.e., it is one of the Sohncke groups.
+    pub fn is_chiral(&self) -> bool {
+        self.operations.iter().all(|ops| {
+            matches!(
+                identify_rotation_type(&ops.rotation),
+                RotationType::Rotation1
+                    | RotationType::Rotation2
+                    | RotationType::Rotation3
+                    | RotationType::Rotation4
+                    | RotationType::Rotation6
+            )
+        })
+    }
+
+    /// Return the Laue class, the geometric crystal class of the point group augmented with the inversion.
+    pub fn laue_class(&self) -> GeometricCrystalClass {
+        let rotations = with_inversion(self.operations.iter().map(|ops| ops.rotation));
+        geometric_crystal_class_from_rotations(&rotations).unwrap()
+    }
+
+    /// Return the order of the holohedry (Bravais group) of the lattice, which is searched for on the primitive standardized lattice with the actually used tolerances.
+    /// It can be larger than the order of the crystal point group, `operations` without translations, by coincidental metric of the lattice, e.g. a tetragonal crystal with `c` close to `a`.
+    /// The lattice is reduced in the same way as the symmetry search, falling back to Delaunay reduction if Minkowski reduction fails.
+    pub fn holohedry_order(&self) -> Result<usize, MoyoError> {
+        let (reduced_lattice, _) = retry_with_delaunay(|reduction| {
+            let (lattice, _) = reduction.reduce(&self.prim_std_cell.lattice)?;
+            lattice.minkowski_reduce()
+        })?;
+        let bravais_group =
+            search_bravais_group(&reduced_lattice, self.symprec, self.angle_tolerance)?;
+        Ok(bravais_group.len())
+    }
+
+    /// Return the space-group number of the Patterson symmetry.
+    /// The Patterson symmetry is the symmorphic space group with the same lattice centering as the space group and the point group of its Laue class.
+    pub fn patterson_symmetry(&self) -> Number {
+        let hall_symbol = HallSymbol::from_hall_number(self.hall_number).unwrap();
+        let rotations = with_inversion(hall_symbol.traverse().iter().map(|ops| ops.rotation))
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // Search for a symmorphic Hall symbol with the same centering and rotations in the same basis
+        (1..=530)
+            .find_map(|hall_number| {
+                let entry = hall_symbol_entry(hall_number)?;
+                if entry.centering != hall_symbol.centering {
+                    return None;
+                }
+                let other = HallSymbol::new(entry.hall_symbol)?.traverse();
+                let symmorphic = other
+                    .iter()
+                    .all(|ops| ops.translation.iter().all(|e| e.abs() < EPS));
+                let other_rotations = other.iter().map(|ops| ops.rotation).collect::<HashSet<_>>();
+                (symmorphic && other_rotations == rotations).then_some(entry.number)
+            })
+            .unwrap()
+    }
+
+    /// Return groups of sites in the standardized cell, `std_cell`, related to each other only by centering translations.
+    /// Each group is sorted in ascending order, and groups are sorted by their first sites.
+    pub fn centering_equivalent_groups(&self) -> Vec<Vec<usize>> {
+        let centering = hall_symbol_entry(self.hall_number).unwrap().centering;
+        let lattice_points = centering.lattice_points();
+        let lattice = &self.std_cell.lattice;
+        let num_atoms = self.std_cell.num_atoms();
+
+        let mut visited = vec![false; num_atoms];
+        let mut groups = vec![];
+        for i in 0..num_atoms {
+            if visited[i] {
+                continue;
+            }
+            let mut group = vec![];
+            for lattice_point in lattice_points.iter() {
+                let new_position = self.std_cell.positions[i] + lattice_point;
+                for (j, visited_j) in visited.iter_mut().enumerate() {
+                    if *visited_j || self.std_cell.numbers[j] != self.std_cell.numbers[i] {
+                        continue;
+                    }
+                    let diff = (self.std_cell.positions[j] - new_position).map(|e| e - e.round());
+                    if lattice.cartesian_coords(&diff).norm() < self.symprec {
+                        *visited_j = true;
+                        group.push(j);
+                    }
+                }
+            }
+            group.sort();
+            groups.push(group);
+        }
+        groups
+    }
+
+    /// Return the primitive cell whose basis vectors are `std_cell.lattice.basis * centering.inverse()` for the centering of the Hall symbol.
+    /// Unlike reduced cells, the basis vectors follow the conventional choice, e.g., rhombohedral primitive vectors for R-centered space groups in hexagonal axes.
+    /// This is the same as `prim_std_cell` except that fractional coordinates are wrapped into [0, 1).
+    pub fn ita_primitive_cell(&self) -> Cell {
+        self.prim_std_cell.wrap()
+    }
+
+    /// Return the standardized cell, `std_cell`, with rhombohedral lattice points in `rhombohedral_setting`.
+    /// For R-centered space groups in hexagonal axes, the reverse setting is obtained by transforming `std_cell` with [`RhombohedralSetting::linear`] and rotating it by 180 degrees around the c axis, which keeps the basis vectors.
+    /// `std_cell` and the other fields, e.g. `hall_number` and `std_linear`, are always in the obverse setting.
+    pub fn std_cell_in(&self, rhombohedral_setting: RhombohedralSetting) -> Cell {
+        if hall_symbol_entry(self.hall_number).unwrap().centering != Centering::R {
+            return self.std_cell.clone();
+        }
+        let transformation = UnimodularTransformation::from_linear(rhombohedral_setting.linear());
+        let transformed = transformation.transform_cell(&self.std_cell);
+        let rotation_matrix =
+            self.std_cell.lattice.basis * transformed.lattice.basis.try_inverse().unwrap();
+        transformed.rotate(&rotation_matrix)
+    }
+
+    /// Return the standardized cell, `std_cell`, with fractional coordinates wrapped into [0, 1) and sites sorted by (atomic specie, Wyckoff letter, fractional coordinates).
+    /// The order of sites does not depend on that of the input cell, which makes outputs comparable across runs.
+    pub fn canonical_std_cell(&self) -> Cell {
+        let std_cell = self.std_cell.wrap();
+        let std_wyckoffs = self
+            .std_to_prim_std
+            .iter()
+            .map(|prim_site| {
+                let input_site = self
+                    .mapping_std_prim
+                    .iter()
+                    .position(|e| e == prim_site)
+                    .unwrap();
+                self.wyckoffs[input_site]
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = (0..std_cell.num_atoms()).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| {
+            std_cell.numbers[i]
+                .cmp(&std_cell.numbers[j])
+                .then(std_wyckoffs[i].cmp(&std_wyckoffs[j]))
+                .then_with(|| {
+                    (0..3)
+                        .map(|k| std_cell.positions[i][k].total_cmp(&std_cell.positions[j][k]))
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+
+        std_cell.with_element_symbols_of(Cell::new(
+            std_cell.lattice.clone(),
+            order.iter().map(|&i| std_cell.positions[i]).collect(),
+            order.iter().map(|&i| std_cell.numbers[i]).collect(),
+        ))
+    }
+
+    /// Return the asymmetric unit of the standardized cell, `std_cell`.
+    /// The first element contains indices of `std_cell` sites, one per crystallographic orbit, and the second element contains operations in `std_cell` to regenerate all sites from them.
+    pub fn asymmetric_unit(&self) -> (Vec<usize>, Operations) {
+        let operations = self.conventional_symmetry_operations();
+        let lattice = &self.std_cell.lattice;
+        let num_atoms = self.std_cell.num_atoms();
+
+        let mut visited = vec![false; num_atoms];
+        let mut representatives = vec![];
+        for i in 0..num_atoms {
+            if visited[i] {
+                continue;
+            }
+            representatives.push(i);
+            for operation in operations.iter() {
+                let new_position = operation.rotation.map(|e| e as f64)
+                    * self.std_cell.positions[i]
+                    + operation.translation;
+                for (j, visited_j) in visited.iter_mut().enumerate() {
+                    if self.std_cell.numbers[j] != self.std_cell.numbers[i] {
+                        continue;
+                    }
+                    let diff = (self.std_cell.positions[j] - new_position).map(|e| e - e.round());
+                    if lattice.cartesian_coords(&diff).norm() < self.symprec {
+                        *visited_j = true;
+                    }
+                }
+            }
+        }
+        (representatives, operations)
+    }
+
+    /// Return coset representatives generating the orbit of the `site_index`th site in the standardized cell, `std_cell`.
+    /// The `k`th operation of [`MoyoDataset::conventional_symmetry_operations`] maps the `site_index`th site to the `k`th site of its orbit in ascending order of site indices, and the first one is chosen for each site.
+    /// Thus, the number of returned operations is equal to the number of sites in the orbit in `std_cell`.
+    pub fn orbit_generators(&self, site_index: usize) -> Operations {
+        let operations = self.conventional_symmetry_operations();
+        let lattice = &self.std_cell.lattice;
+        let position = self.std_cell.positions[site_index];
+        let number = self.std_cell.numbers[site_index];
+
+        let mut generators = vec![None; self.std_cell.num_atoms()];
+        for operation in operations.iter() {
+            let new_position =
+                operation.rotation.map(|e| e as f64) * position + operation.translation;
+            for (j, generator) in generators.iter_mut().enumerate() {
+                if generator.is_some() || self.std_cell.numbers[j] != number {
+                    continue;
+                }
+                let diff = (self.std_cell.positions[j] - new_position).map(|e| e - e.round());
+                if lattice.cartesian_coords(&diff).norm() < self.symprec {
+                    *generator = Some(operation.clone());
+                }
+            }
+        }
+        generators.into_iter().flatten().collect()
+    }
+
+    /// Return pairs of sites in the standardized cell, `std_cell`, within `cutoff` as `(i, j, distance)`, one for each class of symmetry-equivalent pairs.
+    /// A pair consists of the `i`th site and a periodic image of the `j`th site, and is equivalent to the pair swapping them.
+    /// The returned pairs are sorted by distance.
+    pub fn inequivalent_pairs(&self, cutoff: f64) -> Vec<(usize, usize, f64)> {
+        let operations = self.conventional_symmetry_operations();
+        let lattice = &self.std_cell.lattice;
+        let positions = &self.std_cell.positions;
+        let num_atoms = self.std_cell.num_atoms();
+
+        // The `k`th operation moves the `i`th site to the `mappings[k][i].0`th site in the `mappings[k][i].1` image
+        let mappings = operations
+            .iter()
+            .map(|operation| {
+                (0..num_atoms)
+                    .map(|i| {
+                        let new_position = operation.rotation.map(|e| e as f64) * positions[i]
+                            + operation.translation;
+                        (0..num_atoms)
+                            .find_map(|j| {
+                                let image = new_position - positions[j];
+                                let diff = image.map(|e| e - e.round());
+                                if self.std_cell.numbers[j] == self.std_cell.numbers[i]
+                                    && lattice.cartesian_coords(&diff).norm() < self.symprec
+                                {
+                                    Some((j, image.map(|e| e.round() as i32)))
+                                } else {
+                                    None
+                                }
+                            })
+                            .unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Enumerate pairs (i, j, offset) with the `j`th site shifted by `offset`
+        let reciprocal_basis = lattice.basis.try_inverse().unwrap();
+        let max_offsets = (0..3)
+            .map(|k| (cutoff * reciprocal_basis.row(k).norm()).ceil() as i32 + 1)
+            .collect::<Vec<_>>();
+        let mut pairs = vec![];
+        for (i, j) in iproduct!(0..num_atoms, 0..num_atoms) {
+            for offset in iproduct!(
+                -max_offsets[0]..=max_offsets[0],
+                -max_offsets[1]..=max_offsets[1],
+                -max_offsets[2]..=max_offsets[2]
+            ) {
+                let offset = Vector3::new(offset.0, offset.1, offset.2);
+                let diff = positions[j] + offset.map(|e| e as f64) - positions[i];
+                let distance = lattice.cartesian_coords(&diff).norm();
+                if (i != j || offset != Vector3::zeros()) && distance < cutoff {
+                    pairs.push((i, j, offset, distance));
+                }
+            }
+        }
+        let indices = pairs
+            .iter()
+            .enumerate()
+            .map(|(index, &(i, j, offset, _))| ((i, j, offset), index))
+            .collect::<HashMap<_, _>>();
+
+        let mut uf = QuickFindUf::<UnionByRank>::new(pairs.len());
+        for (index, &(i, j, offset, _)) in pairs.iter().enumerate() {
+            // Swapping the two sites
+            if let Some(&other) = indices.get(&(j, i, -offset)) {
+                uf.union(index, other);
+            }
+            for (operation, mapping) in operations.iter().zip(mappings.iter()) {
+                let (new_i, image_i) = mapping[i];
+                let (new_j, image_j) = mapping[j];
+                let new_offset = operation.rotation * offset + image_j - image_i;
+                if let Some(&other) = indices.get(&(new_i, new_j, new_offset)) {
+                    uf.union(index, other);
+                }
+            }
+        }
+
+        let mut representatives = BTreeMap::new();
+        for (index, &(i, j, _, distance)) in pairs.iter().enumerate() {
+            representatives
+                .entry(uf.find(index))
+                .or_insert((i, j, distance));
+        }
+        let mut inequivalent_pairs = representatives.into_values().collect::<Vec<_>>();
+        inequivalent_pairs.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+        inequivalent_pairs
+    }
+
+    /// Enumerate symmetry-distinct collinear magnetic orderings on the standardized cell, `std_cell`.
+    /// Each ordering assigns +1 (up) or -1 (down) to every site of `std_cell`.
+    /// Two orderings are regarded as equivalent if a symmetry operation, optionally followed by flipping all spins, maps one onto the other, and the lexicographically largest one is returned for each class.
+    /// To place spins only on some species, call this method on a dataset of the magnetic sublattice, e.g., from [`sublattice_dataset`].
+    ///
+    /// Since all 2^n assignments of the n sites are visited, an empty list is returned if `std_cell` has more than 16 sites.
+    pub fn allowed_magnetic_orderings(&self) -> Vec<Vec<i32>> {
+        let num_atoms = self.std_cell.num_atoms();
+        if num_atoms > MAX_MAGNETIC_ORDERING_SITES {
+            return vec![];
+        }
+        // `std_cell` is symmetrized, so every operation permutes its sites.
+        let permutations = site_permutations(
+            &self.std_cell,
+            &self.conventional_symmetry_operations(),
+            self.symprec,
+        )
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        let mut orderings = vec![];
+        for bits in 0..(1_u32 << num_atoms) {
+            let ordering = (0..num_atoms)
+                .map(|i| if (bits >> i) & 1 == 0 { 1 } else { -1 })
+                .collect::<Vec<_>>();
+            // Keep only the largest representative among equivalent orderings
+            let is_representative = permutations.iter().all(|permutation| {
+                let mut image = vec![0; num_atoms];
+                for (i, &spin) in ordering.iter().enumerate() {
+                    image[permutation.apply(i)] = spin;
+                }
+                let flipped = image.iter().map(|&spin| -spin).collect::<Vec<_>>();
+                image <= ordering && flipped <= ordering
+            });
+            if is_representative {
+                orderings.push(ordering);
+            }
+        }
+        orderings
+    }
+
+    /// Return symmetry operations in the primitive standardized cell, `prim_std_cell`.
+    /// The order of operations is the same as coset representatives from the Hall symbol for `hall_number`.
+    pub fn prim_std_operations(&self) -> Result<Operations, MoyoError> {
+        let hall_symbol = HallSymbol::from_hall_number(self.hall_number)
+            .ok_or(MoyoError::UnknownHallNumberError)?;
+        Ok(hall_symbol
+            .primitive_traverse()
+            .into_iter()
+            .map(|ops| Operation::new(ops.rotation, ops.translation.map(|e| e.rem_euclid(1.))))
+            .collect())
+    }
+
+    /// Return conjugacy classes of the point group.
+    /// Each class is a list of indices into the operations of [`MoyoDataset::prim_std_operations`], which contain each rotation once.
+    /// Classes are sorted by their smallest indices, and the first class is the identity.
+    pub fn point_group_conjugacy_classes(&self) -> Result<Vec<Vec<usize>>, MoyoError> {
+        let rotations = self
+            .prim_std_operations()?
+            .iter()
+            .map(|ops| ops.rotation)
+            .collect::<Vec<_>>();
+        let inverses = rotations
+            .iter()
+            .map(|rotation| {
+                rotations
+                    .iter()
+                    .find(|other| *rotation * **other == Rotation::identity())
+                    .copied()
+                    .ok_or(MoyoError::InvalidOperationsError)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut visited = vec![false; rotations.len()];
+        let mut classes = vec![];
+        for i in 0..rotations.len() {
+            if visited[i] {
+                continue;
+            }
+            let mut class = vec![];
+            for (g, g_inv) in rotations.iter().zip(inverses.iter()) {
+                let conjugated = g * rotations[i] * g_inv;
+                let j = rotations
+                    .iter()
+                    .position(|r| *r == conjugated)
+                    .ok_or(MoyoError::InvalidOperationsError)?;
+                if !visited[j] {
+                    visited[j] = true;
+                    class.push(j);
+                }
+            }
+            class.sort();
+            classes.push(class);
+        }
+        Ok(classes)
+    }
+
+    /// Return generators of the space group in the standardized cell, `std_cell`, from the Hall symbol for `hall_number`.
+    /// Centering translations are not included.
+    pub fn conventional_generators(&self) -> Result<Operations, MoyoError> {
+        let hall_symbol = HallSymbol::from_hall_number(self.hall_number)
+            .ok_or(MoyoError::UnknownHallNumberError)?;
+        Ok(hall_symbol.generators)
+    }
+
+    /// Return generators of the Hall symbol, [`MoyoDataset::conventional_generators`], and pure translations transformed into the basis of the input cell.
+    /// Translations are wrapped into [0, 1), and the returned operations generate [`MoyoDataset::operations`] up to translations of the input lattice.
+    /// For a supercell input, Hall generators that do not preserve the input lattice are replaced by operations in [`MoyoDataset::operations`].
+    pub fn input_cell_generators(&self) -> Operations {
+        let hall_symbol = HallSymbol::from_hall_number(self.hall_number).unwrap();
+        // Centering translations, and also translations of the primitive lattice for a supercell input
+        let mut generators: Operations = self
+            .operations
+            .iter()
+            .filter(|operation| {
+                operation.rotation == Rotation::identity()
+                    && operation
+                        .translation
+                        .iter()
+                        .any(|e| (e - e.round()).abs() > EPS)
+            })
+            .cloned()
+            .collect();
+
+        // x_input = std_linear * x_std + std_origin_shift
+        let std_linear_inv = self.std_linear.try_inverse().unwrap();
+        for operation in hall_symbol.generators {
+            let rotation_f64 =
+                self.std_linear * operation.rotation.map(|e| e as f64) * std_linear_inv;
+            let rotation = rotation_f64.map(|e| e.round() as i32);
+            if (rotation_f64 - rotation.map(|e| e as f64)).amax() > EPS {
+                continue;
+            }
+            let translation = self.std_linear * operation.translation + self.std_origin_shift
+                - rotation.map(|e| e as f64) * self.std_origin_shift;
+            generators.push(Operation::new(
+                rotation,
+                translation.map(|e| e.rem_euclid(1.)),
+            ));
+        }
+
+        // Complete the generators with operations not generated yet
+        let lattice = self.input_lattice();
+        let equal_mod1 = |lhs: &Operation, rhs: &Operation| {
+            let diff = (lhs.translation - rhs.translation).map(|e| e - e.round());
+            lhs.rotation == rhs.rotation && lattice.cartesian_coords(&diff).norm() < self.symprec
+        };
+        let mut group = vec![Operation::identity()];
+        loop {
+            let mut index = 0;
+            while index < group.len() {
+                for generator in generators.iter() {
+                    let product = group[index].compose_mod1(generator);
+                    if !group.iter().any(|other| equal_mod1(other, &product)) {
+                        group.push(product);
+                    }
+                }
+                index += 1;
+            }
+
+            match self
+                .operations
+                .iter()
+                .find(|operation| !group.iter().any(|other| equal_mod1(other, operation)))
+            {
+                Some(operation) => generators.push(operation.clone()),
+                None => break,
+            }
+        }
+        generators
+    }
+
+    /// Return geometric crystal classes of site-symmetry groups for each site in the input cell.
+    /// [`MoyoError::GeometricCrystalClassIdentificationError`] is returned if the operations fixing a site within `symprec` do not form a point group.
+    pub fn site_symmetry_point_groups(&self) -> Result<Vec<GeometricCrystalClass>, MoyoError> {
+        let operations = self.conventional_symmetry_operations();
+        let lattice = &self.std_cell.lattice;
+
+        // Site-symmetry groups of sites in the same orbit are conjugate to each other
+        let mut prim_std_point_groups = vec![None; self.prim_std_cell.num_atoms()];
+        for (i, position) in self.std_cell.positions.iter().enumerate() {
+            let j = self.std_to_prim_std[i];
+            if prim_std_point_groups[j].is_some() {
+                continue;
+            }
+
+            let rotations = operations
+                .iter()
+                .filter(|operation| {
+                    let diff = (operation.rotation.map(|e| e as f64) * position
+                        + operation.translation
+                        - position)
+                        .map(|e| e - e.round());
+                    lattice.cartesian_coords(&diff).norm() < self.symprec
+                })
+                .map(|operation| operation.rotation)
+                .unique()
+                .collect::<Vec<_>>();
+            prim_std_point_groups[j] = Some(geometric_crystal_class_from_rotations(&rotations)?);
+        }
+
+        self.mapping_std_prim
+            .iter()
+            .map(|&j| prim_std_point_groups[j])
+            .collect::<Option<Vec<_>>>()
+            .ok_or(MoyoError::GeometricCrystalClassIdentificationError)
+    }
+
+    /// Return free parameters of Wyckoff positions for each site in the input cell.
+    /// For example, `[x]` for a site at Wyckoff position with coordinates `x,x,0`, where `x` is wrapped into [0, 1).
+    /// The parameters are solved for the first site of each orbit in the standardized cell, `std_cell`, and shared within the orbit.
+    /// [`MoyoError::WyckoffPositionAssignmentError`] is returned if no Wyckoff position matches a site within `symprec`.
+    pub fn wyckoff_parameters(&self) -> Result<Vec<Vec<f64>>, MoyoError> {
+        let mut orbit_parameters = HashMap::new();
+        for &orbit in self.orbits.iter() {
+            if orbit_parameters.contains_key(&orbit) {
+                continue;
+            }
+
+            // Sites in std_cell belonging to the same orbit
+            let orbit_prim_sites = (0..self.orbits.len())
+                .filter(|&k| self.orbits[k] == orbit)
+                .map(|k| self.mapping_std_prim[k])
+                .collect::<Vec<_>>();
+            let std_sites = (0..self.std_cell.num_atoms())
+                .filter(|&j| orbit_prim_sites.contains(&self.std_to_prim_std[j]))
+                .collect::<Vec<_>>();
+
+            let representative = *std_sites
+                .first()
+                .ok_or(MoyoError::WyckoffPositionAssignmentError)?;
+            let (wyckoff, y) = assign_wyckoff_position(
+                &self.std_cell.positions[representative],
+                std_sites.len(),
+                self.hall_number,
+                &self.std_cell.lattice,
+                self.symprec,
+            )?;
+            let space = WyckoffPositionSpace::new(wyckoff.coordinates);
+            let parameters = (0..3)
+                .filter(|&k| space.linear.column(k).iter().any(|&e| e != 0))
+                .map(|k| y[k].rem_euclid(1.0))
+                .collect::<Vec<_>>();
+            orbit_parameters.insert(orbit, parameters);
+        }
+
+        Ok(self
+            .orbits
+            .iter()
+            .map(|orbit| orbit_parameters[orbit].clone())
+            .collect())
+    }
+
+    /// Return the symmetry-allowed form of a rank-2 tensor in the cartesian coordinates of the input cell.
+    /// The tensor is averaged as `R^T * tensor * R` over cartesian rotations `R` of `operations`.
+    pub fn symmetrize_rank2_tensor(&self, tensor: Matrix3<f64>) -> Matrix3<f64> {
+        let lattice = self.input_lattice();
+        let sum = self
+            .operations
+            .iter()
+            .map(|operation| {
+                let rotation = operation.cartesian_rotation(&lattice);
+                rotation.transpose() * tensor * rotation
+            })
+            .sum::<Matrix3<f64>>();
+        sum / (self.num_operations() as f64)
+    }
+
+    /// Return symmetry-inequivalent atomic displacements for finite-difference phonon calculations as pairs of a site index in the input cell and a cartesian displacement with length `amplitude`.
+    /// For each of [`MoyoDataset::orbit_representatives`], cartesian axes are tried in order, and an axis is kept only if its images by the site-symmetry operations are not spanned by those of the previously kept axes.
+    pub fn phonon_displacements(&self, amplitude: f64) -> Vec<(usize, Vector3<f64>)> {
+        let lattice = self.input_lattice();
+        let mut displacements = vec![];
+        for representative in self.orbit_representatives() {
+            // Symmetrized position of a site equivalent to `representative` by a pure translation, which shares its site-symmetry operations
+            let std_site = self
+                .std_to_prim_std
+                .iter()
+                .position(|&i| i == self.mapping_std_prim[representative])
+                .expect("Each primitive site should appear in std_cell");
+            let position =
+                self.std_linear * self.std_cell.positions[std_site] + self.std_origin_shift;
+            let rotations = self
+                .operations
+                .iter()
+                .filter(|operation| {
+                    let diff = (operation.rotation.map(|e| e as f64) * position
+                        + operation.translation
+                        - position)
+                        .map(|e| e - e.round());
+                    lattice.cartesian_coords(&diff).norm() < self.symprec
+                })
+                .map(|operation| operation.cartesian_rotation(&lattice))
+                .collect::<Vec<_>>();
+
+            // Orthonormal basis of the space spanned by images of kept displacements
+            let mut span: Vec<Vector3<f64>> = vec![];
+            for axis in 0..3 {
+                if span.len() == 3 {
+                    break;
+                }
+                let direction = Vector3::ith(axis, 1.0);
+                let mut independent = false;
+                for rotation in rotations.iter() {
+                    let mut image = rotation * direction;
+                    for basis in span.iter() {
+                        image -= basis.dot(&image) * basis;
+                    }
+                    if image.norm() > EPS {
+                        span.push(image.normalize());
+                        independent = true;
+                    }
+                }
+                if independent {
+                    displacements.push((representative, amplitude * direction));
+                }
+            }
+        }
+        displacements
+    }
+
+    /// Return rotations acting on fractional coordinates in the reciprocal basis of the input cell, `(R^-1)^T` for rotation `R` of each of `operations`.
+    /// They are integer matrices because `R` is unimodular.
+    pub fn reciprocal_operations(&self) -> Vec<Matrix3<i32>> {
+        self.operations
+            .iter()
+            .map(|operation| {
+                operation
+                    .rotation
+                    .map(|e| e as f64)
+                    .try_inverse()
+                    .unwrap()
+                    .transpose()
+                    .map(|e| e.round() as i32)
+            })
+            .collect()
+    }
+
+    /// Return irreducible k-points of a `mesh` with a `shift` and their weights under the point group and time reversal.
+    /// The `i`th coordinate of k-points in the reciprocal basis of the input cell is `(n + shift[i]) / mesh[i]` with `0 <= n < mesh[i]`, and the weights sum to the number of k-points in the mesh.
+    /// Reciprocal-space rotations are `(R^-1)^T` for rotations `R` of `operations`, and ones not preserving the mesh are ignored.
+    pub fn irreducible_kpoints(
+        &self,
+        mesh: [usize; 3],
+        shift: [f64; 3],
+    ) -> Vec<(Vector3<f64>, usize)> {
+        let mesh_f64 = Vector3::from(mesh.map(|e| e as f64));
+        let shift = Vector3::from(shift);
+        let num_kpoints = mesh.iter().product::<usize>();
+        let address = |index: usize| {
+            Vector3::new(
+                index % mesh[0],
+                (index / mesh[0]) % mesh[1],
+                index / (mesh[0] * mesh[1]),
+            )
+        };
+        let kpoint =
+            |index: usize| (address(index).map(|e| e as f64) + shift).component_div(&mesh_f64);
+        let index_of = |k: &Vector3<f64>| {
+            let n = k.component_mul(&mesh_f64) - shift;
+            if n.iter().any(|e| (e - e.round()).abs() > EPS) {
+                return None;
+            }
+            let n =
+                Vector3::from_fn(|i, _| (n[i].round() as i64).rem_euclid(mesh[i] as i64) as usize);
+            Some(n[0] + mesh[0] * (n[1] + mesh[1] * n[2]))
+        };
+
+        let reciprocal_rotations = self
+            .reciprocal_operations()
+            .into_iter()
+            .unique()
+            .map(|rotation| rotation.map(|e| e as f64))
+            .collect::<Vec<_>>();
+
+        let mut uf = QuickFindUf::<UnionByRank>::new(num_kpoints);
+        for index in 0..num_kpoints {
+            let k = kpoint(index);
+            for rotation in reciprocal_rotations.iter() {
+                let rotated = rotation * k;
+                // Time reversal maps k to -k
+                for other in [rotated, -rotated].iter().filter_map(index_of) {
+                    uf.union(index, other);
+                }
+            }
+        }
+
+        let mut weights = BTreeMap::new();
+        for index in 0..num_kpoints {
+            let root = uf.find(index);
+            weights.entry(root).or_insert((index, 0)).1 += 1;
+        }
+        weights
+            .values()
+            .sorted()
+            .map(|&(representative, weight)| (kpoint(representative), weight))
+            .collect()
+    }
+
+    /// Return the multiplication table of `operations`.
+    /// `operations[i] * operations[j]` is equal to `operations[table[i][j]]` modulo lattice translations.
+    /// The table is computed on the first call and reused afterwards.
+    pub fn cayley_table(&self) -> Result<&[Vec<usize>], MoyoError> {
+        if let Some(table) = self.cayley_table.get() {
+            return Ok(table);
+        }
+        // `operations` are closed under multiplication with the refined translations
+        let table = multiplication_table(&self.operations, &self.input_lattice(), self.symprec)
+            .ok_or(MoyoError::InvalidOperationsError)?;
+        Ok(self.cayley_table.get_or_init(|| table))
+    }
+
+    /// Return the index of the identity operation in `operations`.
+    pub fn identity_index(&self) -> Result<usize, MoyoError> {
+        Self::identity_index_from_table(self.cayley_table()?)
+    }
+
+    /// Return `inverse_indices` such that `operations[i] * operations[inverse_indices[i]]` is a pure lattice translation.
+    pub fn inverse_indices(&self) -> Result<Vec<usize>, MoyoError> {
+        let table = self.cayley_table()?;
+        let identity = Self::identity_index_from_table(table)?;
+        table
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .position(|&k| k == identity)
+                    .ok_or(MoyoError::InvalidOperationsError)
+            })
+            .collect()
+    }
+
+    /// Return a presentation of the space group modulo lattice translations as generators and relations among them.
+    /// Generators are chosen from `operations` greedily in descending order of their orders.
+    /// A relation is a word equal to the identity modulo lattice translations, where `k` (`-k`) stands for the `k`th generator (its inverse) in one-based indexing.
+    /// The first relations give the orders of the generators, and the remaining ones are read off from the Cayley graph, which makes the presentation complete but not necessarily minimal.
+    pub fn group_presentation(&self) -> Result<(Operations, Vec<Vec<i32>>), MoyoError> {
+        let table = self.cayley_table()?;
+        let identity = Self::identity_index_from_table(table)?;
+        let order = table.len();
+        let element_orders = (0..order)
+            .map(|i| {
+                let mut power = i;
+                let mut element_order = 1;
+                while power != identity {
+                    power = table[power][i];
+                    element_order += 1;
+                }
+                element_order
+            })
+            .collect::<Vec<_>>();
+
+        // Choose generators until they generate the whole group
+        let mut generators: Vec<usize> = vec![];
+        let mut words = vec![None; order];
+        while words.iter().any(|word| word.is_none()) {
+            let generator = (0..order)
+                .filter(|&i| words[i].is_none())
+                .max_by_key(|&i| (element_orders[i], std::cmp::Reverse(i)))
+                .unwrap();
+            generators.push(generator);
+
+            // Shortest words for elements in the subgroup by BFS on the Cayley graph
+            words = vec![None; order];
+            words[identity] = Some(vec![]);
+            let mut queue = VecDeque::from([identity]);
+            while let Some(i) = queue.pop_front() {
+                for (k, &g) in generators.iter().enumerate() {
+                    let j = table[i][g];
+                    if words[j].is_none() {
+                        let mut word = words[i].clone().unwrap();
+                        word.push(k as i32 + 1);
+                        words[j] = Some(word);
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+        let words = words
+            .into_iter()
+            .map(|word| word.unwrap())
+            .collect::<Vec<_>>();
+
+        let mut relations = generators
+            .iter()
+            .enumerate()
+            .map(|(k, &g)| vec![k as i32 + 1; element_orders[g]])
+            .collect::<Vec<_>>();
+        for i in 0..order {
+            for (k, &g) in generators.iter().enumerate() {
+                // words[i] * g * words[table[i][g]]^-1 = identity
+                let mut relation = vec![];
+                for letter in words[i]
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(k as i32 + 1))
+                    .chain(words[table[i][g]].iter().rev().map(|&letter| -letter))
+                {
+                    if relation.last() == Some(&-letter) {
+                        relation.pop();
+                    } else {
+                        relation.push(letter);
+                    }
+                }
+                if !relation.is_empty() && !relations.contains(&relation) {
+                    relations.push(relation);
+                }
+            }
+        }
+
+        Ok((
+            generators
+                .iter()
+                .map(|&g| self.operations[g].clone())
+                .collect(),
+            relations,
+        ))
+    }
+
+    fn identity_index_from_table(table: &[Vec<usize>]) -> Result<usize, MoyoError> {
+        // The identity is the only idempotent element of a group
+        (0..table.len())
+            .find(|&i| table[i][i] == i)
+            .ok_or(MoyoError::InvalidOperationsError)
+    }
+
+    /// Match each site of the input cell, `cell`, to the nearest symmetrized site of the same primitive site, compared in `std_cell`.
+    /// Return pairs of the symmetrized position in fractional coordinates of the input cell, which is not wrapped, and the distance to it.
+    fn symmetrized_sites(&self, cell: &Cell) -> Vec<(Position, f64)> {
+        let std_linear_inv = self
+            .std_linear
+            .try_inverse()
+            .expect("std_linear should be nonsingular");
+        cell.positions
+            .iter()
+            .zip(self.mapping_std_prim.iter())
+            .map(|(input_position, &prim_site)| {
+                let position = std_linear_inv * (input_position - self.std_origin_shift);
+                let (diff, distance) = (0..self.std_cell.num_atoms())
+                    .filter(|&j| self.std_to_prim_std[j] == prim_site)
+                    .map(|j| {
+                        let diff = (position - self.std_cell.positions[j]).map(|e| e - e.round());
+                        (diff, self.std_cell.lattice.cartesian_coords(&diff).norm())
+                    })
+                    .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+                    .expect("Each primitive site should appear in std_cell");
+                // x_input = std_linear * x_std + std_origin_shift
+                (input_position - self.std_linear * diff, distance)
+            })
+            .collect()
+    }
+
+    fn input_lattice(&self) -> Lattice {
+        input_lattice(
+            &self.std_cell.lattice,
+            &self.std_linear,
+            &self.std_rotation_matrix,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Builder for [`MoyoDataset`] with optional configurations.
+/// ```
+/// use nalgebra::{matrix, vector};
+/// use moyo::MoyoDatasetBuilder;
+/// use moyo::base::{Cell, Lattice};
+/// use moyo::data::Setting;
+///
+/// let cell = Cell::new(
+///     Lattice::new(matrix![1.0, 0.0, 0.0; 0.0, 1.0, 0.0; 0.0, 0.0, 1.0]),
+///     vec![vector![0.0, 0.0, 0.0]],
+///     vec![0],
+/// );
+/// let dataset = MoyoDatasetBuilder::new()
+///     .symprec(1e-5)
+///     .setting(Setting::Standard)
+///     .build(&cell)
+///     .unwrap();
+/// assert_eq!(dataset.number, 221);  // Pm-3m
+/// ```
+pub struct MoyoDatasetBuilder {
+    symprec: Symprec,
+    angle_tolerance: AngleTolerance,
+    setting: Setting,
+    origin_choice: Option<OriginChoice>,
+    standardize_rotation: bool,
+    max_operations: Option<usize>,
+}
+
+impl Default for MoyoDatasetBuilder {
+    fn default() -> Self {
+        Self {
+            symprec: Symprec::Absolute(1e-4),
+            angle_tolerance: AngleTolerance::Default,
+            setting: Setting::Spglib,
+            origin_choice: None,
+            standardize_rotation: true,
+            max_operations: None,
+        }
+    }
+}
+
+impl MoyoDatasetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tolerance for searching symmetry operations.
+    /// A plain `f64` is regarded as [`Symprec::Absolute`] in the unit of the lattice.
+    pub fn symprec(mut self, symprec: impl Into<Symprec>) -> Self {
+        self.symprec = symprec.into();
+        self
+    }
+
+    /// Angle tolerance for searching symmetry operations.
+    pub fn angle_tolerance(mut self, angle_tolerance: AngleTolerance) -> Self {
+        self.angle_tolerance = angle_tolerance;
+        self
+    }
+
+    /// Preference for the "standardized" setting of a detected space-group type.
+    pub fn setting(mut self, setting: Setting) -> Self {
+        self.setting = setting;
+        self
+    }
+
+    /// Override the origin choice of `setting` for space groups with two origins.
+    pub fn origin_choice(mut self, origin_choice: OriginChoice) -> Self {
+        self.origin_choice = Some(origin_choice);
+        self
+    }
+
+    /// If false, the standardized cells keep the orientation of the input cell and `std_rotation_matrix` is the identity.
+    pub fn standardize_rotation(mut self, standardize_rotation: bool) -> Self {
+        self.standardize_rotation = standardize_rotation;
+        self
+    }
+
+    /// Reject the input cell with [`MoyoError::TooManyOperationsError`] if it has more symmetry operations than `max_operations`.
+    /// The symmetry search stops as soon as more operations than the cap are found, so `num_operations` in the error is the number found by then, which may be smaller than the actual number.
+    pub fn max_operations(mut self, max_operations: usize) -> Self {
+        self.max_operations = Some(max_operations);
+        self
+    }
+
+    /// Create a new [`MoyoDataset`] from the input cell, `cell`.
+    /// If the search fails, [`MoyoError`] is returned.
+    pub fn build(&self, cell: &Cell) -> Result<MoyoDataset, MoyoError> {
+        let symprec = self.symprec.absolute(&cell.lattice);
+        let context = SymmetryContext::with_cache(
+            cell,
+            symprec,
+            self.angle_tolerance,
+            self.max_operations,
+            &mut |_, _| {},
+            &mut LatticeCache::default(),
+        )?;
+        context.build_dataset(self.setting, self.origin_choice, self.standardize_rotation)
+    }
 }
 
-impl MoyoDataset {
-    /// Create a new [`MoyoDataset`] from the input cell, `cell`.
-    /// `symprec` and `angle_tolerance` control the tolerances for searching symmetry operations.
-    /// `setting` determines the preference for the "standardized" setting of a detected space-group type.
-    /// If the search fails, [`MoyoError`] is returned.
-    pub fn new(
+/// Symmetry search result of a cell which can be reused to create [`MoyoDataset`]s in different settings.
+///
+/// ```
+/// use nalgebra::{matrix, vector};
+/// use moyo::SymmetryContext;
+/// use moyo::base::{AngleTolerance, Cell, Lattice};
+/// use moyo::data::Setting;
+///
+/// let cell = Cell::new(
+///     Lattice::new(matrix![1.0, 0.0, 0.0; 0.0, 1.0, 0.0; 0.0, 0.0, 1.0]),
+///     vec![vector![0.0, 0.0, 0.0]],
+///     vec![0],
+/// );
+/// let context = SymmetryContext::new(&cell, 1e-5, AngleTolerance::Default).unwrap();
+/// let dataset = context.dataset(Setting::Standard).unwrap();
+/// assert_eq!(dataset.number, 221);  // Pm-3m
+/// ```
+#[derive(Debug)]
+pub struct SymmetryContext {
+    cell: Cell,
+    prim_cell: PrimitiveCell,
+    symmetry_search: PrimitiveSymmetrySearch,
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+    tolerance_adjusted: bool,
+    iterations: usize,
+}
+
+impl SymmetryContext {
+    /// Search symmetry operations of the input cell, `cell`.
+    /// If the search fails, [`MoyoError`] is returned.
+    pub fn new(
+        cell: &Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+    ) -> Result<Self, MoyoError> {
+        Self::with_callback(cell, symprec, angle_tolerance, &mut |_, _| {})
+    }
+
+    /// Same as [`SymmetryContext::new`] but report progress of the iterative symmetry search.
+    /// `callback` is called after each trial with the tried `symprec` and whether the trial succeeded.
+    pub fn with_callback(
+        cell: &Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        callback: &mut dyn FnMut(f64, bool),
+    ) -> Result<Self, MoyoError> {
+        Self::with_cache(
+            cell,
+            symprec,
+            angle_tolerance,
+            None,
+            callback,
+            &mut LatticeCache::default(),
+        )
+    }
+
+    fn with_cache(
         cell: &Cell,
         symprec: f64,
         angle_tolerance: AngleTolerance,
-        setting: Setting,
+        max_operations: Option<usize>,
+        callback: &mut dyn FnMut(f64, bool),
+        cache: &mut LatticeCache,
     ) -> Result<Self, MoyoError> {
-        let (prim_cell, symmetry_search, symprec, angle_tolerance) =
-            iterative_symmetry_search(cell, symprec, angle_tolerance)?;
-        let operations = operations_in_cell(&prim_cell, &symmetry_search.operations);
+        let (prim_cell, symmetry_search, new_symprec, new_angle_tolerance, iterations) =
+            iterative_symmetry_search(
+                cell,
+                symprec,
+                angle_tolerance,
+                max_operations,
+                callback,
+                cache,
+            )?;
+        Ok(Self {
+            cell: cell.clone(),
+            prim_cell,
+            symmetry_search,
+            symprec: new_symprec,
+            angle_tolerance: new_angle_tolerance,
+            tolerance_adjusted: new_symprec != symprec || new_angle_tolerance != angle_tolerance,
+            iterations,
+        })
+    }
 
-        // Space-group type identification
-        let epsilon = symprec / prim_cell.cell.lattice.volume().powf(1.0 / 3.0);
-        let space_group = SpaceGroup::new(&symmetry_search.operations, setting, epsilon)?;
+    /// Actually used `symprec` in iterative symmetry search.
+    pub fn symprec(&self) -> f64 {
+        self.symprec
+    }
 
-        // Standardized cell
+    /// Actually used `angle_tolerance` in iterative symmetry search.
+    pub fn angle_tolerance(&self) -> AngleTolerance {
+        self.angle_tolerance
+    }
+
+    /// Return the number of symmetry operations in the input cell.
+    pub fn num_operations(&self) -> usize {
+        self.symmetry_search.operations.len() * self.prim_cell.translations.len()
+    }
+
+    /// Create a new [`MoyoDataset`] in `setting` without redoing the symmetry search.
+    pub fn dataset(&self, setting: Setting) -> Result<MoyoDataset, MoyoError> {
+        self.build_dataset(setting, None, true)
+    }
+
+    /// Create a new [`PrimitiveDataset`] in `setting` without redoing the symmetry search.
+    pub fn primitive_dataset(&self, setting: Setting) -> Result<PrimitiveDataset, MoyoError> {
+        let prim_cell = &self.prim_cell;
+        let symmetry_search = &self.symmetry_search;
+
+        let space_group = self.identify(setting, None)?;
         let std_cell = StandardizedCell::new(
             &prim_cell.cell,
             &symmetry_search.operations,
             &symmetry_search.permutations,
             &space_group,
-            symprec,
+            self.symprec,
         )?;
-
-        // site symmetry
         let orbits = orbits_in_cell(
             prim_cell.cell.num_atoms(),
             &symmetry_search.permutations,
             &prim_cell.site_mapping,
         );
+        let wyckoffs = self.wyckoffs_in_cell(&std_cell)?;
+
+        // (prim_std_linear, prim_std_origin_shift) = (prim_cell.linear^-1, 0) * std_cell.prim_transformation
+        let prim_cell_linear_inv = prim_cell.linear.map(|e| e as f64).try_inverse().unwrap();
+        let prim_std_linear = prim_cell_linear_inv * std_cell.prim_transformation.linear_as_f64();
+        let prim_std_origin_shift =
+            prim_cell_linear_inv * std_cell.prim_transformation.origin_shift;
+
+        Ok(PrimitiveDataset {
+            // Space-group type
+            number: space_group.number,
+            hall_number: space_group.hall_number,
+            // Symmetry operations in the input cell
+            operations: operations_in_cell(prim_cell, &symmetry_search.operations),
+            // Site symmetry
+            orbits,
+            wyckoffs: wyckoffs.iter().map(|w| w.letter).collect(),
+            // Primitive standardized cell
+            prim_std_cell: self.cell.with_element_symbols_of(std_cell.prim_cell),
+            prim_std_linear,
+            prim_std_origin_shift,
+            // Final parameters
+            symprec: self.symprec,
+            angle_tolerance: self.angle_tolerance,
+        })
+    }
+
+    /// Identify the space-group type in `setting`, overriding its origin choice by `origin_choice` if given.
+    fn identify(
+        &self,
+        setting: Setting,
+        origin_choice: Option<OriginChoice>,
+    ) -> Result<SpaceGroup, MoyoError> {
+        let operations = &self.symmetry_search.operations;
+        let epsilon = self.symprec / self.prim_cell.cell.lattice.volume().powf(1.0 / 3.0);
+        let space_group = SpaceGroup::new(operations, setting, epsilon)?;
+        if let Some(origin_choice) = origin_choice {
+            let hall_number = origin_choice
+                .hall_number(space_group.hall_number)
+                .ok_or(MoyoError::SpaceGroupTypeIdentificationError)?;
+            if hall_number != space_group.hall_number {
+                return SpaceGroup::new(operations, Setting::HallNumber(hall_number), epsilon);
+            }
+        }
+        Ok(space_group)
+    }
+
+    /// Return Wyckoff positions of sites in the input cell from those in `std_cell`.
+    fn wyckoffs_in_cell(
+        &self,
+        std_cell: &StandardizedCell,
+    ) -> Result<Vec<WyckoffPosition>, MoyoError> {
         // StandardizedCell.prim_cell and prim_cell have the same site order
-        let mapping_std_prim = prim_cell.site_mapping.clone();
-        let mut std_prim_wyckoffs = vec![None; prim_cell.cell.num_atoms()];
+        let mut std_prim_wyckoffs = vec![None; self.prim_cell.cell.num_atoms()];
         for (i, wyckoff) in std_cell.wyckoffs.iter().enumerate() {
             let j = std_cell.site_mapping[i];
             if std_prim_wyckoffs[j].is_none() {
                 std_prim_wyckoffs[j] = Some(wyckoff.clone());
             }
         }
-        let wyckoffs: Option<Vec<_>> = mapping_std_prim
+        self.prim_cell
+            .site_mapping
             .iter()
             .map(|&i| std_prim_wyckoffs[i].clone())
-            .collect();
-        let wyckoffs = wyckoffs.ok_or(MoyoError::WyckoffPositionAssignmentError)?;
+            .collect::<Option<Vec<_>>>()
+            .ok_or(MoyoError::WyckoffPositionAssignmentError)
+    }
+
+    fn build_dataset(
+        &self,
+        setting: Setting,
+        origin_choice: Option<OriginChoice>,
+        standardize_rotation: bool,
+    ) -> Result<MoyoDataset, MoyoError> {
+        let cell = &self.cell;
+        let prim_cell = &self.prim_cell;
+        let symmetry_search = &self.symmetry_search;
+        let symprec = self.symprec;
+        let angle_tolerance = self.angle_tolerance;
+        let operations = operations_in_cell(prim_cell, &symmetry_search.operations);
+
+        // Space-group type identification
+        let space_group = self.identify(setting, origin_choice)?;
+
+        // Standardized cell
+        let mut std_cell = StandardizedCell::new(
+            &prim_cell.cell,
+            &symmetry_search.operations,
+            &symmetry_search.permutations,
+            &space_group,
+            symprec,
+        )?;
+
+        if !standardize_rotation {
+            // Undo the rigid rotation applied in symmetrizing the lattice
+            let rotation_matrix_inv = std_cell.rotation_matrix.transpose();
+            std_cell.cell = std_cell.cell.rotate(&rotation_matrix_inv);
+            std_cell.prim_cell = std_cell.prim_cell.rotate(&rotation_matrix_inv);
+            std_cell.symmetrized_lattice =
+                std_cell.symmetrized_lattice.rotate(&rotation_matrix_inv);
+            std_cell.rotation_matrix = Matrix3::identity();
+        }
+
+        // site symmetry
+        let orbits = orbits_in_cell(
+            prim_cell.cell.num_atoms(),
+            &symmetry_search.permutations,
+            &prim_cell.site_mapping,
+        );
+        // StandardizedCell.prim_cell and prim_cell have the same site order
+        let mapping_std_prim = prim_cell.site_mapping.clone();
+        let wyckoffs = self.wyckoffs_in_cell(&std_cell)?;
 
         // cell <-(prim_cell.linear, 0)- prim_cell.cell -(std_cell.transformation)-> std_cell.cell
         // (std_linear, std_origin_shift) = (prim_cell.linear^-1, 0) * std_cell.transformation
@@ -204,22 +1692,51 @@ impl MoyoDataset {
         let prim_std_origin_shift =
             prim_cell_linear_inv * std_cell.prim_transformation.origin_shift;
 
-        Ok(Self {
+        // (reduction, primitive, prim_reduction) compose to prim_cell.linear^-1
+        let reduction_linear = prim_cell.reduced_linear.map(|e| e as f64);
+        let prim_reduction_linear = prim_cell.prim_reduced_linear.map(|e| e as f64);
+        let transformation_chain = TransformationChain {
+            reduction_linear,
+            primitive_linear: reduction_linear.try_inverse().unwrap()
+                * prim_cell_linear_inv
+                * prim_reduction_linear.try_inverse().unwrap(),
+            prim_reduction_linear,
+            standardization_linear: std_cell.prim_transformation.linear_as_f64(),
+            standardization_origin_shift: std_cell.prim_transformation.origin_shift,
+            centering_linear: hall_symbol_entry(space_group.hall_number)
+                .unwrap()
+                .centering
+                .linear()
+                .map(|e| e as f64),
+        };
+
+        let arithmetic_entry = arithmetic_crystal_class_entry(
+            hall_symbol_entry(space_group.hall_number)
+                .unwrap()
+                .arithmetic_number,
+        );
+
+        Ok(MoyoDataset {
             // Space-group type
             number: space_group.number,
             hall_number: space_group.hall_number,
+            arithmetic_number: arithmetic_entry.arithmetic_number,
+            arithmetic_symbol: arithmetic_entry.symbol.to_string(),
             // Symmetry operations in the input cell
             operations,
             // Standardized cell
-            std_cell: std_cell.cell,
+            std_cell: cell.with_element_symbols_of(std_cell.cell),
             std_linear,
             std_origin_shift,
+            std_volume_ratio: std_linear.determinant(),
             std_rotation_matrix: std_cell.rotation_matrix,
+            ideal_std_lattice: std_cell.symmetrized_lattice,
             // Primitive standardized cell
-            prim_std_cell: std_cell.prim_cell,
+            prim_std_cell: cell.with_element_symbols_of(std_cell.prim_cell),
             prim_std_linear,
             prim_std_origin_shift,
             mapping_std_prim,
+            std_to_prim_std: std_cell.site_mapping,
             // Site symmetry
             orbits,
             wyckoffs: wyckoffs.iter().map(|w| w.letter).collect(),
@@ -230,8 +1747,197 @@ impl MoyoDataset {
             // Final parameters
             symprec,
             angle_tolerance,
+            tolerance_adjusted: self.tolerance_adjusted,
+            iterations: self.iterations,
+            transformation_chain,
+            cayley_table: OnceLock::new(),
         })
     }
+}
+
+/// Analyzer of a cell under interactive editing, which caches the symmetry search until the cell is modified.
+///
+/// ```
+/// use nalgebra::{matrix, vector};
+/// use moyo::StructureAnalyzer;
+/// use moyo::base::{AngleTolerance, Cell, Lattice};
+/// use moyo::data::Setting;
+///
+/// let cell = Cell::new(
+///     Lattice::new(matrix![1.0, 0.0, 0.0; 0.0, 1.0, 0.0; 0.0, 0.0, 1.0]),
+///     vec![vector![0.0, 0.0, 0.0]],
+///     vec![0],
+/// );
+/// let mut analyzer = StructureAnalyzer::new(cell, 1e-4, AngleTolerance::Default, Setting::Standard);
+/// assert_eq!(analyzer.dataset().unwrap().number, 221);  // Pm-3m
+/// analyzer.add_atom(vector![0.5, 0.5, 0.5], 1);
+/// assert_eq!(analyzer.dataset().unwrap().number, 221);  // CsCl-type
+/// analyzer.add_atom(vector![0.5, 0.5, 0.0], 2);
+/// assert_eq!(analyzer.dataset().unwrap().number, 123);  // P4/mmm
+/// ```
+#[derive(Debug)]
+pub struct StructureAnalyzer {
+    cell: Cell,
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+    setting: Setting,
+    /// Symmetry search of `cell`, which is invalidated by editing `cell`
+    context: Option<SymmetryContext>,
+    /// Lattice reductions and Bravais groups, which are kept across edits because they do not depend on positions
+    lattice_cache: LatticeCache,
+}
+
+impl StructureAnalyzer {
+    pub fn new(
+        cell: Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        setting: Setting,
+    ) -> Self {
+        Self {
+            cell,
+            symprec,
+            angle_tolerance,
+            setting,
+            context: None,
+            lattice_cache: LatticeCache::default(),
+        }
+    }
+
+    /// Return the current cell.
+    pub fn cell(&self) -> &Cell {
+        &self.cell
+    }
+
+    /// Add a site with fractional coordinates `position` and atomic specie `number`.
+    /// The lattice is kept, and the cached symmetry search is invalidated except for lattice reductions and Bravais groups.
+    pub fn add_atom(&mut self, position: Position, number: AtomicSpecie) {
+        self.cell.positions.push(position);
+        self.cell.numbers.push(number);
+        self.context = None;
+    }
+
+    /// Remove the `index`th site. Panics if `index` is out of range.
+    /// The lattice is kept, and the cached symmetry search is invalidated except for lattice reductions and Bravais groups.
+    pub fn remove_atom(&mut self, index: usize) {
+        self.cell.positions.remove(index);
+        self.cell.numbers.remove(index);
+        self.context = None;
+    }
+
+    /// Return a [`MoyoDataset`] of the current cell.
+    /// The symmetry search is redone only if the cell has been edited since the last call, and then only its position-dependent steps are recomputed unless the primitive lattice changes.
+    pub fn dataset(&mut self) -> Result<MoyoDataset, MoyoError> {
+        let context = match self.context.take() {
+            Some(context) => context,
+            None => SymmetryContext::with_cache(
+                &self.cell,
+                self.symprec,
+                self.angle_tolerance,
+                None,
+                &mut |_, _| {},
+                &mut self.lattice_cache,
+            )?,
+        };
+        let dataset = context.dataset(self.setting);
+        self.context = Some(context);
+        dataset
+    }
+}
+
+#[derive(Debug)]
+/// A dataset following field names of Spglib's `SpglibDataset`, created by [`MoyoDataset::to_spglib_like`].
+pub struct SpglibDataset {
+    /// Space group number.
+    pub spacegroup_number: Number,
+    /// Hall symbol number.
+    pub hall_number: HallNumber,
+    /// Hermann-Mauguin symbol in short notation without spaces, e.g. "P4_2/mnm".
+    pub international_symbol: String,
+    /// Hall symbol.
+    pub hall_symbol: String,
+    /// Setting of the space group, e.g. origin choice or unique axis.
+    pub choice: String,
+    /// Linear part of transformation `x_std = transformation_matrix * x + origin_shift` from the input cell to the standardized cell.
+    pub transformation_matrix: Matrix3<f64>,
+    /// Origin shift of transformation `x_std = transformation_matrix * x + origin_shift` from the input cell to the standardized cell.
+    pub origin_shift: OriginShift,
+    /// Rotation parts of symmetry operations in the input cell.
+    pub rotations: Vec<Rotation>,
+    /// Translation parts of symmetry operations in the input cell.
+    pub translations: Vec<Translation>,
+    /// Wyckoff letters for each site in the input cell.
+    pub wyckoffs: Vec<char>,
+    /// Site symmetry symbols for each site in the input cell.
+    pub site_symmetry_symbols: Vec<String>,
+    /// Same as `crystallographic_orbits`, since moyo does not distinguish them.
+    pub equivalent_atoms: Vec<usize>,
+    /// The `i`th atom in the input cell is equivalent to the `crystallographic_orbits[i]`th atom in the input cell.
+    pub crystallographic_orbits: Vec<usize>,
+    /// Lattice of the standardized cell.
+    pub std_lattice: Lattice,
+    /// Atomic species of the standardized cell.
+    pub std_types: Vec<AtomicSpecie>,
+    /// Fractional coordinates of the standardized cell.
+    pub std_positions: Vec<Position>,
+    /// Rigid rotation
+    pub std_rotation_matrix: Matrix3<f64>,
+    /// Hermann-Mauguin symbol of the point group.
+    pub pointgroup_symbol: String,
+}
+
+#[derive(Debug)]
+/// A dataset containing symmetry information of the input crystal structure without the conventional standardized cell.
+pub struct PrimitiveDataset {
+    // ------------------------------------------------------------------------
+    // Space-group type
+    // ------------------------------------------------------------------------
+    /// Space group number.
+    pub number: Number,
+    /// Hall symbol number.
+    pub hall_number: HallNumber,
+    // ------------------------------------------------------------------------
+    // Symmetry operations in the input cell
+    // ------------------------------------------------------------------------
+    /// Symmetry operations in the input cell.
+    pub operations: Operations,
+    // ------------------------------------------------------------------------
+    // Site symmetry
+    // ------------------------------------------------------------------------
+    /// The `i`th atom in the input cell is equivalent to the `orbits[i]`th atom in the **input** cell.
+    pub orbits: Vec<usize>,
+    /// Wyckoff letters for each site in the input cell.
+    pub wyckoffs: Vec<char>,
+    // ------------------------------------------------------------------------
+    // Primitive standardized cell
+    // ------------------------------------------------------------------------
+    /// Primitive standardized cell
+    pub prim_std_cell: Cell,
+    /// Linear part of transformation from the input cell to the primitive standardized cell.
+    pub prim_std_linear: Matrix3<f64>,
+    /// Origin shift of transformation from the input cell to the primitive standardized cell.
+    pub prim_std_origin_shift: OriginShift,
+    // ------------------------------------------------------------------------
+    // Final parameters
+    // ------------------------------------------------------------------------
+    /// Actually used `symprec` in iterative symmetry search.
+    pub symprec: f64,
+    /// Actually used `angle_tolerance` in iterative symmetry search.
+    pub angle_tolerance: AngleTolerance,
+}
+
+impl PrimitiveDataset {
+    /// Create a new [`PrimitiveDataset`] from the input cell, `cell`.
+    /// Only the symmetry search, the space-group type identification, and the standardization of the primitive cell are performed.
+    /// Note that the conventional standardized cell is still constructed internally to assign Wyckoff positions, but it is not rotated, strained, or matched to the input sites.
+    pub fn new(
+        cell: &Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        setting: Setting,
+    ) -> Result<Self, MoyoError> {
+        SymmetryContext::new(cell, symprec, angle_tolerance)?.primitive_dataset(setting)
+    }
 
     /// Return the number of symmetry operations in the input cell.
     pub fn num_operations(&self) -> usize {
@@ -287,15 +1993,36 @@ pub struct MoyoMagneticDataset<M: MagneticMoment> {
     pub angle_tolerance: AngleTolerance,
     /// Actually used `mag_symprec` in iterative symmetry search.
     pub mag_symprec: f64,
+    /// How rotations act on magnetic moments.
+    pub action: RotationMagneticMomentAction,
 }
 
 impl<M: MagneticMoment> MoyoMagneticDataset<M> {
+    /// Create a new [`MoyoMagneticDataset`] from the input magnetic cell, `magnetic_cell`.
+    /// `mag_symprec` is an absolute tolerance for magnetic moments, and `symprec` is used if it is `None`.
     pub fn new(
         magnetic_cell: &MagneticCell<M>,
         symprec: f64,
         angle_tolerance: AngleTolerance,
         mag_symprec: Option<f64>,
         action: RotationMagneticMomentAction,
+    ) -> Result<Self, MoyoError> {
+        Self::with_mag_symprec(
+            magnetic_cell,
+            symprec,
+            angle_tolerance,
+            mag_symprec.map(MagSymprec::Absolute),
+            action,
+        )
+    }
+
+    /// Same as [`MoyoMagneticDataset::new`] but `mag_symprec` may be given relative to the largest magnitude of magnetic moments in `magnetic_cell`.
+    pub fn with_mag_symprec(
+        magnetic_cell: &MagneticCell<M>,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        mag_symprec: Option<MagSymprec>,
+        action: RotationMagneticMomentAction,
     ) -> Result<Self, MoyoError> {
         let (prim_mag_cell, magnetic_symmetry_search, symprec, angle_tolerance, mag_symprec) =
             iterative_magnetic_symmetry_search(
@@ -305,10 +2032,12 @@ impl<M: MagneticMoment> MoyoMagneticDataset<M> {
                 mag_symprec,
                 action,
             )?;
-        let magnetic_operations = magnetic_operations_in_magnetic_cell(
+        let mut magnetic_operations = magnetic_operations_in_magnetic_cell(
             &prim_mag_cell,
             &magnetic_symmetry_search.magnetic_operations,
         );
+        // Canonical order independent of the traversal in the symmetry search
+        sort_magnetic_operations(&mut magnetic_operations);
 
         // Magnetic space-group type identification
         let epsilon = symprec
@@ -377,6 +2106,7 @@ impl<M: MagneticMoment> MoyoMagneticDataset<M> {
             symprec,
             angle_tolerance,
             mag_symprec,
+            action,
         })
     }
 
@@ -384,4 +2114,492 @@ impl<M: MagneticMoment> MoyoMagneticDataset<M> {
     pub fn num_magnetic_operations(&self) -> usize {
         self.magnetic_operations.len()
     }
+
+    /// Return the construction type (type-I, II, III, or IV) of the magnetic space group.
+    pub fn magnetic_type(&self) -> ConstructType {
+        get_magnetic_space_group_type(self.uni_number)
+            .unwrap()
+            .construct_type
+    }
+
+    /// Return true if the magnetic space group is a gray group (type-II), which contains time reversal itself as an operation.
+    /// This is the case for paramagnetic or nonmagnetic structures.
+    pub fn is_gray_group(&self) -> bool {
+        self.magnetic_type() == ConstructType::Type2
+    }
+
+    /// Map per-site magnetic moments of the input magnetic cell onto sites of the standardized magnetic cell, `std_mag_cell`.
+    /// Each moment is rotated by `std_rotation_matrix` with `action`, and a site in `std_mag_cell` takes the moment of a site in the input magnetic cell mapped to the same site in `prim_std_mag_cell`.
+    /// Unlike `std_mag_cell.magnetic_moments`, the moments are not symmetrized.
+    pub fn transform_moments_to_std(&self, moments: &[M]) -> Vec<M> {
+        // x_input = std_linear * x_std + std_origin_shift = prim_std_linear * x_prim_std + prim_std_origin_shift
+        let std_linear_inv = self.std_linear.try_inverse().unwrap();
+        let prim_std_cell = &self.prim_std_mag_cell.cell;
+        let prim_positions_in_std = prim_std_cell
+            .positions
+            .iter()
+            .map(|position| {
+                std_linear_inv
+                    * (self.prim_std_linear * position + self.prim_std_origin_shift
+                        - self.std_origin_shift)
+            })
+            .collect::<Vec<_>>();
+
+        let std_cell = &self.std_mag_cell.cell;
+        std_cell
+            .positions
+            .iter()
+            .zip(std_cell.numbers.iter())
+            .map(|(position, number)| {
+                let prim_site = (0..prim_std_cell.num_atoms())
+                    .find(|&k| {
+                        let diff = (prim_positions_in_std[k] - position).map(|e| e - e.round());
+                        prim_std_cell.numbers[k] == *number
+                            && std_cell.lattice.cartesian_coords(&diff).norm() < self.symprec
+                    })
+                    .unwrap();
+                let input_site = self
+                    .mapping_std_prim
+                    .iter()
+                    .position(|&k| k == prim_site)
+                    .unwrap();
+                moments[input_site].act_rotation(&self.std_rotation_matrix, self.action)
+            })
+            .collect()
+    }
+
+    /// Return magnetic symmetry operations sorted by rotation, time reversal, and then translation.
+    /// `magnetic_operations` is already in this order unless it is modified.
+    pub fn sorted_magnetic_operations(&self) -> MagneticOperations {
+        let mut magnetic_operations = self.magnetic_operations.clone();
+        sort_magnetic_operations(&mut magnetic_operations);
+        magnetic_operations
+    }
+
+    /// Return magnetic symmetry operations decomposed into left cosets of the maximal space subgroup (operations without time reversal).
+    /// The first coset is the maximal space subgroup itself. For type-I magnetic space groups, only one coset is returned.
+    pub fn cosets(&self) -> Vec<MagneticOperations> {
+        let (_, contained) =
+            primitive_maximal_space_subgroup_from_magnetic_space_group(&self.magnetic_operations);
+        let mut subgroup = vec![];
+        let mut coset = vec![];
+        for (mops, is_contained) in self.magnetic_operations.iter().zip(contained) {
+            if is_contained {
+                subgroup.push(mops.clone());
+            } else {
+                coset.push(mops.clone());
+            }
+        }
+
+        if coset.is_empty() {
+            vec![subgroup]
+        } else {
+            vec![subgroup, coset]
+        }
+    }
+
+    /// Return magnetic moments of sites in the standardized magnetic cell, `std_mag_cell`, consistent with the magnetic space group.
+    /// The moments are averaged over the actions of all magnetic symmetry operations, including time reversal, during standardization.
+    /// Thus, each moment lies along the direction allowed by its magnetic site-symmetry group and equivalent sites have related moments.
+    pub fn symmetrized_moments(&self) -> Vec<M> {
+        self.std_mag_cell.magnetic_moments.clone()
+    }
+
+    /// Return the symmetry-allowed form of a time-reversal-odd axial rank-2 tensor in the cartesian coordinates of the input magnetic cell.
+    /// The tensor is averaged as `s * det(R) * R^T * tensor * R` over cartesian rotations `R` of `magnetic_operations`, where `s = -1` for time-reversed operations.
+    pub fn symmetrize_axial_tensor(&self, tensor: Matrix3<f64>) -> Matrix3<f64> {
+        let lattice = input_lattice(
+            &self.std_mag_cell.cell.lattice,
+            &self.std_linear,
+            &self.std_rotation_matrix,
+        );
+        let sum = self
+            .magnetic_operations
+            .iter()
+            .map(|mops| {
+                let rotation = mops.operation.cartesian_rotation(&lattice);
+                let sign = if mops.time_reversal { -1.0 } else { 1.0 };
+                sign * rotation.determinant().round() * rotation.transpose() * tensor * rotation
+            })
+            .sum::<Matrix3<f64>>();
+        sum / (self.num_magnetic_operations() as f64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Result of [`validate_space_group`].
+pub struct SpaceGroupMatch {
+    /// True if the space group number detected with the given `symprec` is the expected one.
+    pub matched: bool,
+    /// Space group number detected with the given `symprec`, or `None` if the symmetry search fails.
+    pub detected: Option<Number>,
+    /// `symprec` with which the expected space group number is detected, or `None` if it is not found in the scanned range.
+    /// If `matched` is true, this is the given `symprec`.
+    pub recovered_symprec: Option<f64>,
+}
+
+/// Maximum denominator of the origin shift in [`MoyoDataset::ita_transformation`]
+const MAX_ITA_DENOMINATOR: i32 = 24;
+/// Maximum number of magnetic sites in [`MoyoDataset::allowed_magnetic_orderings`], which visits 2^n orderings of n sites
+const MAX_MAGNETIC_ORDERING_SITES: usize = 16;
+
+/// Ratio between adjacent `symprec`s scanned in [`validate_space_group`]
+const SYMPREC_SCAN_FACTOR: f64 = 2.0;
+/// Number of `symprec`s scanned on each side of the given one in [`validate_space_group`]
+const SYMPREC_SCAN_STEPS: i32 = 10;
+
+/// Compare the space group number detected for `cell` against `expected`.
+/// If they differ, `symprec` is scanned from the given one on a geometric grid, in order of increasing distance from the given one, to find a `symprec` with which `expected` is detected.
+/// This is intended for validating databases of crystal structures with reference space-group numbers.
+pub fn validate_space_group(
+    cell: &Cell,
+    expected: Number,
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+    setting: Setting,
+) -> SpaceGroupMatch {
+    let detect = |symprec: f64| {
+        MoyoDataset::new(cell, symprec, angle_tolerance, setting)
+            .ok()
+            .map(|dataset| dataset.number)
+    };
+
+    let detected = detect(symprec);
+    if detected == Some(expected) {
+        return SpaceGroupMatch {
+            matched: true,
+            detected,
+            recovered_symprec: Some(symprec),
+        };
+    }
+
+    let recovered_symprec = (1..=SYMPREC_SCAN_STEPS)
+        .flat_map(|step| {
+            let factor = SYMPREC_SCAN_FACTOR.powi(step);
+            [symprec / factor, symprec * factor]
+        })
+        .find(|&trial_symprec| detect(trial_symprec) == Some(expected));
+    SpaceGroupMatch {
+        matched: false,
+        detected,
+        recovered_symprec,
+    }
+}
+
+/// Return whether each of `operations` in the basis of `cell` maps `cell` onto itself within `symprec`.
+/// An operation is regarded as a symmetry if its rotation keeps the metric tensor of the lattice and every site is mapped onto a site of the same specie.
+pub fn check_operations(cell: &Cell, operations: &Operations, symprec: f64) -> Vec<bool> {
+    let metric_tensor = cell.lattice.metric_tensor();
+    let max_length = metric_tensor.diagonal().max().sqrt();
+    operations
+        .iter()
+        .zip(site_permutations(cell, operations, symprec))
+        .map(|(operation, permutation)| {
+            let rotation = operation.rotation.map(|e| e as f64);
+            let metric_diff = rotation.transpose() * metric_tensor * rotation - metric_tensor;
+            metric_diff.abs().max() <= 2.0 * symprec * max_length && permutation.is_some()
+        })
+        .collect()
+}
+
+/// Return permutations of sites in `cell` by each of `operations`, or `None` if an operation does not map sites onto sites of the same specie.
+/// The `k`th operation moves the `i`th site to the `permutation.apply(i)`th site.
+fn site_permutations(
+    cell: &Cell,
+    operations: &Operations,
+    symprec: f64,
+) -> Vec<Option<Permutation>> {
+    // Sites are compared in a reduced cell, where the periodic kd-tree is valid
+    let reduced_trans_mat = match cell.lattice.minkowski_reduce() {
+        Ok((_, trans_mat)) => trans_mat,
+        Err(_) => Matrix3::identity(),
+    };
+    let transformation = UnimodularTransformation::from_linear(reduced_trans_mat);
+    let reduced_cell = transformation.transform_cell(cell);
+    let pkdtree = PeriodicKdTree::new(&reduced_cell, symprec);
+
+    operations
+        .iter()
+        .map(|operation| {
+            let reduced_operation = transformation.transform_operation(operation);
+            let reduced_rotation = reduced_operation.rotation.map(|e| e as f64);
+            let new_positions = reduced_cell
+                .positions
+                .iter()
+                .map(|position| reduced_rotation * position + reduced_operation.translation)
+                .collect::<Vec<_>>();
+            solve_correspondence(&pkdtree, &reduced_cell, &new_positions)
+        })
+        .collect()
+}
+
+/// Enumerate symmetry-distinct configurations substituting `num_substituted` sites in the orbit of the `orbit_repr`th site of `cell`.
+/// Substituted sites are labeled by a new atomic specie, one larger than the largest in `cell.numbers`, and the lattice and site order of `cell` are kept.
+/// Two configurations are regarded as equivalent if a symmetry operation of `cell` maps one onto the other, and the lexicographically smallest set of substituted site indices is returned for each class.
+pub fn enumerate_substitutions(
+    cell: &Cell,
+    orbit_repr: usize,
+    num_substituted: usize,
+    symprec: f64,
+) -> Result<Vec<Cell>, MoyoError> {
+    let dataset = MoyoDataset::new(cell, symprec, AngleTolerance::Default, Setting::Spglib)?;
+    let permutations = site_permutations(cell, &dataset.operations, dataset.symprec)
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or(MoyoError::InvalidOperationsError)?;
+    let orbit_sites = (0..cell.num_atoms())
+        .filter(|&i| dataset.orbits[i] == dataset.orbits[orbit_repr])
+        .collect::<Vec<_>>();
+    let new_number = cell.numbers.iter().max().unwrap() + 1;
+
+    let mut substituted_cells = vec![];
+    for sites in orbit_sites.into_iter().combinations(num_substituted) {
+        // Keep only the smallest representative among equivalent configurations
+        let is_representative = permutations.iter().all(|permutation| {
+            let mut images = sites
+                .iter()
+                .map(|&i| permutation.apply(i))
+                .collect::<Vec<_>>();
+            images.sort();
+            images >= sites
+        });
+        if !is_representative {
+            continue;
+        }
+
+        let mut numbers = cell.numbers.clone();
+        for &i in sites.iter() {
+            numbers[i] = new_number;
+        }
+        substituted_cells.push(Cell::new(
+            cell.lattice.clone(),
+            cell.positions.clone(),
+            numbers,
+        ));
+    }
+    Ok(substituted_cells)
+}
+
+/// Create a [`MoyoDataset`] of the sublattice consisting of sites whose atomic species are in `species`.
+/// The lattice of `cell` is kept, and [`MoyoError::EmptySublatticeError`] is returned if no site is selected.
+/// Note that site indices in the returned dataset refer to the filtered cell, not to `cell`.
+pub fn sublattice_dataset(
+    cell: &Cell,
+    species: &[AtomicSpecie],
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+    setting: Setting,
+) -> Result<MoyoDataset, MoyoError> {
+    let (positions, numbers): (Vec<_>, Vec<_>) = cell
+        .positions
+        .iter()
+        .zip(cell.numbers.iter())
+        .filter(|(_, number)| species.contains(number))
+        .map(|(position, &number)| (*position, number))
+        .unzip();
+    if positions.is_empty() {
+        return Err(MoyoError::EmptySublatticeError);
+    }
+    let sublattice =
+        cell.with_element_symbols_of(Cell::new(cell.lattice.clone(), positions, numbers));
+    MoyoDataset::new(&sublattice, symprec, angle_tolerance, setting)
+}
+
+/// Return a wavevector of the commensurate modulation of `cell` relative to its average structure, which is given by relabeling atomic species of `cell` with `average_numbers`.
+/// The wavevector is in fractional coordinates of the reciprocal basis of the primitive lattice of the average structure, with components in [0, 1), and is returned together with that lattice.
+/// The primitive lattice is in the same cartesian coordinates as `cell.lattice`, so `lattice.basis.try_inverse().unwrap().transpose() * q` gives the wavevector in cartesian coordinates without the factor of 2 pi.
+/// If the modulation has several independent wavevectors, the first one found is returned.
+/// Return `None` if the lengths of `average_numbers` and `cell.numbers` differ, the average structure has the same translations as `cell`, or the primitive cell search fails.
+pub fn modulation_wavevector(
+    cell: &Cell,
+    average_numbers: &[AtomicSpecie],
+    symprec: f64,
+) -> Option<(Vector3<f64>, Lattice)> {
+    if average_numbers.len() != cell.num_atoms() {
+        return None;
+    }
+    let average_cell = Cell::new(
+        cell.lattice.clone(),
+        cell.positions.clone(),
+        average_numbers.to_vec(),
+    );
+    let prim_cell = PrimitiveCell::new(cell, symprec).ok()?;
+    let prim_average_cell = PrimitiveCell::new(&average_cell, symprec).ok()?;
+
+    // cell.lattice.basis = prim_cell.cell.lattice.basis * prim_cell.linear, and similarly for the average structure.
+    // Reciprocal lattice vectors of prim_cell with integer coordinates `k` have coordinates `linear_average^-T * linear^T * k` w.r.t. those of prim_average_cell.
+    let linear = prim_cell.linear.map(|e| e as f64);
+    let linear_average = prim_average_cell.linear.map(|e| e as f64);
+    let trans_mat = linear_average.try_inverse()?.transpose() * linear.transpose();
+    let q = (0..3)
+        .map(|i| {
+            trans_mat.column(i).map(|e| {
+                let e = e.rem_euclid(1.);
+                if e > 1. - EPS {
+                    0.
+                } else {
+                    e
+                }
+            })
+        })
+        .find(|q| q.iter().any(|e| e.abs() > EPS))?;
+    Some((q, prim_average_cell.cell.lattice))
+}
+
+/// Pairs of space-group numbers of enantiomorphic space-group types, e.g. P4_1 and P4_3
+pub(crate) const ENANTIOMORPHIC_PAIRS: [(Number, Number); 11] = [
+    (76, 78),
+    (91, 95),
+    (92, 96),
+    (144, 145),
+    (151, 153),
+    (152, 154),
+    (169, 170),
+    (171, 172),
+    (178, 179),
+    (180, 181),
+    (212, 213),
+];
+
+/// Return true if `cell1` and `cell2` are the same crystal structure up to an isometry and a choice of unit cell.
+/// Both cells are standardized with [`Setting::Standard`], and their primitive standardized cells are compared up to automorphisms of the lattice and origin shifts.
+/// Sites are compared by `numbers`, and enantiomorphic structures are regarded as equivalent.
+pub fn are_equivalent(
+    cell1: &Cell,
+    cell2: &Cell,
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+) -> Result<bool, MoyoError> {
+    let dataset1 = MoyoDataset::new(cell1, symprec, angle_tolerance, Setting::Standard)?;
+    let dataset2 = MoyoDataset::new(cell2, symprec, angle_tolerance, Setting::Standard)?;
+    Ok(equivalent_datasets(
+        &dataset1,
+        &dataset2,
+        symprec,
+        angle_tolerance,
+    ))
+}
+
+/// Group `cells` into classes of equivalent structures in the sense of [`are_equivalent`].
+/// The `i`th cell is equivalent to the `representatives[i]`th cell, which is the first cell in its class.
+/// A cell whose symmetry search fails is regarded as its own representative.
+pub fn dedupe_structures(
+    cells: &[Cell],
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+) -> Vec<usize> {
+    let datasets = cells
+        .iter()
+        .map(|cell| MoyoDataset::new(cell, symprec, angle_tolerance, Setting::Standard).ok())
+        .collect::<Vec<_>>();
+
+    let mut representatives: Vec<usize> = vec![];
+    for (i, dataset) in datasets.iter().enumerate() {
+        let representative = dataset.as_ref().and_then(|dataset| {
+            (0..i).filter(|&j| representatives[j] == j).find(|&j| {
+                datasets[j].as_ref().is_some_and(|other| {
+                    equivalent_datasets(other, dataset, symprec, angle_tolerance)
+                })
+            })
+        });
+        representatives.push(representative.unwrap_or(i));
+    }
+    representatives
+}
+
+/// Compare primitive standardized cells of `dataset1` and `dataset2` created with [`Setting::Standard`].
+fn equivalent_datasets(
+    dataset1: &MoyoDataset,
+    dataset2: &MoyoDataset,
+    symprec: f64,
+    angle_tolerance: AngleTolerance,
+) -> bool {
+    let cell1 = &dataset1.prim_std_cell;
+    let cell2 = &dataset2.prim_std_cell;
+    // Enantiomorphic structures belong to either space-group type of an enantiomorphic pair
+    let same_type = dataset1.hall_number == dataset2.hall_number
+        || ENANTIOMORPHIC_PAIRS.iter().any(|&(number1, number2)| {
+            (dataset1.number, dataset2.number) == (number1, number2)
+                || (dataset1.number, dataset2.number) == (number2, number1)
+        });
+    if !same_type
+        || cell1.num_atoms() != cell2.num_atoms()
+        || cell1
+            .numbers
+            .iter()
+            .sorted()
+            .ne(cell2.numbers.iter().sorted())
+    {
+        return false;
+    }
+    // Standardized cells share their orientation
+    if (0..3)
+        .any(|i| (cell1.lattice.basis.column(i) - cell2.lattice.basis.column(i)).norm() > symprec)
+    {
+        return false;
+    }
+
+    // Compare sites in a common reduced basis, where the minimum image is found by rounding
+    let Ok((_, trans_mat)) = cell1.lattice.minkowski_reduce() else {
+        return false;
+    };
+    let transformation = UnimodularTransformation::from_linear(trans_mat);
+    let reduced_cell1 = transformation.transform_cell(cell1);
+    let reduced_cell2 = transformation.transform_cell(cell2);
+    let Ok(rotations) = search_bravais_group(&reduced_cell1.lattice, symprec, angle_tolerance)
+    else {
+        return false;
+    };
+
+    let is_close = |lhs: &Position, rhs: &Position| {
+        let diff = (lhs - rhs).map(|e| e - e.round());
+        reduced_cell1.lattice.cartesian_coords(&diff).norm() < symprec
+    };
+    let pivot = reduced_cell1.numbers[0];
+    rotations.iter().any(|rotation| {
+        let rotated = reduced_cell2
+            .positions
+            .iter()
+            .map(|position| rotation.map(|e| e as f64) * position)
+            .collect::<Vec<_>>();
+        // Map a site of the pivot specie to the first site of `reduced_cell1`
+        (0..reduced_cell2.num_atoms())
+            .filter(|&j| reduced_cell2.numbers[j] == pivot)
+            .any(|j| {
+                let translation = reduced_cell1.positions[0] - rotated[j];
+                rotated
+                    .iter()
+                    .zip(reduced_cell2.numbers.iter())
+                    .all(|(position, number)| {
+                        let mapped = position + translation;
+                        reduced_cell1
+                            .positions
+                            .iter()
+                            .zip(reduced_cell1.numbers.iter())
+                            .any(|(other, other_number)| {
+                                other_number == number && is_close(&mapped, other)
+                            })
+                    })
+            })
+    })
+}
+
+/// Return distinct rotations in `rotations` and their products with the inversion.
+fn with_inversion(rotations: impl Iterator<Item = Rotation>) -> Vec<Rotation> {
+    rotations
+        .flat_map(|rotation| [rotation, -rotation])
+        .unique()
+        .collect()
+}
+
+/// Recover the lattice of the input cell from the standardized one.
+fn input_lattice(
+    std_lattice: &Lattice,
+    std_linear: &Matrix3<f64>,
+    std_rotation_matrix: &Matrix3<f64>,
+) -> Lattice {
+    // std_rotation_matrix * input_basis * std_linear = std_lattice.basis
+    let basis =
+        std_rotation_matrix.transpose() * std_lattice.basis * std_linear.try_inverse().unwrap();
+    Lattice { basis }
 }
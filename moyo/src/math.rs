@@ -3,14 +3,20 @@ mod delaunay;
 mod elementary;
 mod hnf;
 mod integer_system;
+mod lll;
 mod minkowski;
 mod niggli;
+mod rational;
 mod snf;
 
 pub use hnf::HNF;
+pub use integer_system::solve_conjugation;
+pub use rational::Rational;
 pub use snf::SNF;
 
 pub(super) use delaunay::delaunay_reduce;
 pub(super) use integer_system::sylvester3;
+pub(super) use lll::lll_reduce;
 pub(super) use minkowski::{is_minkowski_reduced, minkowski_reduce};
 pub(super) use niggli::{is_niggli_reduced, niggli_reduce};
+pub(super) use rational::gcd;
@@ -61,11 +61,29 @@ pub fn delaunay_reduce(basis: &Matrix3<f64>) -> (Matrix3<f64>, Matrix3<i32>) {
     let mut argsort = (0..7).collect::<Vec<_>>();
     argsort.sort_by(|&i, &j| norms[i].partial_cmp(&norms[j]).unwrap());
 
-    let trans_mat_shortest = Matrix3::<i32>::from_columns(&[
-        basis_candidates[argsort[0]],
-        basis_candidates[argsort[1]],
-        basis_candidates[argsort[2]],
-    ]);
+    // Three shortest vectors may be linearly dependent, e.g. b1, b2, and b1 + b2
+    let mut shortest = vec![];
+    for &i in argsort.iter() {
+        let candidate = basis_candidates[i];
+        let independent = match shortest.len() {
+            0 => true,
+            1 => candidate.cross(&shortest[0]) != Vector3::zeros(),
+            _ => {
+                Matrix3::from_columns(&[shortest[0], shortest[1], candidate])
+                    .map(|e| e as f64)
+                    .determinant()
+                    .round() as i32
+                    != 0
+            }
+        };
+        if independent {
+            shortest.push(candidate);
+        }
+        if shortest.len() == 3 {
+            break;
+        }
+    }
+    let trans_mat_shortest = Matrix3::<i32>::from_columns(&shortest);
     trans_mat *= trans_mat_shortest;
     reduced_basis *= trans_mat_shortest.map(|e| e as f64);
 
@@ -91,6 +91,43 @@ pub fn sylvester3(a: &[Matrix3<i32>], b: &[Matrix3<i32>]) -> Option<Vec<Matrix3<
     }
 }
 
+/// Return a basis of integer matrices `P` satisfying `a[i] * P = P * b[i]` for all `i`, i.e., `P^-1 * a[i] * P = b[i]` if `P` is invertible.
+/// Unimodular integer combinations of the basis conjugate the rotations `a` to `b`, while each basis matrix itself may be singular.
+/// Return `None` if `a` and `b` have different lengths or no nonzero solution exists.
+///
+/// ```
+/// use nalgebra::matrix;
+/// use moyo::math::solve_conjugation;
+///
+/// // Four-fold rotations about the z and x axes
+/// let a = vec![matrix![0, -1, 0; 1, 0, 0; 0, 0, 1]];
+/// let b = vec![matrix![1, 0, 0; 0, 0, -1; 0, 1, 0]];
+/// let basis = solve_conjugation(&a, &b).unwrap();
+/// for p in basis.iter() {
+///     assert_eq!(a[0] * p, p * b[0]);
+/// }
+///
+/// // Find a unimodular conjugator among small combinations of the basis
+/// let p = (0..3_i32.pow(basis.len() as u32))
+///     .map(|mut k| {
+///         basis.iter().fold(nalgebra::Matrix3::<i32>::zeros(), |acc, e| {
+///             let c = k % 3 - 1;
+///             k /= 3;
+///             acc + e * c
+///         })
+///     })
+///     .find(|p| p.map(|e| e as f64).determinant().round().abs() == 1.0)
+///     .unwrap();
+/// let p_inv = p.map(|e| e as f64).try_inverse().unwrap().map(|e| e.round() as i32);
+/// assert_eq!(p_inv * a[0] * p, b[0]);
+/// ```
+pub fn solve_conjugation(a: &[Matrix3<i32>], b: &[Matrix3<i32>]) -> Option<Vec<Matrix3<i32>>> {
+    if a.len() != b.len() {
+        return None;
+    }
+    sylvester3(a, b).filter(|basis| !basis.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use nalgebra::{matrix, vector};
@@ -0,0 +1,118 @@
+use nalgebra::{Matrix3, U3};
+
+use super::elementary::{adding_column_matrix, swapping_column_matrix};
+
+/// basis is column-wise
+/// `delta` should be in (1/4, 1], and 3/4 is a conventional choice
+pub fn lll_reduce(basis: &Matrix3<f64>, delta: f64) -> (Matrix3<f64>, Matrix3<i32>) {
+    let mut reduced_basis = *basis;
+    let mut trans_mat = Matrix3::<i32>::identity();
+
+    let mut k = 1;
+    while k < 3 {
+        // Size reduction
+        for j in (0..k).rev() {
+            let (_, mu) = gram_schmidt(&reduced_basis);
+            let q = mu[(k, j)].round() as i32;
+            if q != 0 {
+                let trans_mat_tmp = adding_column_matrix(U3, j, k, -q);
+                reduced_basis *= trans_mat_tmp.map(|e| e as f64);
+                trans_mat *= trans_mat_tmp;
+            }
+        }
+
+        // Lovasz condition
+        let (orthogonal, mu) = gram_schmidt(&reduced_basis);
+        if orthogonal.column(k).norm_squared()
+            >= (delta - mu[(k, k - 1)].powi(2)) * orthogonal.column(k - 1).norm_squared()
+        {
+            k += 1;
+        } else {
+            let trans_mat_tmp = swapping_column_matrix(U3, k - 1, k);
+            reduced_basis *= trans_mat_tmp.map(|e| e as f64);
+            trans_mat *= trans_mat_tmp;
+            k = (k - 1).max(1);
+        }
+    }
+
+    // Preserve parity
+    if trans_mat.map(|e| e as f64).determinant() < 0. {
+        reduced_basis *= -1.;
+        trans_mat *= -1;
+    }
+
+    (reduced_basis, trans_mat)
+}
+
+/// Return Gram-Schmidt orthogonalized basis (not normalized) and coefficients `mu[(i, j)] = <b_i, b*_j> / <b*_j, b*_j>`
+fn gram_schmidt(basis: &Matrix3<f64>) -> (Matrix3<f64>, Matrix3<f64>) {
+    let mut orthogonal = *basis;
+    let mut mu = Matrix3::<f64>::identity();
+    for i in 0..3 {
+        for j in 0..i {
+            mu[(i, j)] =
+                basis.column(i).dot(&orthogonal.column(j)) / orthogonal.column(j).norm_squared();
+            let projection = mu[(i, j)] * orthogonal.column(j);
+            orthogonal.set_column(i, &(orthogonal.column(i) - projection));
+        }
+    }
+    (orthogonal, mu)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{matrix, Matrix3};
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{gram_schmidt, lll_reduce};
+
+    fn assert_lll_reduced(basis: &Matrix3<f64>, delta: f64) {
+        let (orthogonal, mu) = gram_schmidt(basis);
+        for i in 0..3 {
+            for j in 0..i {
+                assert!(mu[(i, j)].abs() <= 0.5 + 1e-8);
+            }
+        }
+        for k in 1..3 {
+            assert!(
+                orthogonal.column(k).norm_squared()
+                    >= (delta - mu[(k, k - 1)].powi(2)) * orthogonal.column(k - 1).norm_squared()
+                        - 1e-8
+            );
+        }
+    }
+
+    #[test]
+    fn test_lll_small() {
+        let basis = matrix![
+            1.0, 0.0, 0.0;
+            0.0, 1.0, 0.0;
+            5.0, 7.0, 1.0;
+        ];
+        let (reduced_basis, trans_mat) = lll_reduce(&basis, 0.75);
+        assert_eq!(basis * trans_mat.map(|e| e as f64), reduced_basis);
+        assert_eq!(trans_mat.map(|e| e as f64).determinant().round(), 1.0);
+        assert_lll_reduced(&reduced_basis, 0.75);
+        for i in 0..3 {
+            assert!((reduced_basis.column(i).norm() - 1.0).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_lll_random() {
+        let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+        for _ in 0..256 {
+            let basis = Matrix3::<i32>::from_fn(|_, _| rng.gen_range(-8..=8));
+            if basis.map(|e| e as f64).determinant().abs() < 1e-8 {
+                continue;
+            }
+            let basis = basis.map(|e| e as f64);
+            let (reduced_basis, trans_mat) = lll_reduce(&basis, 0.75);
+            assert_eq!(basis * trans_mat.map(|e| e as f64), reduced_basis);
+            assert_eq!(trans_mat.map(|e| e as f64).determinant().round(), 1.0);
+            assert_lll_reduced(&reduced_basis, 0.75);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+
+/// Rational number `numerator / denominator` with a positive denominator in lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    pub numerator: i32,
+    pub denominator: i32,
+}
+
+impl Rational {
+    /// Panics if `denominator` is zero.
+    pub fn new(numerator: i32, denominator: i32) -> Self {
+        if denominator == 0 {
+            panic!("denominator should be nonzero");
+        }
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    /// Return the nearest fraction to `value` with a denominator up to `max_denominator`.
+    /// Denominators are tried in ascending order so that ties are snapped to the simpler fraction.
+    pub fn approximate(value: f64, max_denominator: i32) -> Self {
+        (1..=max_denominator)
+            .map(|denominator| Self::new((value * denominator as f64).round() as i32, denominator))
+            .min_by(|a, b| {
+                (a.to_f64() - value)
+                    .abs()
+                    .total_cmp(&(b.to_f64() - value).abs())
+            })
+            .unwrap()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+pub(crate) fn gcd(a: i32, b: i32) -> i32 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rational;
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(Rational::new(2, -4), Rational::new(-1, 2));
+        assert_eq!(Rational::new(0, 3), Rational::new(0, 1));
+        assert_eq!(Rational::approximate(0.3749999, 24), Rational::new(3, 8));
+        assert_eq!(Rational::approximate(-0.5, 24).to_string(), "-1/2");
+        assert_eq!(Rational::approximate(1.0 + 1e-9, 24).to_string(), "1");
+    }
+}
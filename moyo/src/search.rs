@@ -1,3 +1,4 @@
+mod lattice_cache;
 mod primitive_cell;
 mod primitive_symmetry_search;
 mod solve;
@@ -7,8 +8,12 @@ pub use solve::{
     solve_correspondence, solve_correspondence_naive, PeriodicKdTree, PeriodicNeighbor,
 };
 
-pub(super) use primitive_cell::{PrimitiveCell, PrimitiveMagneticCell};
+pub(crate) use lattice_cache::LatticeCache;
+pub use primitive_cell::{internal_translations, LatticeReduction, PrimitiveCell};
+pub(super) use primitive_cell::{retry_with_delaunay, PrimitiveMagneticCell};
+pub use primitive_symmetry_search::PrimitiveSymmetrySearch;
 pub(super) use primitive_symmetry_search::{
-    magnetic_operations_in_magnetic_cell, operations_in_cell, PrimitiveMagneticSymmetrySearch,
+    magnetic_operations_in_magnetic_cell, operations_in_cell, search_bravais_group,
+    PrimitiveMagneticSymmetrySearch,
 };
 pub(super) use symmetry_search::{iterative_magnetic_symmetry_search, iterative_symmetry_search};
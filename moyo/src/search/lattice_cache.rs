@@ -0,0 +1,126 @@
+use nalgebra::Matrix3;
+
+use super::primitive_cell::LatticeReduction;
+use super::primitive_symmetry_search::search_bravais_group;
+use crate::base::{AngleTolerance, Lattice, MoyoError, Rotations};
+
+/// Maximum number of cached entries, which bounds the memory of long interactive sessions
+const MAX_CACHED_ENTRIES: usize = 64;
+
+type Reduction = Result<(Lattice, Matrix3<i32>), MoyoError>;
+type BravaisGroup = Result<Rotations, MoyoError>;
+
+/// Lattice-dependent results of the symmetry search, which are reused for cells sharing the same lattice.
+/// Entries are keyed by basis vectors (and tolerances), so the cache stays valid when positions or the lattice are edited.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatticeCache {
+    reductions: Vec<(LatticeReduction, Matrix3<f64>, Reduction)>,
+    bravais_groups: Vec<(Matrix3<f64>, f64, AngleTolerance, BravaisGroup)>,
+}
+
+impl LatticeCache {
+    /// Same as [`LatticeReduction::reduce`] but reuse the result for the same lattice.
+    pub fn reduce(&mut self, lattice: &Lattice, reduction: LatticeReduction) -> Reduction {
+        if let Some((_, _, result)) = self
+            .reductions
+            .iter()
+            .find(|(other, basis, _)| *other == reduction && *basis == lattice.basis)
+        {
+            return result.clone();
+        }
+
+        let result = reduction.reduce(lattice);
+        if self.reductions.len() >= MAX_CACHED_ENTRIES {
+            self.reductions.clear();
+        }
+        self.reductions
+            .push((reduction, lattice.basis, result.clone()));
+        result
+    }
+
+    /// Same as [`search_bravais_group`] but reuse the result for the same lattice and tolerances.
+    pub fn bravais_group(
+        &mut self,
+        lattice: &Lattice,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+    ) -> BravaisGroup {
+        if let Some((_, _, _, result)) =
+            self.bravais_groups
+                .iter()
+                .find(|(basis, other_symprec, other_angle_tolerance, _)| {
+                    *basis == lattice.basis
+                        && *other_symprec == symprec
+                        && *other_angle_tolerance == angle_tolerance
+                })
+        {
+            return result.clone();
+        }
+
+        let result = search_bravais_group(lattice, symprec, angle_tolerance);
+        if self.bravais_groups.len() >= MAX_CACHED_ENTRIES {
+            self.bravais_groups.clear();
+        }
+        self.bravais_groups
+            .push((lattice.basis, symprec, angle_tolerance, result.clone()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{matrix, vector};
+
+    use super::LatticeCache;
+    use crate::base::{AngleTolerance, Cell, Lattice};
+    use crate::search::iterative_symmetry_search;
+
+    #[test]
+    fn test_lattice_cache() {
+        let lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 3.0, 0.0;
+            0.0, 0.0, 4.0;
+        ]);
+        let symprec = 1e-4;
+        let angle_tolerance = AngleTolerance::Default;
+        let mut cache = LatticeCache::default();
+
+        let cell = Cell::new(
+            lattice.clone(),
+            vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+            vec![0, 1],
+        );
+        let (_, symmetry_search, ..) = iterative_symmetry_search(
+            &cell,
+            symprec,
+            angle_tolerance,
+            None,
+            &mut |_, _| {},
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(symmetry_search.operations.len(), 16);
+        let num_reductions = cache.reductions.len();
+        let num_bravais_groups = cache.bravais_groups.len();
+
+        // Editing positions without changing the primitive lattice reuses all lattice-dependent results
+        let cell = Cell::new(
+            lattice,
+            vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.3]],
+            vec![0, 1],
+        );
+        let (_, symmetry_search, ..) = iterative_symmetry_search(
+            &cell,
+            symprec,
+            angle_tolerance,
+            None,
+            &mut |_, _| {},
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(symmetry_search.operations.len(), 8);
+        assert_eq!(cache.reductions.len(), num_reductions);
+        assert_eq!(cache.bravais_groups.len(), num_bravais_groups);
+    }
+}
@@ -3,13 +3,14 @@ use std::collections::BTreeMap;
 use log::debug;
 use nalgebra::{Dyn, Matrix3, OMatrix, Vector3, U3};
 
+use super::lattice_cache::LatticeCache;
 use super::solve::{
     pivot_site_indices, solve_correspondence, symmetrize_translation_from_permutation,
     PeriodicKdTree,
 };
 use crate::base::{
-    orbits_from_permutations, Cell, Linear, MagneticCell, MagneticMoment, MoyoError, Permutation,
-    Position, Rotation, Transformation, Translation, UnimodularTransformation, EPS,
+    orbits_from_permutations, Cell, Lattice, Linear, MagneticCell, MagneticMoment, MoyoError,
+    Permutation, Position, Rotation, Transformation, Translation, UnimodularTransformation, EPS,
 };
 use crate::math::HNF;
 
@@ -19,6 +20,10 @@ pub struct PrimitiveCell {
     pub cell: Cell,
     /// Transformation matrix from the **primitive** cell to the input cell
     pub linear: Linear,
+    /// Transformation matrix from the input cell to its reduced cell
+    pub reduced_linear: Linear,
+    /// Transformation matrix from the primitive cell before reduction to `cell`
+    pub prim_reduced_linear: Linear,
     /// Mapping from sites of the input cell to those of the primitive cell (many-to-one).
     /// The `i`th atom in the input cell is equivalent to the `site_mapping[i]`th atom in the **primitive** cell.
     pub site_mapping: Vec<usize>,
@@ -27,76 +32,73 @@ pub struct PrimitiveCell {
     /// Permutations induced by translations in the input cell.
     /// `translations[i]` moves the `k`th site to the `permutations[i].apply(k)`th site.
     pub permutations: Vec<Permutation>,
+    /// Lattice reduction used for the input cell
+    pub reduction: LatticeReduction,
+}
+
+/// Lattice reduction used in searching a primitive cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeReduction {
+    Minkowski,
+    /// Fallback when Minkowski reduction fails
+    Delaunay,
+    /// LLL reduction with `delta = 3/4`, which is numerically stable for highly skewed cells.
+    /// The primitive cell is still Minkowski reduced afterwards for the symmetry search.
+    Lll,
+}
+
+impl LatticeReduction {
+    pub(crate) fn reduce(&self, lattice: &Lattice) -> Result<(Lattice, Matrix3<i32>), MoyoError> {
+        match self {
+            LatticeReduction::Minkowski => lattice.minkowski_reduce(),
+            LatticeReduction::Delaunay => lattice.delaunay_reduce(),
+            LatticeReduction::Lll => Ok(lattice.lll_reduce(0.75)),
+        }
+    }
 }
 
 impl PrimitiveCell {
     /// Return primitive cell and transformation matrix from the primitive cell to the input cell
     /// Possible replacements for spglib/src/primitive.h::prm_get_primitive
     pub fn new(cell: &Cell, symprec: f64) -> Result<Self, MoyoError> {
-        // cell.lattice.basis * reduced_trans_mat = reduced_cell.lattice.basis
-        let (reduced_lattice, reduced_trans_mat) = cell.lattice.minkowski_reduce()?;
-        let reduced_cell =
-            UnimodularTransformation::from_linear(reduced_trans_mat).transform_cell(cell);
+        retry_with_delaunay(|reduction| Self::with_reduction(cell, symprec, reduction))
+    }
 
-        // Check if symprec is sufficiently small
-        let minimum_basis_norm = reduced_lattice
-            .basis
-            .column_iter()
-            .map(|v| v.norm())
-            .reduce(f64::min)
-            .unwrap();
-        let rough_symprec = 2.0 * symprec;
-        if rough_symprec > minimum_basis_norm / 2.0 {
-            debug!(
-                "symprec is too large compared to the basis vectors. Consider reducing symprec."
-            );
-            return Err(MoyoError::TooLargeToleranceError);
-        }
+    /// Same as [`PrimitiveCell::new`] but reuse lattice reductions in `cache`.
+    pub(crate) fn with_cache(
+        cell: &Cell,
+        symprec: f64,
+        cache: &mut LatticeCache,
+    ) -> Result<Self, MoyoError> {
+        retry_with_delaunay(|reduction| {
+            Self::with_reduction_and_cache(cell, symprec, reduction, cache)
+        })
+    }
 
-        // Try possible translations: overlap the `src`the site to the `dst`th site
-        let pkdtree = PeriodicKdTree::new(&reduced_cell, rough_symprec);
-        let pivot_site_indices = pivot_site_indices(&reduced_cell.numbers);
-        let mut permutations_translations_tmp = vec![];
-        let src = pivot_site_indices[0];
-        for dst in pivot_site_indices.iter() {
-            let translation = reduced_cell.positions[*dst] - reduced_cell.positions[src];
-            let new_positions: Vec<Position> = reduced_cell
-                .positions
-                .iter()
-                .map(|pos| pos + translation)
-                .collect();
-
-            // Because the translation may not be optimal to minimize distance between input and acted positions,
-            // use a larger symprec (diameter of a Ball) for finding correspondence
-            if let Some(permutation) = solve_correspondence(&pkdtree, &reduced_cell, &new_positions)
-            {
-                permutations_translations_tmp.push((permutation, translation));
-            }
-        }
+    /// Same as [`PrimitiveCell::new`] but with the given lattice reduction and without fallback.
+    /// `reduction` is used for the input cell and before reducing the primitive cell.
+    /// The returned primitive cell is always Minkowski reduced because the symmetry search assumes it, and [`MoyoError::MinkowskiReductionError`] is returned otherwise.
+    pub fn with_reduction(
+        cell: &Cell,
+        symprec: f64,
+        reduction: LatticeReduction,
+    ) -> Result<Self, MoyoError> {
+        Self::with_reduction_and_cache(cell, symprec, reduction, &mut LatticeCache::default())
+    }
 
-        // Purify translations by permutations
-        let mut translations = vec![];
-        let mut permutations = vec![];
-        for (permutation, rough_translation) in permutations_translations_tmp.iter() {
-            let (translation, distance) = symmetrize_translation_from_permutation(
-                &reduced_cell,
-                permutation,
-                &Rotation::identity(),
-                rough_translation,
-            );
-            if distance < symprec {
-                translations.push(translation);
-                permutations.push(permutation.clone());
-            }
-        }
+    fn with_reduction_and_cache(
+        cell: &Cell,
+        symprec: f64,
+        reduction: LatticeReduction,
+        cache: &mut LatticeCache,
+    ) -> Result<Self, MoyoError> {
+        // cell.lattice.basis * reduced_trans_mat = reduced_cell.lattice.basis
+        let (reduced_lattice, reduced_trans_mat) = cache.reduce(&cell.lattice, reduction)?;
+        let reduced_cell =
+            UnimodularTransformation::from_linear(reduced_trans_mat).transform_cell(cell);
 
-        // Check number of translations
-        let size = translations.len() as i32;
-        if (size == 0) || (reduced_cell.num_atoms() % (size as usize) != 0) {
-            debug!("Failed to properly find translations: {} translations in {} atoms. Consider increasing symprec.", size, reduced_cell.num_atoms());
-            return Err(MoyoError::TooSmallToleranceError);
-        }
-        debug!("Found {} pure translations", size);
+        let (translations, permutations) =
+            search_translations(&reduced_cell, &reduced_lattice, symprec)?;
 
         // Recover a transformation matrix from primitive to input cell
         let trans_mat = if let Some(trans_mat) =
@@ -105,7 +107,7 @@ impl PrimitiveCell {
             trans_mat
         } else {
             debug!("Failed to find a transformation matrix for a primitive cell. Consider increasing symprec.");
-            return Err(MoyoError::TooSmallToleranceError);
+            return Err(MoyoError::TooSmallToleranceError { symprec });
         };
 
         // Primitive cell
@@ -115,7 +117,11 @@ impl PrimitiveCell {
             &translations,
             &permutations,
         );
-        let (_, prim_trans_mat) = primitive_cell.lattice.minkowski_reduce()?;
+        let (prim_reduced_lattice, prim_trans_mat) =
+            cache.reduce(&primitive_cell.lattice, reduction)?;
+        let (_, minkowski_trans_mat) =
+            cache.reduce(&prim_reduced_lattice, LatticeReduction::Minkowski)?;
+        let prim_trans_mat = prim_trans_mat * minkowski_trans_mat;
         let reduced_prim_cell =
             UnimodularTransformation::from_linear(prim_trans_mat).transform_cell(&primitive_cell);
 
@@ -133,16 +139,115 @@ impl PrimitiveCell {
             cell: reduced_prim_cell,
             linear: ((inv_prim_trans_mat * trans_mat).map(|e| e as f64) * inv_reduced_trans_mat)
                 .map(|e| e.round() as i32),
+            reduced_linear: reduced_trans_mat,
+            prim_reduced_linear: prim_trans_mat,
             site_mapping,
             translations: translations
                 .iter()
                 .map(|translation| reduced_trans_mat.map(|e| e as f64) * translation)
                 .collect(),
             permutations,
+            reduction,
         })
     }
 }
 
+/// Call `search` with Minkowski reduction, and retry with Delaunay reduction only if Minkowski reduction fails
+pub(crate) fn retry_with_delaunay<T>(
+    mut search: impl FnMut(LatticeReduction) -> Result<T, MoyoError>,
+) -> Result<T, MoyoError> {
+    match search(LatticeReduction::Minkowski) {
+        Err(MoyoError::MinkowskiReductionError) => {
+            debug!("Minkowski reduction failed. Retry with Delaunay reduction.");
+            search(LatticeReduction::Delaunay)
+        }
+        result => result,
+    }
+}
+
+/// Return pure translations of the input cell up to lattice translations, which include the identity.
+/// Unlike [`PrimitiveCell::new`], a transformation matrix to a primitive cell is not recovered.
+pub fn internal_translations(cell: &Cell, symprec: f64) -> Result<Vec<Translation>, MoyoError> {
+    let with_reduction = |reduction: LatticeReduction| {
+        // cell.lattice.basis * reduced_trans_mat = reduced_cell.lattice.basis
+        let (reduced_lattice, reduced_trans_mat) = reduction.reduce(&cell.lattice)?;
+        let reduced_cell =
+            UnimodularTransformation::from_linear(reduced_trans_mat).transform_cell(cell);
+        let (translations, _) = search_translations(&reduced_cell, &reduced_lattice, symprec)?;
+        Ok(translations
+            .iter()
+            .map(|translation| reduced_trans_mat.map(|e| e as f64) * translation)
+            .collect())
+    };
+    retry_with_delaunay(with_reduction)
+}
+
+/// Return pure translations of the reduced cell and permutations induced by them
+fn search_translations(
+    reduced_cell: &Cell,
+    reduced_lattice: &Lattice,
+    symprec: f64,
+) -> Result<(Vec<Translation>, Vec<Permutation>), MoyoError> {
+    // Check if symprec is sufficiently small
+    let minimum_basis_norm = reduced_lattice
+        .basis
+        .column_iter()
+        .map(|v| v.norm())
+        .reduce(f64::min)
+        .unwrap();
+    let rough_symprec = 2.0 * symprec;
+    if rough_symprec > minimum_basis_norm / 2.0 {
+        debug!("symprec is too large compared to the basis vectors. Consider reducing symprec.");
+        return Err(MoyoError::TooLargeToleranceError { symprec });
+    }
+
+    // Try possible translations: overlap the `src`the site to the `dst`th site
+    let pkdtree = PeriodicKdTree::new(reduced_cell, rough_symprec);
+    let pivot_site_indices = pivot_site_indices(&reduced_cell.numbers);
+    let mut permutations_translations_tmp = vec![];
+    let src = pivot_site_indices[0];
+    for dst in pivot_site_indices.iter() {
+        let translation = reduced_cell.positions[*dst] - reduced_cell.positions[src];
+        let new_positions: Vec<Position> = reduced_cell
+            .positions
+            .iter()
+            .map(|pos| pos + translation)
+            .collect();
+
+        // Because the translation may not be optimal to minimize distance between input and acted positions,
+        // use a larger symprec (diameter of a Ball) for finding correspondence
+        if let Some(permutation) = solve_correspondence(&pkdtree, reduced_cell, &new_positions) {
+            permutations_translations_tmp.push((permutation, translation));
+        }
+    }
+
+    // Purify translations by permutations
+    let mut translations = vec![];
+    let mut permutations = vec![];
+    for (permutation, rough_translation) in permutations_translations_tmp.iter() {
+        let (translation, distance) = symmetrize_translation_from_permutation(
+            reduced_cell,
+            permutation,
+            &Rotation::identity(),
+            rough_translation,
+        );
+        if distance < symprec {
+            translations.push(translation);
+            permutations.push(permutation.clone());
+        }
+    }
+
+    // Check number of translations
+    let size = translations.len() as i32;
+    if (size == 0) || (reduced_cell.num_atoms() % (size as usize) != 0) {
+        debug!("Failed to properly find translations: {} translations in {} atoms. Consider increasing symprec.", size, reduced_cell.num_atoms());
+        return Err(MoyoError::TooSmallToleranceError { symprec });
+    }
+    debug!("Found {} pure translations", size);
+
+    Ok((translations, permutations))
+}
+
 #[derive(Debug)]
 pub struct PrimitiveMagneticCell<M: MagneticMoment> {
     pub magnetic_cell: MagneticCell<M>,
@@ -167,7 +272,9 @@ impl<M: MagneticMoment> PrimitiveMagneticCell<M> {
         // Prepare candidate translations from nonmagnetic cell
         let prim_nonmagnetic_cell = PrimitiveCell::new(&magnetic_cell.cell, symprec)?;
 
-        // Filter translations that keep magnetic moments
+        // Filter translations that keep magnetic moments.
+        // Nonmagnetic sites are skipped because a translation mapping magnetic sites to magnetic ones also maps nonmagnetic sites to nonmagnetic ones.
+        let magnetic_sites = magnetic_cell.magnetic_sites();
         let mut translations = vec![];
         let mut permutations = vec![];
         for (translation, permutation) in prim_nonmagnetic_cell
@@ -175,14 +282,12 @@ impl<M: MagneticMoment> PrimitiveMagneticCell<M> {
             .iter()
             .zip(prim_nonmagnetic_cell.permutations.iter())
         {
-            let new_magnetic_moments = (0..magnetic_cell.cell.num_atoms())
-                .map(|i| magnetic_cell.magnetic_moments[permutation.apply(i)].clone())
-                .collect::<Vec<_>>();
-            let take = magnetic_cell
-                .magnetic_moments
-                .iter()
-                .zip(new_magnetic_moments.iter())
-                .all(|(m1, m2)| m1.is_close(m2, mag_symprec));
+            let take = magnetic_sites.iter().all(|&i| {
+                magnetic_cell.magnetic_moments[i].is_close(
+                    &magnetic_cell.magnetic_moments[permutation.apply(i)],
+                    mag_symprec,
+                )
+            });
             if take {
                 translations.push(*translation);
                 permutations.push(permutation.clone());
@@ -193,7 +298,7 @@ impl<M: MagneticMoment> PrimitiveMagneticCell<M> {
         let size = translations.len() as i32;
         if (size == 0) || (magnetic_cell.cell.num_atoms() % (size as usize) != 0) {
             debug!("Failed to properly find translations: {} translations in {} atoms. Consider increasing symprec.", size, magnetic_cell.cell.num_atoms());
-            return Err(MoyoError::TooSmallToleranceError);
+            return Err(MoyoError::TooSmallToleranceError { symprec });
         }
         debug!("Found {} pure translations", size);
 
@@ -205,7 +310,7 @@ impl<M: MagneticMoment> PrimitiveMagneticCell<M> {
             trans_mat
         } else {
             debug!("Failed to find a transformation matrix for a primitive cell. Consider increasing symprec.");
-            return Err(MoyoError::TooSmallToleranceError);
+            return Err(MoyoError::TooSmallToleranceError { symprec });
         };
 
         // Primitive magnetic cell
@@ -343,15 +448,19 @@ fn site_mapping_from_orbits(orbits: &[usize]) -> Vec<usize> {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+    use std::path::Path;
+
     use nalgebra::{matrix, Matrix3, Vector3};
 
     use crate::base::{
-        Cell, Collinear, Lattice, MagneticCell, MagneticMoment, Transformation, Translation,
+        Cell, Collinear, Lattice, MagneticCell, MagneticMoment, MoyoError, Transformation,
+        Translation,
     };
 
     use super::{
-        site_mapping_from_orbits, transformation_matrix_from_translations, PrimitiveCell,
-        PrimitiveMagneticCell,
+        retry_with_delaunay, site_mapping_from_orbits, transformation_matrix_from_translations,
+        LatticeReduction, PrimitiveCell, PrimitiveMagneticCell,
     };
 
     #[test]
@@ -413,6 +522,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_too_large_symprec() {
+        let cell = Cell::new(
+            Lattice::new(Matrix3::identity()),
+            vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.5, 0.5, 0.5)],
+            vec![0, 1],
+        );
+
+        let symprec = 0.3;
+        match PrimitiveCell::new(&cell, symprec) {
+            Err(err @ MoyoError::TooLargeToleranceError { symprec: actual }) => {
+                assert_eq!(actual, symprec);
+                assert!(err.to_string().contains("consider reducing symprec"));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
     #[test]
     fn test_rhombohedral_lattice() {
         let a = 4.0;
@@ -495,4 +622,92 @@ mod tests {
             magnetic_cell.cell.lattice.basis,
         );
     }
+
+    #[test]
+    fn test_reduction_fallback() {
+        // https://github.com/spglib/moyo/issues/35
+        let symprec = 1e-5;
+        for path in [
+            Path::new("tests/assets/wbm-1-42389.json"),
+            Path::new("tests/assets/wbm-1-42433.json"),
+        ] {
+            let cell: Cell = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+
+            let minkowski =
+                PrimitiveCell::with_reduction(&cell, symprec, LatticeReduction::Minkowski).unwrap();
+            let delaunay =
+                PrimitiveCell::with_reduction(&cell, symprec, LatticeReduction::Delaunay).unwrap();
+            assert_eq!(minkowski.reduction, LatticeReduction::Minkowski);
+            assert_eq!(delaunay.reduction, LatticeReduction::Delaunay);
+            assert_eq!(minkowski.cell.num_atoms(), delaunay.cell.num_atoms());
+            assert_eq!(minkowski.translations.len(), delaunay.translations.len());
+
+            let result = PrimitiveCell::new(&cell, symprec).unwrap();
+            assert_eq!(result.reduction, LatticeReduction::Minkowski);
+
+            // Fall back to Delaunay reduction when Minkowski reduction fails
+            let mut tried = vec![];
+            let fallback = retry_with_delaunay(|reduction| {
+                tried.push(reduction);
+                match reduction {
+                    LatticeReduction::Minkowski => Err(MoyoError::MinkowskiReductionError),
+                    _ => PrimitiveCell::with_reduction(&cell, symprec, reduction),
+                }
+            })
+            .unwrap();
+            assert_eq!(
+                tried,
+                vec![LatticeReduction::Minkowski, LatticeReduction::Delaunay]
+            );
+            assert_eq!(fallback.reduction, LatticeReduction::Delaunay);
+            // The symmetry search requires a Minkowski-reduced primitive cell regardless of the reduction
+            assert!(fallback.cell.lattice.is_minkowski_reduced());
+
+            // Other errors are not retried
+            let mut tried = vec![];
+            let err = retry_with_delaunay(|reduction| {
+                tried.push(reduction);
+                Err::<(), _>(MoyoError::TooSmallToleranceError { symprec })
+            })
+            .unwrap_err();
+            assert_eq!(err, MoyoError::TooSmallToleranceError { symprec });
+            assert_eq!(tried, vec![LatticeReduction::Minkowski]);
+        }
+    }
+
+    #[test]
+    fn test_lll_reduction() {
+        // https://github.com/spglib/moyo/issues/38
+        let symprec = 1e-2;
+        let path = Path::new("tests/assets/wbm-1-29497.json");
+        let cell: Cell = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+
+        let minkowski =
+            PrimitiveCell::with_reduction(&cell, symprec, LatticeReduction::Minkowski).unwrap();
+        let lll = PrimitiveCell::with_reduction(&cell, symprec, LatticeReduction::Lll).unwrap();
+        assert_eq!(lll.reduction, LatticeReduction::Lll);
+        assert_eq!(minkowski.cell.num_atoms(), lll.cell.num_atoms());
+        assert_eq!(minkowski.translations.len(), lll.translations.len());
+        // The primitive cell is Minkowski reduced even if the input cell is LLL reduced
+        assert!(lll.cell.lattice.is_minkowski_reduced());
+        assert_relative_eq!(
+            minkowski.cell.lattice.volume(),
+            lll.cell.lattice.volume(),
+            epsilon = 1e-8
+        );
+
+        // LLL-reduced basis vectors are not much longer than Minkowski-reduced ones
+        let (reduced, trans_mat) = cell.lattice.lll_reduce(0.75);
+        let (minkowski_reduced, _) = cell.lattice.minkowski_reduce().unwrap();
+        assert_relative_eq!(
+            cell.lattice.basis * trans_mat.map(|e| e as f64),
+            reduced.basis,
+            epsilon = 1e-8
+        );
+        for i in 0..3 {
+            assert!(
+                reduced.basis.column(i).norm() < 2.0 * minkowski_reduced.basis.column(2).norm()
+            );
+        }
+    }
 }
@@ -5,6 +5,7 @@ use log::debug;
 use nalgebra::{Matrix3, Vector3};
 
 use super::{
+    lattice_cache::LatticeCache,
     primitive_cell::PrimitiveMagneticCell,
     solve::{
         pivot_site_indices, solve_correspondence, symmetrize_translation_from_permutation,
@@ -13,10 +14,12 @@ use super::{
     PrimitiveCell,
 };
 use crate::base::{
-    traverse, AngleTolerance, Cell, Lattice, MagneticCell, MagneticMoment, MagneticOperation,
-    MagneticOperations, MoyoError, Operation, Operations, Permutation, Rotation,
-    RotationMagneticMomentAction, Rotations, Transformation, EPS,
+    check_closure, project_rotations, traverse, AngleTolerance, Cell, Lattice, MagneticCell,
+    MagneticMoment, MagneticOperation, MagneticOperations, MoyoError, Operation, Operations,
+    Permutation, Rotation, RotationMagneticMomentAction, Rotations, Transformation, EPS,
 };
+use crate::data::{GeometricCrystalClass, PointGroupRepresentative};
+use crate::identify::{geometric_crystal_class_from_rotations, identify_rotation_type};
 
 #[derive(Debug)]
 pub struct PrimitiveSymmetrySearch {
@@ -36,69 +39,157 @@ impl PrimitiveSymmetrySearch {
         symprec: f64,
         angle_tolerance: AngleTolerance,
     ) -> Result<Self, MoyoError> {
-        // Check if symprec is sufficiently small
-        let minimum_basis_norm = primitive_cell.lattice.basis.column(0).norm();
-        let rough_symprec = 2.0 * symprec;
-        if rough_symprec > minimum_basis_norm / 2.0 {
-            debug!(
-                "symprec is too large compared to the basis vectors. Consider reducing symprec."
-            );
-            return Err(MoyoError::TooLargeToleranceError);
+        Self::with_cache(
+            primitive_cell,
+            symprec,
+            angle_tolerance,
+            None,
+            &mut LatticeCache::default(),
+        )
+    }
+
+    /// Same as [`PrimitiveSymmetrySearch::new`] but reuse Bravais groups in `cache`.
+    /// If more than `max_operations` operations are found, the search stops and returns [`MoyoError::TooManyOperationsError`] with the number of operations found so far.
+    pub(crate) fn with_cache(
+        primitive_cell: &Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        max_operations: Option<usize>,
+        cache: &mut LatticeCache,
+    ) -> Result<Self, MoyoError> {
+        check_symprec(primitive_cell, symprec)?;
+        let bravais_group =
+            cache.bravais_group(&primitive_cell.lattice, symprec, angle_tolerance)?;
+        Self::with_rotations(primitive_cell, symprec, &bravais_group, max_operations)
+    }
+
+    /// Same as [`PrimitiveSymmetrySearch::new`] but only try rotations compatible with the given geometric crystal class, `point_group`.
+    /// Return an error early if the lattice does not have enough rotations for `point_group`, or if the found operations belong to another geometric crystal class.
+    pub fn new_with_point_group(
+        primitive_cell: &Cell,
+        symprec: f64,
+        angle_tolerance: AngleTolerance,
+        point_group: GeometricCrystalClass,
+    ) -> Result<Self, MoyoError> {
+        check_symprec(primitive_cell, symprec)?;
+        let bravais_group =
+            search_bravais_group(&primitive_cell.lattice, symprec, angle_tolerance)?;
+
+        // Only keep rotations whose types appear in `point_group`
+        let representative = PointGroupRepresentative::from_geometric_crystal_class(point_group);
+        let rotation_types = traverse(&representative.generators)
+            .iter()
+            .map(identify_rotation_type)
+            .collect::<Vec<_>>();
+        let candidates = bravais_group
+            .into_iter()
+            .filter(|rotation| rotation_types.contains(&identify_rotation_type(rotation)))
+            .collect::<Vec<_>>();
+        for rotation_type in rotation_types.iter() {
+            let required = rotation_types
+                .iter()
+                .filter(|t| *t == rotation_type)
+                .count();
+            let available = candidates
+                .iter()
+                .filter(|rotation| identify_rotation_type(rotation) == *rotation_type)
+                .count();
+            if available < required {
+                debug!(
+                    "The lattice does not have enough rotations of {:?} for {:?}.",
+                    rotation_type, point_group
+                );
+                return Err(MoyoError::PrimitiveSymmetrySearchError);
+            }
+        }
+
+        let symmetry_search = Self::with_rotations(primitive_cell, symprec, &candidates, None)?;
+        let found = geometric_crystal_class_from_rotations(&project_rotations(
+            &symmetry_search.operations,
+        ))?;
+        if found != point_group {
+            debug!("Found {:?} instead of {:?}.", found, point_group);
+            return Err(MoyoError::PrimitiveSymmetrySearchError);
         }
+        Ok(symmetry_search)
+    }
 
+    /// Search symmetry operations whose rotation parts are chosen from `rotations`
+    /// Stop as soon as more than `max_operations` rotations have operations within `symprec`.
+    fn with_rotations(
+        primitive_cell: &Cell,
+        symprec: f64,
+        rotations: &Rotations,
+        max_operations: Option<usize>,
+    ) -> Result<Self, MoyoError> {
         // Search symmetry operations
+        let rough_symprec = 2.0 * symprec;
         let pkdtree = PeriodicKdTree::new(primitive_cell, rough_symprec);
-        let bravais_group =
-            search_bravais_group(&primitive_cell.lattice, symprec, angle_tolerance)?;
         let pivot_site_indices = pivot_site_indices(&primitive_cell.numbers);
-        let mut symmetries_tmp = vec![];
+        let mut num_candidates = 0;
+        let mut operations_and_permutations = vec![];
+        let mut num_found_rotations = 0;
         let src = pivot_site_indices[0];
-        for rotation in bravais_group.iter() {
+        for rotation in rotations.iter() {
             let rotated_positions = primitive_cell
                 .positions
                 .iter()
                 .map(|pos| rotation.map(|e| e as f64) * pos)
                 .collect::<Vec<_>>();
+            let mut found = false;
             for dst in pivot_site_indices.iter() {
                 // Try to overlap the `src`-th site to the `dst`-th site
-                let translation = primitive_cell.positions[*dst] - rotated_positions[src];
+                let rough_translation = primitive_cell.positions[*dst] - rotated_positions[src];
                 let new_positions = rotated_positions
                     .iter()
-                    .map(|pos| pos + translation)
+                    .map(|pos| pos + rough_translation)
                     .collect::<Vec<_>>();
 
                 if let Some(permutation) =
                     solve_correspondence(&pkdtree, primitive_cell, &new_positions)
                 {
-                    symmetries_tmp.push((*rotation, translation, permutation));
+                    num_candidates += 1;
+                    // Purify symmetry operations by permutations
+                    let (translation, distance) = symmetrize_translation_from_permutation(
+                        primitive_cell,
+                        &permutation,
+                        rotation,
+                        &rough_translation,
+                    );
+                    if distance < symprec {
+                        operations_and_permutations
+                            .push((Operation::new(*rotation, translation), permutation));
+                        found = true;
+                    }
                     // Do not break here because there may be multiple translations with the rough tolerance
                 }
             }
+
+            if found {
+                num_found_rotations += 1;
+                if let Some(max_operations) = max_operations {
+                    if num_found_rotations > max_operations {
+                        debug!(
+                            "Stop symmetry search: more than {} operations are found.",
+                            max_operations
+                        );
+                        return Err(MoyoError::TooManyOperationsError {
+                            num_operations: num_found_rotations,
+                        });
+                    }
+                }
+            }
         }
         debug!(
             "Number of symmetry operation candidates: {}",
-            symmetries_tmp.len()
+            num_candidates
         );
 
-        // Purify symmetry operations by permutations
-        let mut operations_and_permutations = vec![];
-        for (rotation, rough_translation, permutation) in symmetries_tmp.iter() {
-            let (translation, distance) = symmetrize_translation_from_permutation(
-                primitive_cell,
-                permutation,
-                rotation,
-                rough_translation,
-            );
-            if distance < symprec {
-                operations_and_permutations
-                    .push((Operation::new(*rotation, translation), permutation.clone()));
-            }
-        }
         if operations_and_permutations.is_empty() {
             debug!(
                 "No symmetry operations are found. Consider increasing symprec and angle_tolerance."
             );
-            return Err(MoyoError::TooSmallToleranceError);
+            return Err(MoyoError::TooSmallToleranceError { symprec });
         }
 
         // Recover operations by group multiplication
@@ -128,12 +219,12 @@ impl PrimitiveSymmetrySearch {
         }
         if operations.len() != operations_and_permutations.len() {
             debug!("Found operations do not form a group. Consider reducing symprec and angle_tolerance.");
-            return Err(MoyoError::TooLargeToleranceError);
+            return Err(MoyoError::ClosureError { symprec });
         }
 
-        if !Self::check_closure(&operations, &primitive_cell.lattice, rough_symprec) {
+        if !check_closure(&operations, &primitive_cell.lattice, rough_symprec) {
             debug!("Some centering translations are missing. Consider reducing symprec and angle_tolerance.");
-            return Err(MoyoError::TooLargeToleranceError);
+            return Err(MoyoError::ClosureError { symprec });
         }
 
         debug!("Order of point group: {}", operations.len());
@@ -142,24 +233,6 @@ impl PrimitiveSymmetrySearch {
             permutations,
         })
     }
-
-    fn check_closure(operations: &Operations, lattice: &Lattice, symprec: f64) -> bool {
-        let mut translations_map = HashMap::new();
-        for operation in operations.iter() {
-            translations_map.insert(operation.rotation, operation.translation);
-        }
-        for ops1 in operations.iter() {
-            for ops2 in operations.iter() {
-                let ops12 = ops1.clone() * ops2.clone();
-                let diff =
-                    (translations_map[&ops12.rotation] - ops12.translation).map(|e| e - e.round());
-                if lattice.cartesian_coords(&diff).norm() > symprec {
-                    return false;
-                }
-            }
-        }
-        true
-    }
 }
 
 #[derive(Debug)]
@@ -191,6 +264,7 @@ impl PrimitiveMagneticSymmetrySearch {
 
         // Find time reversal parts that keep magnetic moments
         let pkdtree = PeriodicKdTree::new(&primitive_magnetic_cell.cell, symprec);
+        let magnetic_sites = primitive_magnetic_cell.magnetic_sites();
         let mut magnetic_operations = vec![];
         let mut permutations = vec![];
         for operation in candidate_operations.iter() {
@@ -205,14 +279,20 @@ impl PrimitiveMagneticSymmetrySearch {
             {
                 let cartesian_rotation =
                     operation.cartesian_rotation(&primitive_magnetic_cell.cell.lattice);
-                let rotated_magnetic_moments = primitive_magnetic_cell
-                    .magnetic_moments
+                // Nonmagnetic sites are skipped because they are mapped to nonmagnetic ones whenever magnetic sites are mapped to magnetic ones
+                let rotated_magnetic_moments = magnetic_sites
                     .iter()
-                    .map(|m| m.act_rotation(&cartesian_rotation, action))
+                    .map(|&i| {
+                        primitive_magnetic_cell.magnetic_moments[i]
+                            .act_rotation(&cartesian_rotation, action)
+                    })
                     .collect::<Vec<_>>();
 
-                let new_magnetic_moments = (0..primitive_magnetic_cell.num_atoms())
-                    .map(|i| primitive_magnetic_cell.magnetic_moments[permutation.apply(i)].clone())
+                let new_magnetic_moments = magnetic_sites
+                    .iter()
+                    .map(|&i| {
+                        primitive_magnetic_cell.magnetic_moments[permutation.apply(i)].clone()
+                    })
                     .collect::<Vec<_>>();
 
                 // Find time_reversal s.t. time_reversal * rotated_magnetic_moments[:] = new_magnetic_moments[:]
@@ -243,7 +323,7 @@ impl PrimitiveMagneticSymmetrySearch {
             symprec,
         ) {
             debug!("Some centering translations are missing. Consider reducing symprec and angle_tolerance.");
-            return Err(MoyoError::TooLargeToleranceError);
+            return Err(MoyoError::ClosureError { symprec });
         }
 
         Ok(Self {
@@ -314,8 +394,19 @@ pub fn magnetic_operations_in_magnetic_cell<M: MagneticMoment>(
     mag_operations
 }
 
+/// Check if symprec is sufficiently small compared to the basis vectors
+fn check_symprec(primitive_cell: &Cell, symprec: f64) -> Result<(), MoyoError> {
+    let minimum_basis_norm = primitive_cell.lattice.basis.column(0).norm();
+    let rough_symprec = 2.0 * symprec;
+    if rough_symprec > minimum_basis_norm / 2.0 {
+        debug!("symprec is too large compared to the basis vectors. Consider reducing symprec.");
+        return Err(MoyoError::TooLargeToleranceError { symprec });
+    }
+    Ok(())
+}
+
 /// Relevant to spglib.c/symmetry.c::get_lattice_symmetry
-fn search_bravais_group(
+pub(crate) fn search_bravais_group(
     minkowski_lattice: &Lattice,
     symprec: f64,
     angle_tolerance: AngleTolerance,
@@ -410,13 +501,13 @@ fn search_bravais_group(
     // 48 for Oh
     if rotations.is_empty() || (48 % rotations.len() != 0) {
         debug!("Found automorphisms for the lattice do not form a group. Consider reducing symprec and angle_tolerance.");
-        return Err(MoyoError::TooLargeToleranceError);
+        return Err(MoyoError::ClosureError { symprec });
     }
 
     let complemented_rotations = traverse(&rotations);
     if complemented_rotations.len() != rotations.len() {
         debug!("Found automorphisms for the lattice do not form a group. Consider reducing symprec and angle_tolerance.");
-        return Err(MoyoError::TooLargeToleranceError);
+        return Err(MoyoError::ClosureError { symprec });
     }
     debug!("Order of Bravais group: {}", complemented_rotations.len());
     Ok(complemented_rotations)
@@ -457,11 +548,95 @@ mod tests {
     use nalgebra::{matrix, Matrix3, Vector3};
     use test_log::test;
 
-    use super::{search_bravais_group, PrimitiveMagneticSymmetrySearch};
+    use super::{search_bravais_group, PrimitiveMagneticSymmetrySearch, PrimitiveSymmetrySearch};
     use crate::base::{
-        AngleTolerance, Collinear, Lattice, MagneticCell, NonCollinear,
+        AngleTolerance, Cell, Collinear, Lattice, MagneticCell, MoyoError, NonCollinear,
         RotationMagneticMomentAction,
     };
+    use crate::data::GeometricCrystalClass;
+    use crate::search::LatticeCache;
+
+    #[test]
+    fn test_search_with_point_group() {
+        let symprec = 1e-4;
+        let angle_tolerance = AngleTolerance::Default;
+
+        // Simple tetragonal lattice
+        let cell = Cell::new(
+            Lattice::new(matrix![
+                1.0, 0.0, 0.0;
+                0.0, 1.0, 0.0;
+                0.0, 0.0, 1.2;
+            ]),
+            vec![Vector3::zeros()],
+            vec![0],
+        );
+        assert!(PrimitiveSymmetrySearch::new_with_point_group(
+            &cell,
+            symprec,
+            angle_tolerance,
+            GeometricCrystalClass::Oh
+        )
+        .is_err());
+        let symmetry_search = PrimitiveSymmetrySearch::new_with_point_group(
+            &cell,
+            symprec,
+            angle_tolerance,
+            GeometricCrystalClass::D4h,
+        )
+        .unwrap();
+        assert_eq!(symmetry_search.operations.len(), 16);
+
+        // Subgroups of the actual point group are rejected
+        assert!(PrimitiveSymmetrySearch::new_with_point_group(
+            &cell,
+            symprec,
+            angle_tolerance,
+            GeometricCrystalClass::C4v
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_search_with_max_operations() {
+        let symprec = 1e-4;
+        let angle_tolerance = AngleTolerance::Default;
+        let mut cache = LatticeCache::default();
+
+        // Simple cubic lattice with 48 operations
+        let cell = Cell::new(
+            Lattice::new(matrix![
+                1.0, 0.0, 0.0;
+                0.0, 1.0, 0.0;
+                0.0, 0.0, 1.0;
+            ]),
+            vec![Vector3::zeros()],
+            vec![0],
+        );
+
+        // Stop as soon as the fifth rotation is found
+        assert_eq!(
+            PrimitiveSymmetrySearch::with_cache(
+                &cell,
+                symprec,
+                angle_tolerance,
+                Some(4),
+                &mut cache
+            )
+            .unwrap_err(),
+            MoyoError::TooManyOperationsError { num_operations: 5 }
+        );
+
+        let symmetry_search = PrimitiveSymmetrySearch::with_cache(
+            &cell,
+            symprec,
+            angle_tolerance,
+            Some(48),
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(symmetry_search.operations.len(), 48);
+    }
 
     #[test]
     fn test_search_bravais_group() {
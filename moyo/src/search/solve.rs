@@ -90,6 +90,24 @@ impl PeriodicKdTree {
             None
         }
     }
+
+    /// Return all neighbors within symprec sorted by distance, including periodic images of the same site.
+    pub fn within(&self, position: &Position) -> Vec<PeriodicNeighbor> {
+        let mut wrapped_position = *position;
+        wrapped_position -= wrapped_position.map(|e| e.floor()); // [0, 1)
+        let cart_coords = self.lattice.cartesian_coords(&wrapped_position);
+        self.kdtree
+            .within::<SquaredEuclidean>(
+                &[cart_coords.x, cart_coords.y, cart_coords.z],
+                self.symprec.powi(2), // squared distance for KdTree
+            )
+            .iter()
+            .map(|entry| PeriodicNeighbor {
+                index: self.indices[entry.item as usize],
+                distance: entry.distance.sqrt(),
+            })
+            .collect()
+    }
 }
 
 /// Choose atomic specie with the smallest occurrence
@@ -145,6 +163,7 @@ pub fn solve_correspondence(
 /// Search permutation such that new_positions\[i\] = reduced_cell.positions\[permutation\[i\]\].
 /// Then, a corresponding symmetry operation moves the i-th site into the permutation\[i\]-th site.
 /// This function takes O(num_atoms^2) time.
+/// Symmetry search always uses [`solve_correspondence`]; this is kept as a reference implementation for tests and benchmarks.
 #[doc(hidden)]
 #[allow(clippy::needless_range_loop)]
 pub fn solve_correspondence_naive(
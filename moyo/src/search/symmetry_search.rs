@@ -1,49 +1,96 @@
+use super::lattice_cache::LatticeCache;
 use super::primitive_cell::{PrimitiveCell, PrimitiveMagneticCell};
 use super::primitive_symmetry_search::{PrimitiveMagneticSymmetrySearch, PrimitiveSymmetrySearch};
+use super::solve::PeriodicKdTree;
 use crate::base::{
-    AngleTolerance, Cell, MagneticCell, MagneticMoment, MagneticSymmetryTolerances, MoyoError,
-    RotationMagneticMomentAction, SymmetryTolerances, ToleranceHandler,
+    AngleTolerance, Cell, MagSymprec, MagneticCell, MagneticMoment, MagneticSymmetryTolerances,
+    MoyoError, RotationMagneticMomentAction, SymmetryTolerances, ToleranceHandler,
+    UnimodularTransformation, EPS,
 };
 
-use log::debug;
+use log::{debug, warn};
 
 const MAX_SYMMETRY_SEARCH_TRIALS: usize = 16;
 const MAX_TOLERANCE_HANDLER_TRIALS: usize = 4;
 
+/// Return the primitive cell, its symmetry operations, actually used tolerances, and the number of tolerance adjustments.
+/// `callback` is called after each trial with the tried `symprec` and whether the trial succeeded.
+/// Lattice reductions and Bravais groups are reused from and stored in `cache`.
+/// If a trial finds more than `max_operations` symmetry operations of `cell`, the search stops without adjusting the tolerances and returns [`MoyoError::TooManyOperationsError`].
+#[allow(clippy::type_complexity)]
 pub fn iterative_symmetry_search(
     cell: &Cell,
     symprec: f64,
     angle_tolerance: AngleTolerance,
-) -> Result<(PrimitiveCell, PrimitiveSymmetrySearch, f64, AngleTolerance), MoyoError> {
+    max_operations: Option<usize>,
+    callback: &mut dyn FnMut(f64, bool),
+    cache: &mut LatticeCache,
+) -> Result<
+    (
+        PrimitiveCell,
+        PrimitiveSymmetrySearch,
+        f64,
+        AngleTolerance,
+        usize,
+    ),
+    MoyoError,
+> {
+    cell.validate()?;
+    warn_too_close_atoms(cell, symprec);
+
     let mut tolerances = SymmetryTolerances {
         symprec,
         angle_tolerance,
     };
+    let mut num_adjustments = 0;
 
     for _ in 0..MAX_TOLERANCE_HANDLER_TRIALS {
         let mut tolerance_handler = ToleranceHandler::new(tolerances);
 
         for _ in 0..MAX_SYMMETRY_SEARCH_TRIALS {
-            match PrimitiveCell::new(cell, tolerance_handler.tolerances.symprec) {
+            let trial_symprec = tolerance_handler.tolerances.symprec;
+            match PrimitiveCell::with_cache(cell, tolerance_handler.tolerances.symprec, cache) {
                 Ok(prim_cell) => {
-                    match PrimitiveSymmetrySearch::new(
+                    // Each operation of the primitive cell yields one operation per pure translation
+                    let num_translations = prim_cell.translations.len();
+                    if max_operations
+                        .is_some_and(|max_operations| num_translations > max_operations)
+                    {
+                        callback(trial_symprec, false);
+                        return Err(MoyoError::TooManyOperationsError {
+                            num_operations: num_translations,
+                        });
+                    }
+                    match PrimitiveSymmetrySearch::with_cache(
                         &prim_cell.cell,
                         tolerance_handler.tolerances.symprec,
                         tolerance_handler.tolerances.angle_tolerance,
+                        max_operations.map(|max_operations| max_operations / num_translations),
+                        cache,
                     ) {
                         Ok(symmetry_search) => {
+                            callback(trial_symprec, true);
                             return Ok((
                                 prim_cell,
                                 symmetry_search,
                                 tolerance_handler.tolerances.symprec,
                                 tolerance_handler.tolerances.angle_tolerance,
+                                num_adjustments,
                             ));
                         }
+                        Err(MoyoError::TooManyOperationsError { num_operations }) => {
+                            callback(trial_symprec, false);
+                            return Err(MoyoError::TooManyOperationsError {
+                                num_operations: num_operations * num_translations,
+                            });
+                        }
                         Err(err) => tolerance_handler.update(err),
                     }
                 }
                 Err(err) => tolerance_handler.update(err),
             }
+            callback(trial_symprec, false);
+            num_adjustments += 1;
         }
 
         // When the maximum number of symmetry search trials is reached, restart ToleranceHandler to try larger strides
@@ -58,7 +105,7 @@ pub fn iterative_magnetic_symmetry_search<M: MagneticMoment>(
     magnetic_cell: &MagneticCell<M>,
     symprec: f64,
     angle_tolerance: AngleTolerance,
-    mag_symprec: Option<f64>,
+    mag_symprec: Option<MagSymprec>,
     action: RotationMagneticMomentAction,
 ) -> Result<
     (
@@ -70,10 +117,15 @@ pub fn iterative_magnetic_symmetry_search<M: MagneticMoment>(
     ),
     MoyoError,
 > {
+    magnetic_cell.cell.validate()?;
+    warn_too_close_atoms(&magnetic_cell.cell, symprec);
+
     let mut tolerances = MagneticSymmetryTolerances {
         symprec,
         angle_tolerance,
-        mag_symprec: mag_symprec.unwrap_or(symprec),
+        mag_symprec: mag_symprec
+            .map(|mag_symprec| mag_symprec.absolute(&magnetic_cell.magnetic_moments))
+            .unwrap_or(symprec),
     };
 
     for _ in 0..MAX_TOLERANCE_HANDLER_TRIALS {
@@ -116,3 +168,126 @@ pub fn iterative_magnetic_symmetry_search<M: MagneticMoment>(
     debug!("Reach the maximum number of symmetry search trials");
     Err(MoyoError::PrimitiveMagneticSymmetrySearchError)
 }
+
+/// Warn if two sites are within `symprec`, which cannot be distinguished in the symmetry search
+fn warn_too_close_atoms(cell: &Cell, symprec: f64) {
+    if let Some((i, j, distance)) = too_close_atoms(cell, symprec) {
+        warn!(
+            "Sites {} and {} are unphysically close (distance={} < symprec={}). Symmetry search results may be meaningless.",
+            i, j, distance, symprec
+        );
+    }
+}
+
+/// Return a pair of sites and their distance if any interatomic distance is shorter than `threshold`.
+fn too_close_atoms(cell: &Cell, threshold: f64) -> Option<(usize, usize, f64)> {
+    let (_, reduced_trans_mat) = cell.lattice.minkowski_reduce().ok()?;
+    let reduced_cell =
+        UnimodularTransformation::from_linear(reduced_trans_mat).transform_cell(cell);
+    let pkdtree = PeriodicKdTree::new(&reduced_cell, threshold);
+    for (i, position) in reduced_cell.positions.iter().enumerate() {
+        for neighbor in pkdtree.within(position) {
+            // Skip the site itself
+            if neighbor.index == i && neighbor.distance < EPS {
+                continue;
+            }
+            return Some((i, neighbor.index, neighbor.distance));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{matrix, vector};
+
+    use super::{iterative_symmetry_search, too_close_atoms};
+    use crate::base::{AngleTolerance, Cell, Lattice, MoyoError};
+    use crate::search::LatticeCache;
+
+    #[test_log::test]
+    fn test_too_close_atoms() {
+        let symprec = 1e-4;
+        let lattice = Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 3.0, 0.0;
+            0.0, 0.0, 3.0;
+        ]);
+
+        let cell = Cell::new(
+            lattice.clone(),
+            vec![vector![0.0, 0.0, 0.0], vector![0.95, 0.0, 0.0]],
+            vec![0, 1],
+        );
+        assert!(too_close_atoms(&cell, symprec).is_none());
+
+        // Two overlapping atoms across the periodic boundary
+        let cell = Cell::new(
+            lattice,
+            vec![vector![0.0, 0.0, 0.0], vector![0.99999, 0.0, 0.0]],
+            vec![0, 1],
+        );
+        let (i, j, distance) = too_close_atoms(&cell, symprec).unwrap();
+        assert_eq!((i.min(j), i.max(j)), (0, 1));
+        assert_relative_eq!(distance, 3e-5, epsilon = 1e-8);
+
+        // Only warned, not errored
+        assert!(iterative_symmetry_search(
+            &cell,
+            symprec,
+            AngleTolerance::Default,
+            None,
+            &mut |_, _| {},
+            &mut LatticeCache::default()
+        )
+        .is_ok());
+    }
+
+    #[test_log::test]
+    fn test_max_operations() {
+        // Face-centered cubic lattice in the conventional cell with 4 pure translations
+        let cell = Cell::new(
+            Lattice::new(matrix![
+                4.0, 0.0, 0.0;
+                0.0, 4.0, 0.0;
+                0.0, 0.0, 4.0;
+            ]),
+            vec![
+                vector![0.0, 0.0, 0.0],
+                vector![0.0, 0.5, 0.5],
+                vector![0.5, 0.0, 0.5],
+                vector![0.5, 0.5, 0.0],
+            ],
+            vec![0, 0, 0, 0],
+        );
+
+        // Stop in the first trial without adjusting the tolerances
+        let mut num_trials = 0;
+        let err = iterative_symmetry_search(
+            &cell,
+            1e-4,
+            AngleTolerance::Default,
+            Some(16),
+            &mut |_, _| num_trials += 1,
+            &mut LatticeCache::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            MoyoError::TooManyOperationsError { num_operations: 20 }
+        );
+        assert_eq!(num_trials, 1);
+
+        // Stop before searching rotations if the pure translations alone exceed the cap
+        let err = iterative_symmetry_search(
+            &cell,
+            1e-4,
+            AngleTolerance::Default,
+            Some(3),
+            &mut |_, _| {},
+            &mut LatticeCache::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, MoyoError::TooManyOperationsError { num_operations: 4 });
+    }
+}
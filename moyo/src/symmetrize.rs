@@ -2,4 +2,4 @@ mod magnetic_standardize;
 mod standardize;
 
 pub(super) use magnetic_standardize::StandardizedMagneticCell;
-pub(super) use standardize::{orbits_in_cell, StandardizedCell};
+pub(super) use standardize::{assign_wyckoff_position, orbits_in_cell, StandardizedCell};
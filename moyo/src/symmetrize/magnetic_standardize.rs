@@ -71,13 +71,16 @@ impl<M: MagneticMoment> StandardizedMagneticCell<M> {
                     .act_rotation(&ref_std_cell.rotation_matrix, action)
             })
             .collect::<Vec<_>>();
+        // Magnetic moments are already rotated, so cartesian rotations should be taken in the rotated lattice.
+        let rotated_lattice = prim_mag_cell
+            .magnetic_cell
+            .cell
+            .lattice
+            .rotate(&ref_std_cell.rotation_matrix);
         let cart_rotations = magnetic_symmetry_search
             .magnetic_operations
             .iter()
-            .map(|mops| {
-                mops.operation
-                    .cartesian_rotation(&prim_mag_cell.magnetic_cell.cell.lattice)
-            })
+            .map(|mops| mops.operation.cartesian_rotation(&rotated_lattice))
             .collect::<Vec<_>>();
         let time_reversals = magnetic_symmetry_search
             .magnetic_operations
@@ -112,7 +115,7 @@ impl<M: MagneticMoment> StandardizedMagneticCell<M> {
             action,
         );
         let prim_std_mag_cell =
-            MagneticCell::from_cell(ref_std_cell.cell.clone(), prim_std_magnetic_moments);
+            MagneticCell::from_cell(ref_std_cell.prim_cell.clone(), prim_std_magnetic_moments);
 
         // To (conventional) standardized magnetic cell
         let refined_prim_mag_cell = ref_std_cell
@@ -1,4 +1,3 @@
-use itertools::iproduct;
 use log::debug;
 use nalgebra::linalg::{Cholesky, QR};
 use nalgebra::{vector, Matrix3, Vector3};
@@ -10,10 +9,9 @@ use crate::base::{
 };
 use crate::data::{
     arithmetic_crystal_class_entry, hall_symbol_entry, iter_wyckoff_positions, HallNumber,
-    HallSymbol, LatticeSystem, WyckoffPosition, WyckoffPositionSpace,
+    HallSymbol, LatticeSystem, WyckoffPosition,
 };
 use crate::identify::SpaceGroup;
-use crate::math::SNF;
 
 pub struct StandardizedCell {
     // ------------------------------------------------------------------------
@@ -30,6 +28,8 @@ pub struct StandardizedCell {
     pub wyckoffs: Vec<WyckoffPosition>,
     /// Transformation from the input primitive cell to the standardized cell.
     pub transformation: Transformation,
+    /// Lattice of `cell` symmetrized by its point group. `cell.lattice` itself is not symmetrized.
+    pub symmetrized_lattice: Lattice,
     /// Rotation matrix to map the lattice of the input primitive cell to that of the standardized cell.
     // ------------------------------------------------------------------------
     // Miscellaneous
@@ -59,6 +59,7 @@ impl StandardizedCell {
             transformation,
             rotation_matrix,
             site_mapping,
+            symmetrized_lattice,
         ) = Self::standardize_and_symmetrize_cell(
             prim_cell,
             prim_operations,
@@ -83,6 +84,7 @@ impl StandardizedCell {
             cell: std_cell,
             wyckoffs,
             transformation,
+            symmetrized_lattice,
             // Miscellaneous
             rotation_matrix,
             site_mapping,
@@ -104,6 +106,7 @@ impl StandardizedCell {
             Transformation,
             Matrix3<f64>,
             Vec<usize>,
+            Lattice,
         ),
         MoyoError,
     > {
@@ -156,7 +159,7 @@ impl StandardizedCell {
         let (std_cell, site_mapping) = conv_trans.transform_cell(&prim_std_cell);
 
         // Symmetrize lattice
-        let (_, rotation_matrix) =
+        let (symmetrized_lattice, rotation_matrix) =
             symmetrize_lattice(&std_cell.lattice, &project_rotations(&conv_std_operations));
 
         Ok((
@@ -171,6 +174,7 @@ impl StandardizedCell {
             ),
             rotation_matrix,
             site_mapping,
+            symmetrized_lattice,
         ))
     }
 
@@ -214,7 +218,7 @@ impl StandardizedCell {
                 continue;
             }
 
-            if let Ok(wyckoff) = assign_wyckoff_position(
+            if let Ok((wyckoff, _)) = assign_wyckoff_position(
                 position,
                 multiplicities[orbit],
                 hall_number,
@@ -275,40 +279,17 @@ fn standardize_triclinic_cell(lattice: &Lattice) -> UnimodularTransformation {
     UnimodularTransformation::from_linear(linear)
 }
 
-fn assign_wyckoff_position(
+/// Return the Wyckoff position of `position` in the standardized cell and variables `y` of its coordinates.
+pub fn assign_wyckoff_position(
     position: &Position,
     multiplicity: usize,
     hall_number: HallNumber,
     lattice: &Lattice,
     symprec: f64,
-) -> Result<WyckoffPosition, MoyoError> {
+) -> Result<(WyckoffPosition, Vector3<f64>), MoyoError> {
     for wyckoff in iter_wyckoff_positions(hall_number, multiplicity) {
-        // Find variable `y` and integers offset `offset` such that
-        //    | lattice * (space.linear * y + space.origin - position - offset) | < symprec.
-        // Let SNF decomposition of space.linear be
-        //    D = L * space.linear * R.
-        // lattice * (space.linear * y + space.origin - position - offset)
-        //    = lattice * (L^-1 * D * R^-1 * y + space.origin - position - offset)
-        //    = lattice * L^-1 * (D * R^-1 * y + L * (space.origin - position - offset))
-
-        //    = lattice * (D * R^-1 * y - L * (offset + position - space.origin)
-        let space = WyckoffPositionSpace::new(wyckoff.coordinates);
-        let snf = SNF::new(&space.linear);
-        for offset in iproduct!(-1..=1, -1..=1, -1..=1) {
-            let offset = Vector3::new(offset.0 as f64, offset.1 as f64, offset.2 as f64);
-            let b = snf.l.map(|e| e as f64) * (offset + position - space.origin);
-            let mut rinvy = Vector3::zeros();
-            for i in 0..3 {
-                if snf.d[(i, i)] != 0 {
-                    rinvy[i] = b[i] / snf.d[(i, i)] as f64;
-                }
-            }
-
-            let y = snf.r.map(|e| e as f64) * rinvy;
-            let diff = space.linear.map(|e| e as f64) * y + space.origin - position - offset;
-            if lattice.cartesian_coords(&diff).norm() < symprec {
-                return Ok(wyckoff.clone());
-            }
+        if let Some(y) = wyckoff.variables(position, lattice, symprec) {
+            return Ok((wyckoff.clone(), y));
         }
     }
     Err(MoyoError::WyckoffPositionAssignmentError)
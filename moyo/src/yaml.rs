@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::base::{AtomicSpecie, Cell};
+use crate::data::{hall_symbol_entry, HallNumber, Number};
+use crate::MoyoDataset;
+
+impl MoyoDataset {
+    /// Return a human-readable YAML summary of the dataset with space-group information, symmetry operations, Wyckoff positions, and the standardized cell.
+    /// Matrices and vectors are written row by row so that summaries are easy to diff.
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(&DatasetSummary::from(self)).unwrap()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DatasetSummary {
+    space_group: SpaceGroupSummary,
+    symprec: f64,
+    operations: Vec<OperationSummary>,
+    sites: Vec<SiteSummary>,
+    std_cell: CellSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SpaceGroupSummary {
+    number: Number,
+    hall_number: HallNumber,
+    hm_symbol: String,
+    hall_symbol: String,
+    setting: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct OperationSummary {
+    rotation: [[i32; 3]; 3],
+    translation: [f64; 3],
+}
+
+/// Site in the input cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SiteSummary {
+    orbit: usize,
+    wyckoff: char,
+    site_symmetry_symbol: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CellSummary {
+    /// Row-wise basis vectors
+    lattice: [[f64; 3]; 3],
+    positions: Vec<[f64; 3]>,
+    numbers: Vec<AtomicSpecie>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    element_symbols: Option<Vec<String>>,
+}
+
+impl From<&MoyoDataset> for DatasetSummary {
+    fn from(dataset: &MoyoDataset) -> Self {
+        let entry = hall_symbol_entry(dataset.hall_number).unwrap();
+        let space_group = SpaceGroupSummary {
+            number: dataset.number,
+            hall_number: dataset.hall_number,
+            hm_symbol: entry.hm_short.to_string(),
+            hall_symbol: entry.hall_symbol.to_string(),
+            setting: entry.setting.to_string(),
+        };
+        let operations = dataset
+            .operations
+            .iter()
+            .map(|operation| OperationSummary {
+                rotation: [0, 1, 2].map(|i| [0, 1, 2].map(|j| operation.rotation[(i, j)])),
+                translation: [0, 1, 2].map(|i| operation.translation[i]),
+            })
+            .collect();
+        let sites = (0..dataset.orbits.len())
+            .map(|i| SiteSummary {
+                orbit: dataset.orbits[i],
+                wyckoff: dataset.wyckoffs[i],
+                site_symmetry_symbol: dataset.site_symmetry_symbols[i].clone(),
+            })
+            .collect();
+
+        Self {
+            space_group,
+            symprec: dataset.symprec,
+            operations,
+            sites,
+            std_cell: CellSummary::from(&dataset.std_cell),
+        }
+    }
+}
+
+impl From<&Cell> for CellSummary {
+    fn from(cell: &Cell) -> Self {
+        Self {
+            lattice: [0, 1, 2].map(|i| [0, 1, 2].map(|j| cell.lattice.basis[(j, i)])),
+            positions: cell
+                .positions
+                .iter()
+                .map(|position| [0, 1, 2].map(|i| position[i]))
+                .collect(),
+            numbers: cell.numbers.clone(),
+            element_symbols: cell.element_symbols().map(|symbols| symbols.to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{matrix, vector};
+
+    use super::DatasetSummary;
+    use crate::base::{AngleTolerance, Cell, Lattice};
+    use crate::data::Setting;
+    use crate::MoyoDataset;
+
+    #[test]
+    fn test_to_yaml() {
+        // CsCl-type structure
+        let cell = Cell::with_elements(
+            Lattice::new(matrix![
+                4.1, 0.0, 0.0;
+                0.0, 4.1, 0.0;
+                0.0, 0.0, 4.1;
+            ]),
+            vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+            vec!["Cs".to_string(), "Cl".to_string()],
+        );
+        let dataset =
+            MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+
+        let yaml = dataset.to_yaml();
+        let summary: DatasetSummary = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(summary, DatasetSummary::from(&dataset));
+        assert_eq!(summary.space_group.number, 221);
+        assert_eq!(summary.operations.len(), 48);
+        assert_eq!(summary.sites.len(), 2);
+        assert_eq!(summary.std_cell.element_symbols.unwrap(), vec!["Cs", "Cl"]);
+    }
+}
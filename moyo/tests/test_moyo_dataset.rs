@@ -7,9 +7,43 @@ use std::fs;
 use std::path::Path;
 use test_log::test;
 
-use moyo::base::{AngleTolerance, Cell, Lattice, Permutation, Rotation, Translation};
-use moyo::data::Setting;
-use moyo::MoyoDataset;
+use moyo::base::{
+    AngleTolerance, Cell, Lattice, MoyoError, Operation, Permutation, Rotation, Symprec,
+    Transformation, Translation,
+};
+use moyo::data::{Centering, GeometricCrystalClass, OriginChoice, RhombohedralSetting, Setting};
+use moyo::{
+    are_equivalent, check_operations, dedupe_structures, enumerate_substitutions,
+    internal_translations, modulation_wavevector, sublattice_dataset, validate_space_group,
+    MoyoDataset, MoyoDatasetBuilder, PrimitiveDataset, StructureAnalyzer, SymmetryContext,
+};
+
+/// Check that `MoyoDataset::input_cell_generators` generate `MoyoDataset::operations`
+fn assert_input_cell_generators(dataset: &MoyoDataset) {
+    let equal_mod1 = |lhs: &Operation, rhs: &Operation| {
+        let mut diff = lhs.translation - rhs.translation;
+        diff -= diff.map(|e| e.round());
+        lhs.rotation == rhs.rotation && diff.iter().all(|e| e.abs() < 1e-6)
+    };
+
+    let generators = dataset.input_cell_generators();
+    let mut group = vec![Operation::identity()];
+    let mut index = 0;
+    while index < group.len() {
+        for generator in generators.iter() {
+            let product = group[index].compose_mod1(generator);
+            if !group.iter().any(|ops| equal_mod1(ops, &product)) {
+                group.push(product);
+            }
+        }
+        index += 1;
+    }
+
+    assert_eq!(group.len(), dataset.num_operations());
+    for operation in dataset.operations.iter() {
+        assert!(group.iter().any(|ops| equal_mod1(ops, operation)));
+    }
+}
 
 /// Sanity-check MoyoDataset
 fn assert_dataset(
@@ -60,6 +94,15 @@ fn assert_dataset(
     assert_eq!(std_dataset.number, dataset.number);
     assert_eq!(std_dataset.hall_number, dataset.hall_number);
 
+    // std_to_prim_std maps sites with the same species
+    assert_eq!(dataset.std_to_prim_std.len(), dataset.std_cell.num_atoms());
+    for (i, &j) in dataset.std_to_prim_std.iter().enumerate() {
+        assert_eq!(
+            dataset.std_cell.numbers[i],
+            dataset.prim_std_cell.numbers[j]
+        );
+    }
+
     // prim_std_cell
     let prim_std_dataset =
         MoyoDataset::new(&dataset.prim_std_cell, symprec, angle_tolerance, setting).unwrap();
@@ -90,6 +133,12 @@ fn assert_dataset(
         dataset.prim_std_cell.lattice.basis,
         epsilon = 1e-8
     );
+    // Volume ratio of the standardized cell to the input cell
+    assert_relative_eq!(
+        dataset.std_volume_ratio,
+        dataset.std_cell.lattice.volume() / cell.lattice.volume(),
+        epsilon = 1e-8
+    );
     // TODO: std_origin_shift
     // TODO: prim_origin_shift
 
@@ -152,8 +201,110 @@ fn test_with_fcc() {
     assert_eq!(dataset.number, 225); // Fm-3m
     assert_eq!(dataset.hall_number, 523);
     assert_eq!(dataset.num_operations(), 48 * 4);
+    assert_eq!(dataset.point_group_operations().len(), 48);
+    assert!(dataset.is_centrosymmetric());
+    assert!(!dataset.is_polar());
+    assert!(!dataset.is_chiral());
+    assert_eq!(dataset.laue_class(), GeometricCrystalClass::Oh);
+    assert_eq!(dataset.patterson_symmetry(), 225);
+    // Translationengleiche subgroups, Pm-3m and Pn-3m by removing the face-centering translations, and isomorphic ones
+    assert_eq!(
+        dataset.maximal_subgroup_space_groups(),
+        vec![139, 166, 202, 209, 216, 221, 224, 225]
+    );
     assert_eq!(dataset.orbits, vec![0, 0, 0, 0]);
     assert_eq!(dataset.wyckoffs, vec!['a', 'a', 'a', 'a']);
+
+    // Four sites in the F-centered conventional cell fold into one primitive site
+    assert_eq!(dataset.std_cell.num_atoms(), 4);
+    assert_eq!(dataset.prim_std_cell.num_atoms(), 1);
+    assert_eq!(dataset.std_to_prim_std, vec![0, 0, 0, 0]);
+
+    // Internal translations of the conventional cell are the centering translations of F
+    let translations = internal_translations(&cell, symprec).unwrap();
+    assert_eq!(translations.len(), 4);
+    for expected in [
+        vector![0.0, 0.0, 0.0],
+        vector![0.0, 0.5, 0.5],
+        vector![0.5, 0.0, 0.5],
+        vector![0.5, 0.5, 0.0],
+    ] {
+        assert!(translations.iter().any(|translation| {
+            let diff = translation - expected;
+            (diff - diff.map(|e| e.round())).norm() < symprec
+        }));
+    }
+
+    // Conventional operations include the centering translations of F
+    let prim_dataset = assert_dataset(&dataset.prim_std_cell, symprec, angle_tolerance, setting);
+    let conventional_operations = dataset.conventional_symmetry_operations();
+    assert_eq!(prim_dataset.num_operations(), 48);
+    assert_eq!(
+        conventional_operations.len(),
+        4 * prim_dataset.num_operations()
+    );
+    let std_dataset = assert_dataset(&dataset.std_cell, symprec, angle_tolerance, setting);
+    assert_eq!(conventional_operations.len(), std_dataset.num_operations());
+
+    // Operations in the primitive standardized cell
+    let prim_std_operations = dataset.prim_std_operations().unwrap();
+    assert_eq!(prim_std_operations.len(), prim_dataset.num_operations());
+    for operation in prim_std_operations.iter() {
+        assert!(permutation_from_operation(
+            &dataset.prim_std_cell,
+            &operation.rotation,
+            &operation.translation
+        )
+        .is_some());
+    }
+
+    // Rank-2 tensors are projected to isotropic ones
+    let tensor = matrix![
+        1.0, 2.0, 3.0;
+        -4.0, 5.0, 6.0;
+        7.0, 8.0, 9.0;
+    ];
+    assert_relative_eq!(
+        dataset.symmetrize_rank2_tensor(tensor),
+        5.0 * Matrix3::identity(),
+        epsilon = 1e-8
+    );
+}
+
+#[test]
+fn test_validate_space_group() {
+    // Rutile with one O(4f) slightly displaced
+    let a = 4.603;
+    let c = 2.969;
+    let lattice = Lattice::new(matrix![
+        a, 0.0, 0.0;
+        0.0, a, 0.0;
+        0.0, 0.0, c;
+    ]);
+    let x_4f = 0.3046;
+    let dx = 2e-3;
+    let positions = vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.5, 0.5, 0.5),
+        Vector3::new(x_4f + dx, x_4f + dx, 0.0),
+        Vector3::new(-x_4f, -x_4f, 0.0),
+        Vector3::new(-x_4f + 0.5, x_4f + 0.5, 0.5),
+        Vector3::new(x_4f + 0.5, -x_4f + 0.5, 0.5),
+    ];
+    let numbers = vec![0, 0, 1, 1, 1, 1];
+    let cell = Cell::new(lattice, positions, numbers);
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let setting = Setting::Spglib;
+
+    let validation = validate_space_group(&cell, 136, symprec, angle_tolerance, setting);
+    assert!(!validation.matched);
+    assert_ne!(validation.detected, Some(136));
+    let recovered_symprec = validation.recovered_symprec.unwrap();
+    assert!(recovered_symprec > symprec);
+    let dataset = MoyoDataset::new(&cell, recovered_symprec, angle_tolerance, setting).unwrap();
+    assert_eq!(dataset.number, 136);
 }
 
 #[test]
@@ -187,9 +338,170 @@ fn test_with_rutile() {
 
     assert_eq!(dataset.number, 136); // P4_2/mnm
     assert_eq!(dataset.hall_number, 419);
+    assert_eq!(dataset.arithmetic_number, 36);
+    assert_eq!(dataset.arithmetic_symbol, "4/mmmP");
+    assert_eq!(dataset.point_group_operations().len(), 16);
+    // Ti(2a) and O(4f) need displacements along x and z
+    let displacements = dataset.phonon_displacements(0.01);
+    assert_eq!(displacements.len(), 4);
+    assert!(displacements.len() < 3 * cell.num_atoms());
+    assert_eq!(
+        displacements.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        vec![0, 0, 2, 2]
+    );
+    assert_relative_eq!(displacements[1].1, vector![0.0, 0.0, 0.01]);
+    // 4_2 screw axis through (0, 1/2, z)
+    let seitz_strings = dataset
+        .operations
+        .iter()
+        .map(|ops| Operation::identity().compose_mod1(ops).to_seitz_string())
+        .collect::<Vec<_>>();
+    assert!(seitz_strings.contains(&"{4+ 001 | 1/2 1/2 1/2}".to_string()));
+    assert!(seitz_strings.contains(&"{m 1-10 | 0 0 0}".to_string()));
+
+    // Ti sublattice forms a body-centered tetragonal lattice
+    let ti_dataset = sublattice_dataset(&cell, &[0], symprec, angle_tolerance, setting).unwrap();
+    assert_eq!(ti_dataset.number, 139); // I4/mmm
+    assert_eq!(ti_dataset.orbits, vec![0, 0]);
+    assert!(matches!(
+        sublattice_dataset(&cell, &[2], symprec, angle_tolerance, setting),
+        Err(MoyoError::EmptySublatticeError)
+    ));
+
+    assert_input_cell_generators(&dataset);
+    // The four-fold rotation does not preserve the lattice of the 2x1x1 supercell
+    for diagonal in [vector![1, 1, 2], vector![2, 1, 1]] {
+        let supercell = cell
+            .make_supercell(&Matrix3::from_diagonal(&diagonal))
+            .unwrap();
+        let supercell_dataset =
+            MoyoDataset::new(&supercell, symprec, angle_tolerance, setting).unwrap();
+        assert_input_cell_generators(&supercell_dataset);
+    }
+
+    // All O(4f) sites are equivalent for a single substitution
+    let substituted_cells = enumerate_substitutions(&cell, 2, 1, symprec).unwrap();
+    assert_eq!(substituted_cells.len(), 1);
+    assert_eq!(substituted_cells[0].numbers, vec![0, 0, 2, 1, 1, 1]);
+    // Pairs of O(4f) sites in the same and different layers along c
+    let pairs = enumerate_substitutions(&cell, 2, 2, symprec).unwrap();
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs[0].numbers, vec![0, 0, 2, 2, 1, 1]);
+    assert_eq!(pairs[1].numbers, vec![0, 0, 2, 1, 2, 1]);
+
+    // O(4f) orbit is generated by four coset representatives
+    let o_index = (0..dataset.std_cell.num_atoms())
+        .find(|&i| dataset.std_cell.numbers[i] == 1)
+        .unwrap();
+    let generators = dataset.orbit_generators(o_index);
+    assert_eq!(generators.len(), 4);
+    for (generator, j) in generators
+        .iter()
+        .zip((0..dataset.std_cell.num_atoms()).filter(|&j| dataset.std_cell.numbers[j] == 1))
+    {
+        let mut diff = generator.rotation.map(|e| e as f64) * dataset.std_cell.positions[o_index]
+            + generator.translation
+            - dataset.std_cell.positions[j];
+        diff -= diff.map(|e| e.round());
+        assert_relative_eq!(diff, Vector3::zeros(), epsilon = 1e-8);
+    }
+
+    let validation = validate_space_group(&cell, 136, symprec, angle_tolerance, setting);
+    assert!(validation.matched);
+    assert_eq!(validation.detected, Some(136));
+    assert_eq!(validation.recovered_symprec, Some(symprec));
     assert_eq!(dataset.num_operations(), 16);
     assert_eq!(dataset.orbits, vec![0, 0, 2, 2, 2, 2]);
     assert_eq!(dataset.wyckoffs, vec!['a', 'a', 'f', 'f', 'f', 'f']);
+    // Ti(2a) has no free parameter, O(4f) has x
+    let parameters = dataset.wyckoff_parameters().unwrap();
+    assert!(parameters[0].is_empty());
+    assert!(parameters[1].is_empty());
+    for parameter in parameters[2..].iter() {
+        assert_eq!(parameter.len(), 1);
+        assert!(
+            (parameter[0] - x_4f).abs() < symprec || (parameter[0] - (1.0 - x_4f)).abs() < symprec
+        );
+    }
+    // Ti: m.mm, O: m2.m
+    assert_eq!(
+        dataset.site_symmetry_point_groups().unwrap(),
+        vec![
+            GeometricCrystalClass::D2h,
+            GeometricCrystalClass::D2h,
+            GeometricCrystalClass::C2v,
+            GeometricCrystalClass::C2v,
+            GeometricCrystalClass::C2v,
+            GeometricCrystalClass::C2v,
+        ]
+    );
+
+    // 4/mmm has ten conjugacy classes
+    let classes = dataset.point_group_conjugacy_classes().unwrap();
+    assert_eq!(classes.len(), 10);
+    assert_eq!(classes[0], vec![0]);
+    assert_eq!(classes.iter().map(|class| class.len()).sum::<usize>(), 16);
+
+    // Spglib-compatible dataset
+    let spglib_dataset = dataset.to_spglib_like();
+    assert_eq!(spglib_dataset.spacegroup_number, 136);
+    assert_eq!(spglib_dataset.hall_number, 419);
+    assert_eq!(spglib_dataset.international_symbol, "P4_2/mnm");
+    assert_eq!(spglib_dataset.hall_symbol, "-P 4n 2n");
+    assert_eq!(spglib_dataset.pointgroup_symbol, "4/mmm");
+    assert_eq!(spglib_dataset.rotations.len(), 16);
+    assert_eq!(spglib_dataset.translations.len(), 16);
+    assert_eq!(spglib_dataset.wyckoffs, dataset.wyckoffs);
+    assert_eq!(spglib_dataset.equivalent_atoms, vec![0, 0, 2, 2, 2, 2]);
+    assert_eq!(spglib_dataset.std_types, dataset.std_cell.numbers);
+    for (position, std_position) in cell.positions.iter().zip(dataset.std_cell.positions.iter()) {
+        // x_std = P * x + p
+        let diff = spglib_dataset.transformation_matrix * position + spglib_dataset.origin_shift
+            - std_position;
+        assert_relative_eq!(
+            diff.map(|e| e - e.round()),
+            Vector3::zeros(),
+            epsilon = 1e-8
+        );
+    }
+
+    // Asymmetric unit regenerates all sites in the standardized cell
+    let (representatives, operations) = dataset.asymmetric_unit();
+    assert_eq!(representatives.len(), 2);
+    let std_cell = &dataset.std_cell;
+    assert_eq!(std_cell.num_atoms(), 6);
+    for (position, number) in std_cell.positions.iter().zip(std_cell.numbers.iter()) {
+        assert!(representatives.iter().any(|&i| {
+            std_cell.numbers[i] == *number
+                && operations.iter().any(|operation| {
+                    let diff = operation.rotation.map(|e| e as f64) * std_cell.positions[i]
+                        + operation.translation
+                        - position;
+                    diff.iter().all(|e| (e - e.round()).abs() < symprec)
+                })
+        }));
+    }
+
+    // Canonical standardized cell does not depend on the order of input sites
+    let permuted_cell = Cell::new(
+        cell.lattice.clone(),
+        [4, 1, 3, 0, 5, 2]
+            .iter()
+            .map(|&i| cell.positions[i])
+            .collect(),
+        [4, 1, 3, 0, 5, 2]
+            .iter()
+            .map(|&i| cell.numbers[i])
+            .collect(),
+    );
+    let permuted_dataset =
+        MoyoDataset::new(&permuted_cell, symprec, angle_tolerance, setting).unwrap();
+    let canonical_std_cell = dataset.canonical_std_cell();
+    assert_eq!(canonical_std_cell.numbers, vec![0, 0, 1, 1, 1, 1]);
+    assert_eq!(
+        serde_json::to_string(&canonical_std_cell).unwrap(),
+        serde_json::to_string(&permuted_dataset.canonical_std_cell()).unwrap()
+    );
 }
 
 #[test]
@@ -228,6 +540,45 @@ fn test_with_hcp() {
     if dataset.wyckoffs[0] != 'c' && dataset.wyckoffs[0] != 'd' {
         panic!("Unexpected Wyckoff letter: {}", dataset.wyckoffs[0]);
     }
+
+    // -P 6c 2c: -x,-y,-z; x-y,x,z+1/2; -y,-x,-z+1/2
+    let generators = dataset.conventional_generators().unwrap();
+    assert_eq!(generators.len(), 3);
+    assert_eq!(generators[0].rotation, -Rotation::identity());
+    assert_relative_eq!(generators[0].translation, Translation::zeros());
+    assert_eq!(
+        generators[1].rotation,
+        Rotation::new(1, -1, 0, 1, 0, 0, 0, 0, 1)
+    );
+    assert_relative_eq!(generators[1].translation, vector![0.0, 0.0, 0.5]);
+    assert_eq!(
+        generators[2].rotation,
+        Rotation::new(0, -1, 0, -1, 0, 0, 0, 0, -1)
+    );
+    assert_relative_eq!(generators[2].translation, vector![0.0, 0.0, 0.5]);
+
+    // Reciprocal-space rotations map reciprocal lattice vectors to reciprocal lattice vectors
+    let reciprocal_operations = dataset.reciprocal_operations();
+    assert_eq!(reciprocal_operations.len(), dataset.num_operations());
+    let basis = cell.lattice.basis;
+    let reciprocal_basis = cell.lattice.reciprocal().basis;
+    for (operation, reciprocal_rotation) in
+        dataset.operations.iter().zip(reciprocal_operations.iter())
+    {
+        assert_eq!(
+            reciprocal_rotation.transpose() * operation.rotation,
+            Rotation::identity()
+        );
+        let cartesian_rotation =
+            basis * operation.rotation.map(|e| e as f64) * basis.try_inverse().unwrap();
+        let rotated =
+            reciprocal_basis.try_inverse().unwrap() * cartesian_rotation * reciprocal_basis;
+        assert_relative_eq!(
+            rotated,
+            reciprocal_rotation.map(|e| e as f64),
+            epsilon = 1e-8
+        );
+    }
 }
 
 #[test]
@@ -265,6 +616,11 @@ fn test_with_wurtzite() {
     assert_eq!(dataset.number, 186);
     assert_eq!(dataset.hall_number, 480);
     assert_eq!(dataset.num_operations(), 12);
+    assert!(!dataset.is_centrosymmetric());
+    assert!(dataset.is_polar());
+    assert!(!dataset.is_chiral());
+    assert_eq!(dataset.laue_class(), GeometricCrystalClass::D6h);
+    assert_eq!(dataset.patterson_symmetry(), 191); // P6/mmm
     assert_eq!(dataset.orbits, vec![0, 0, 2, 2]);
     // 2a and 2b belong to the same Wyckoff set
     assert_eq!(dataset.wyckoffs[0], dataset.wyckoffs[1]);
@@ -333,6 +689,69 @@ fn test_with_corundum() {
 
     assert_eq!(dataset.number, 167);
     assert_eq!(dataset.hall_number, 460); // Hexagonal setting
+    assert_eq!(dataset.laue_class(), GeometricCrystalClass::D3d);
+    assert_eq!(dataset.patterson_symmetry(), 166); // R-3m
+    assert_eq!(dataset.num_wyckoff_orbits(), 2); // Al(12c) and O(18e)
+    assert_input_cell_generators(&dataset);
+
+    // Standardized cell is the same as the input hexagonal cell
+    assert!(dataset.std_linear_is_integer());
+    assert_relative_eq!(dataset.std_volume_ratio, 1.0, epsilon = 1e-8);
+    // Standardized cell is a supercell of the primitive one by the centering order
+    let prim_dataset = assert_dataset(&dataset.prim_std_cell, symprec, angle_tolerance, setting);
+    assert!(prim_dataset.std_linear_is_integer());
+    assert_relative_eq!(
+        prim_dataset.std_volume_ratio,
+        Centering::R.order() as f64,
+        epsilon = 1e-8
+    );
+
+    // Rhombohedral primitive vectors have the same lengths and angles
+    let ita_primitive_cell = dataset.ita_primitive_cell();
+    assert_eq!(ita_primitive_cell.num_atoms(), 10);
+    assert_relative_eq!(
+        ita_primitive_cell.lattice.basis,
+        dataset.prim_std_cell.lattice.basis
+    );
+    assert_relative_eq!(
+        ita_primitive_cell.lattice.volume() * 3.0,
+        dataset.std_cell.lattice.volume(),
+        epsilon = 1e-6
+    );
+    let metric_tensor = ita_primitive_cell.lattice.metric_tensor();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let k = (i + 2) % 3;
+        assert_relative_eq!(metric_tensor[(i, i)], metric_tensor[(j, j)], epsilon = 1e-4);
+        assert_relative_eq!(metric_tensor[(i, j)], metric_tensor[(j, k)], epsilon = 1e-4);
+    }
+    let ita_dataset = assert_dataset(&ita_primitive_cell, symprec, angle_tolerance, setting);
+    assert_eq!(ita_dataset.number, 167);
+
+    // Standardized cell in the reverse setting
+    let reverse_std_cell = dataset.std_cell_in(RhombohedralSetting::Reverse);
+    assert_relative_eq!(
+        reverse_std_cell.lattice.basis,
+        dataset.std_cell.lattice.basis,
+        epsilon = 1e-8
+    );
+    assert_eq!(
+        dataset.std_cell_in(RhombohedralSetting::Obverse).positions,
+        dataset.std_cell.positions
+    );
+    let centering_translations = Centering::R
+        .lattice_points_in(RhombohedralSetting::Obverse)
+        .into_iter()
+        .chain(Centering::R.lattice_points_in(RhombohedralSetting::Reverse))
+        .map(|translation| Operation::new(Rotation::identity(), translation))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        check_operations(&reverse_std_cell, &centering_translations, symprec),
+        vec![true, false, false, true, true, true]
+    );
+    let reverse_std_dataset =
+        MoyoDataset::new(&reverse_std_cell, symprec, angle_tolerance, setting).unwrap();
+    assert_eq!(reverse_std_dataset.number, 167);
     assert_eq!(dataset.num_operations(), 36);
     assert_eq!(
         dataset.orbits,
@@ -348,6 +767,20 @@ fn test_with_corundum() {
             'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e',
         ]
     );
+    assert_eq!(dataset.orbit_representatives(), vec![0, 12]);
+    assert_eq!(dataset.orbit_sizes(), vec![12, 18]);
+
+    // Operations in the rhombohedral primitive cell
+    let prim_std_operations = dataset.prim_std_operations().unwrap();
+    assert_eq!(prim_std_operations.len(), 12);
+    for operation in prim_std_operations.iter() {
+        assert!(permutation_from_operation(
+            &dataset.prim_std_cell,
+            &operation.rotation,
+            &operation.translation
+        )
+        .is_some());
+    }
 }
 
 #[test]
@@ -386,6 +819,9 @@ fn test_with_hexagonal_Sc() {
     assert_eq!(dataset.number, 178);
     assert_eq!(dataset.hall_number, 472);
     assert_eq!(dataset.num_operations(), 12);
+    assert!(!dataset.is_centrosymmetric());
+    assert!(!dataset.is_polar());
+    assert!(dataset.is_chiral());
     assert_eq!(dataset.orbits, vec![0, 0, 0, 0, 0, 0]);
     assert_eq!(dataset.wyckoffs, vec!['a', 'a', 'a', 'a', 'a', 'a']);
 }
@@ -591,6 +1027,52 @@ fn test_primitive_symmetry_search_corner_case() {
     assert_dataset(&dataset.prim_std_cell, symprec, angle_tolerance, setting);
 }
 
+#[test]
+fn test_centering_equivalent_groups() {
+    // Body-centered tetragonal structure, I4/mmm (No. 139)
+    let a = 4.0;
+    let c = 10.0;
+    let lattice = Lattice::new(matrix![
+        a, 0.0, 0.0;
+        0.0, a, 0.0;
+        0.0, 0.0, c;
+    ]);
+    let positions = vec![
+        vector![0.0, 0.0, 0.0],
+        vector![0.5, 0.5, 0.5],
+        vector![0.0, 0.0, 0.3],
+        vector![0.0, 0.0, 0.7],
+        vector![0.5, 0.5, 0.8],
+        vector![0.5, 0.5, 0.2],
+    ];
+    let numbers = vec![0, 0, 1, 1, 1, 1];
+    let cell = Cell::new(lattice, positions, numbers);
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let setting = Setting::Spglib;
+
+    let dataset = assert_dataset(&cell, symprec, angle_tolerance, setting);
+    assert_eq!(dataset.number, 139);
+    assert_eq!(dataset.std_cell.num_atoms(), 6);
+
+    let groups = dataset.centering_equivalent_groups();
+    assert_eq!(groups.len(), 3);
+    for group in groups.iter() {
+        // Pairs related by (1/2, 1/2, 1/2)
+        assert_eq!(group.len(), 2);
+        let (i, j) = (group[0], group[1]);
+        assert_eq!(dataset.std_cell.numbers[i], dataset.std_cell.numbers[j]);
+        assert_eq!(dataset.std_to_prim_std[i], dataset.std_to_prim_std[j]);
+        let diff = dataset.std_cell.positions[j] - dataset.std_cell.positions[i];
+        assert_relative_eq!(
+            diff.map(|e| (e - 0.5 - (e - 0.5).round()).abs()),
+            Vector3::zeros(),
+            epsilon = 1e-8
+        );
+    }
+}
+
 #[test]
 fn test_with_high_symprec_and_angle_tolerance() {
     let lattice = Lattice::new(Matrix3::identity());
@@ -607,5 +1089,1136 @@ fn test_with_high_symprec_and_angle_tolerance() {
     let angle_tolerance = AngleTolerance::Radian(1.0);
     let setting = Setting::Spglib;
 
-    let _ = MoyoDataset::new(&cell, symprec, angle_tolerance, setting).unwrap();
+    let dataset = MoyoDataset::new(&cell, symprec, angle_tolerance, setting).unwrap();
+    assert!(dataset.tolerance_adjusted);
+    assert!(dataset.iterations > 0);
+
+    // The callback is called for each trial, and only the last one succeeds
+    let mut trials = vec![];
+    let context =
+        SymmetryContext::with_callback(&cell, symprec, angle_tolerance, &mut |symprec, success| {
+            trials.push((symprec, success))
+        })
+        .unwrap();
+    assert_eq!(trials.len(), dataset.iterations + 1);
+    assert_eq!(trials[0].0, symprec);
+    assert!(trials[..dataset.iterations]
+        .iter()
+        .all(|(_, success)| !success));
+    assert_eq!(trials[dataset.iterations], (context.symprec(), true));
+
+    // No adjustment with reasonable tolerances
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, setting).unwrap();
+    assert!(!dataset.tolerance_adjusted);
+    assert_eq!(dataset.iterations, 0);
+}
+
+#[test]
+fn test_builder_with_origin_choice_and_rotation() {
+    // Diamond in a rotated cubic lattice
+    let a = 3.567;
+    let theta = std::f64::consts::PI / 6.0;
+    let (c, s) = (theta.cos(), theta.sin());
+    let lattice = Lattice::new(matrix![
+        a * c, a * s, 0.0;
+        -a * s, a * c, 0.0;
+        0.0, 0.0, a;
+    ]);
+    let mut positions = vec![];
+    for t in [
+        vector![0.0, 0.0, 0.0],
+        vector![0.0, 0.5, 0.5],
+        vector![0.5, 0.0, 0.5],
+        vector![0.5, 0.5, 0.0],
+    ] {
+        positions.push(t);
+        positions.push(t + vector![0.25, 0.25, 0.25]);
+    }
+    let numbers = vec![0; positions.len()];
+    let cell = Cell::new(lattice, positions, numbers);
+
+    let dataset = MoyoDatasetBuilder::new()
+        .symprec(1e-4)
+        .angle_tolerance(AngleTolerance::Radian(1e-2))
+        .setting(Setting::Spglib)
+        .origin_choice(OriginChoice::Two)
+        .standardize_rotation(false)
+        .build(&cell)
+        .unwrap();
+
+    assert_eq!(dataset.number, 227); // Fd-3m
+    assert_eq!(dataset.hall_number, 526); // origin choice 2
+    assert_eq!(dataset.num_operations(), 48 * 4);
+    assert_relative_eq!(dataset.std_rotation_matrix, Matrix3::identity());
+    assert_relative_eq!(
+        cell.lattice.basis * dataset.std_linear,
+        dataset.std_cell.lattice.basis,
+        epsilon = 1e-8
+    );
+    assert_relative_eq!(
+        cell.lattice.basis * dataset.prim_std_linear,
+        dataset.prim_std_cell.lattice.basis,
+        epsilon = 1e-8
+    );
+
+    // `MoyoDataset::new` is equivalent to the builder with default options
+    let dataset_default =
+        MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset_default.hall_number, 525); // origin choice 1
+}
+
+#[test]
+fn test_cayley_table() {
+    // P4 with a general position
+    let lattice = Lattice::new(matrix![
+        4.0, 0.0, 0.0;
+        0.0, 4.0, 0.0;
+        0.0, 0.0, 3.0;
+    ]);
+    let (x, y, z) = (0.1234, 0.2345, 0.3456);
+    let positions = vec![
+        vector![x, y, z],
+        vector![-x, -y, z],
+        vector![-y, x, z],
+        vector![y, -x, z],
+        vector![0.0, 0.0, 0.0],
+    ];
+    let numbers = vec![0, 0, 0, 0, 1];
+    let cell = Cell::new(lattice, positions, numbers);
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let setting = Setting::Spglib;
+
+    let dataset = assert_dataset(&cell, symprec, angle_tolerance, setting);
+    assert_eq!(dataset.number, 75); // P4
+
+    let table = dataset.cayley_table().unwrap();
+    // The table is computed once and reused
+    assert!(std::ptr::eq(dataset.cayley_table().unwrap(), table));
+    let order = dataset.num_operations();
+    assert_eq!(order, 4);
+
+    // Identity
+    let identity = (0..order)
+        .find(|&i| dataset.operations[i].rotation == Rotation::identity())
+        .unwrap();
+    assert_eq!(table[identity], (0..order).collect::<Vec<_>>());
+    for (i, row) in table.iter().enumerate() {
+        assert_eq!(row[identity], i);
+    }
+    // Each row is a permutation and each element has an inverse
+    for row in table.iter() {
+        let mut sorted_row = row.clone();
+        sorted_row.sort();
+        assert_eq!(sorted_row, (0..order).collect::<Vec<_>>());
+        assert!(row.contains(&identity));
+    }
+    // Identity and inverses
+    assert_eq!(dataset.identity_index().unwrap(), identity);
+    let inverse_indices = dataset.inverse_indices().unwrap();
+    for (i, &j) in inverse_indices.iter().enumerate() {
+        let operation = dataset.operations[i].clone() * dataset.operations[j].clone();
+        assert_eq!(operation.rotation, Rotation::identity());
+        assert_relative_eq!(
+            operation.translation,
+            operation.translation.map(|e| e.round()),
+            epsilon = 1e-8
+        );
+    }
+
+    // Associativity
+    for i in 0..order {
+        for j in 0..order {
+            for k in 0..order {
+                assert_eq!(table[table[i][j]][k], table[i][table[j][k]]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_symmetry_context_with_multiple_settings() {
+    // Diamond
+    let a = 3.567;
+    let lattice = Lattice::new(matrix![
+        a, 0.0, 0.0;
+        0.0, a, 0.0;
+        0.0, 0.0, a;
+    ]);
+    let mut positions = vec![];
+    for t in [
+        vector![0.0, 0.0, 0.0],
+        vector![0.0, 0.5, 0.5],
+        vector![0.5, 0.0, 0.5],
+        vector![0.5, 0.5, 0.0],
+    ] {
+        positions.push(t);
+        positions.push(t + vector![0.25, 0.25, 0.25]);
+    }
+    let numbers = vec![0; positions.len()];
+    let cell = Cell::new(lattice, positions, numbers);
+
+    let context = SymmetryContext::new(&cell, 1e-4, AngleTolerance::Default).unwrap();
+    let dataset_spglib = context.dataset(Setting::Spglib).unwrap();
+    let dataset_standard = context.dataset(Setting::Standard).unwrap();
+
+    assert_eq!(dataset_spglib.number, 227);
+    assert_eq!(dataset_spglib.number, dataset_standard.number);
+    assert_eq!(
+        dataset_spglib.num_operations(),
+        dataset_standard.num_operations()
+    );
+    assert_eq!(dataset_spglib.hall_number, 525); // origin choice 1
+    assert_eq!(dataset_standard.hall_number, 526); // origin choice 2
+}
+
+#[test]
+fn test_primitive_dataset() {
+    // Wurtzite
+    let a = 3.81;
+    let c = 6.24;
+    let lattice = Lattice::new(matrix![
+        a, 0.0, 0.0;
+        -a / 2.0, a * 3.0_f64.sqrt() / 2.0, 0.0;
+        0.0, 0.0, c;
+    ]);
+    let z1_2b = 0.00014;
+    let z2_2b = 0.37486;
+    let positions = vec![
+        // 2b
+        Vector3::new(1.0 / 3.0, 2.0 / 3.0, z1_2b),
+        Vector3::new(2.0 / 3.0, 1.0 / 3.0, z1_2b + 0.5),
+        // 2b
+        Vector3::new(1.0 / 3.0, 2.0 / 3.0, z2_2b),
+        Vector3::new(2.0 / 3.0, 1.0 / 3.0, z2_2b + 0.5),
+    ];
+    let numbers = vec![1, 1, 2, 2];
+    let cell = Cell::new(lattice, positions, numbers);
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let setting = Setting::Spglib;
+
+    let dataset = MoyoDataset::new(&cell, symprec, angle_tolerance, setting).unwrap();
+    let prim_dataset = PrimitiveDataset::new(&cell, symprec, angle_tolerance, setting).unwrap();
+
+    assert_eq!(prim_dataset.number, dataset.number);
+    assert_eq!(prim_dataset.hall_number, dataset.hall_number);
+    assert_eq!(prim_dataset.num_operations(), dataset.num_operations());
+    assert_eq!(prim_dataset.orbits, dataset.orbits);
+    assert_eq!(prim_dataset.wyckoffs, dataset.wyckoffs);
+    assert_eq!(
+        prim_dataset.prim_std_cell.numbers,
+        dataset.prim_std_cell.numbers
+    );
+    assert_relative_eq!(
+        prim_dataset.prim_std_cell.lattice.basis,
+        dataset.prim_std_cell.lattice.basis
+    );
+    assert_relative_eq!(prim_dataset.prim_std_linear, dataset.prim_std_linear);
+    assert_relative_eq!(
+        prim_dataset.prim_std_origin_shift,
+        dataset.prim_std_origin_shift
+    );
+    for (actual, expect) in prim_dataset
+        .prim_std_cell
+        .positions
+        .iter()
+        .zip(dataset.prim_std_cell.positions.iter())
+    {
+        assert_relative_eq!(actual, expect);
+    }
+
+    // Reuse the symmetry search
+    let context = SymmetryContext::new(&cell, symprec, angle_tolerance).unwrap();
+    let prim_dataset = context.primitive_dataset(Setting::Standard).unwrap();
+    let dataset = context.dataset(Setting::Standard).unwrap();
+    assert_eq!(prim_dataset.hall_number, dataset.hall_number);
+    assert_eq!(prim_dataset.wyckoffs, dataset.wyckoffs);
+}
+
+#[test]
+fn test_lattice_strain() {
+    // Slightly sheared simple cubic lattice
+    let shear = 1e-3;
+    let lattice = Lattice::new(matrix![
+        1.0, 0.0, 0.0;
+        shear, 1.0, 0.0;
+        0.0, 0.0, 1.0;
+    ]);
+    let cell = Cell::new(lattice, vec![vector![0.0, 0.0, 0.0]], vec![0]);
+
+    let dataset = MoyoDataset::new(&cell, 1e-2, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 221);
+
+    let strain = dataset.lattice_strain();
+    assert_relative_eq!(strain, strain.transpose(), epsilon = 1e-12);
+    assert!(strain[(0, 1)].abs() > 1e-5);
+    assert!(strain.abs().max() < 1e-2);
+
+    // Same strain without rotating the standardized cell
+    let dataset = MoyoDatasetBuilder::new()
+        .symprec(1e-2)
+        .standardize_rotation(false)
+        .build(&cell)
+        .unwrap();
+    assert_relative_eq!(dataset.lattice_strain(), strain, epsilon = 1e-12);
+
+    // No strain for an ideal lattice
+    let cell = Cell::new(
+        Lattice::new(Matrix3::identity()),
+        vec![vector![0.0, 0.0, 0.0]],
+        vec![0],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_relative_eq!(dataset.lattice_strain(), Matrix3::zeros(), epsilon = 1e-8);
+}
+
+#[test]
+fn test_ideal_std_lattice() {
+    // Slightly distorted simple cubic lattice
+    let lattice = Lattice::new(matrix![
+        1.0, 0.0, 0.0;
+        1e-3, 1.001, 0.0;
+        0.0, 0.0, 0.999;
+    ]);
+    let cell = Cell::new(lattice, vec![vector![0.0, 0.0, 0.0]], vec![0]);
+
+    let dataset = MoyoDataset::new(&cell, 1e-2, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 221);
+
+    // The ideal lattice is a perfect cube close to the distorted one
+    let metric_tensor = dataset.ideal_std_lattice.metric_tensor();
+    let a2 = metric_tensor[(0, 0)];
+    assert_relative_eq!(metric_tensor, a2 * Matrix3::identity(), epsilon = 1e-8);
+    assert_relative_eq!(a2, 1.0, epsilon = 1e-2);
+    assert!(
+        (dataset.std_cell.lattice.metric_tensor() - a2 * Matrix3::identity())
+            .abs()
+            .max()
+            > 1e-4
+    );
+    assert_relative_eq!(
+        dataset.ideal_std_lattice.basis,
+        dataset.std_cell.lattice.basis,
+        epsilon = 1e-2
+    );
+}
+
+#[test]
+fn test_structure_analyzer() {
+    // Build rutile from the Ti sublattice
+    let a = 4.603;
+    let c = 2.969;
+    let lattice = Lattice::new(matrix![
+        a, 0.0, 0.0;
+        0.0, a, 0.0;
+        0.0, 0.0, c;
+    ]);
+    let x_4f = 0.3046;
+    let cell = Cell::new(
+        lattice,
+        vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.5, 0.5, 0.5)],
+        vec![0, 0],
+    );
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let setting = Setting::Standard;
+    let mut analyzer = StructureAnalyzer::new(cell, symprec, angle_tolerance, setting);
+    assert_eq!(analyzer.dataset().unwrap().number, 139); // I4/mmm
+
+    for position in [
+        Vector3::new(x_4f, x_4f, 0.0),
+        Vector3::new(-x_4f, -x_4f, 0.0),
+        Vector3::new(-x_4f + 0.5, x_4f + 0.5, 0.5),
+        Vector3::new(x_4f + 0.5, -x_4f + 0.5, 0.5),
+    ] {
+        analyzer.add_atom(position, 1);
+    }
+    assert_eq!(analyzer.cell().num_atoms(), 6);
+
+    // Same as a fresh dataset after edits
+    let dataset = analyzer.dataset().unwrap();
+    let expect = MoyoDataset::new(analyzer.cell(), symprec, angle_tolerance, setting).unwrap();
+    assert_eq!(dataset.number, 136); // P4_2/mnm
+    assert_eq!(dataset.hall_number, expect.hall_number);
+    assert_eq!(dataset.num_operations(), expect.num_operations());
+    assert_eq!(dataset.orbits, expect.orbits);
+    assert_eq!(dataset.wyckoffs, expect.wyckoffs);
+    assert_relative_eq!(dataset.std_linear, expect.std_linear);
+
+    // Remove one of the O sites
+    analyzer.remove_atom(5);
+    let dataset = analyzer.dataset().unwrap();
+    let expect = MoyoDataset::new(analyzer.cell(), symprec, angle_tolerance, setting).unwrap();
+    assert_eq!(dataset.number, expect.number);
+    assert_eq!(dataset.num_operations(), expect.num_operations());
+    assert_eq!(dataset.orbits, expect.orbits);
+    assert_eq!(dataset.wyckoffs, expect.wyckoffs);
+}
+
+#[test]
+fn test_dedupe_structures() {
+    let a = 4.0;
+    let conventional_fcc = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],
+            vector![0.0, 0.5, 0.5],
+            vector![0.5, 0.0, 0.5],
+            vector![0.5, 0.5, 0.0],
+        ],
+        vec![0, 0, 0, 0],
+    );
+    // Primitive fcc with a shifted origin
+    let primitive_fcc = Cell::new(
+        Lattice::new(matrix![
+            0.0, a / 2.0, a / 2.0;
+            a / 2.0, 0.0, a / 2.0;
+            a / 2.0, a / 2.0, 0.0;
+        ]),
+        vec![vector![0.3, 0.3, 0.3]],
+        vec![0],
+    );
+    let bcc = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+        vec![0, 0],
+    );
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    assert!(are_equivalent(&conventional_fcc, &primitive_fcc, symprec, angle_tolerance).unwrap());
+    assert!(!are_equivalent(&conventional_fcc, &bcc, symprec, angle_tolerance).unwrap());
+
+    let cells = vec![primitive_fcc, bcc, conventional_fcc];
+    let representatives = dedupe_structures(&cells, symprec, angle_tolerance);
+    assert_eq!(representatives, vec![0, 1, 0]);
+
+    // Rutile with shifted origins and swapped a and b axes are equivalent, but not with a different O(4f) parameter
+    let rutile = |x_4f: f64, shift: Vector3<f64>, swap: bool| {
+        let positions = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.5, 0.5, 0.5),
+            Vector3::new(x_4f, x_4f, 0.0),
+            Vector3::new(-x_4f, -x_4f, 0.0),
+            Vector3::new(-x_4f + 0.5, x_4f + 0.5, 0.5),
+            Vector3::new(x_4f + 0.5, -x_4f + 0.5, 0.5),
+        ]
+        .iter()
+        .map(|position| {
+            let position = if swap {
+                Vector3::new(position.y, position.x, -position.z)
+            } else {
+                *position
+            };
+            position + shift
+        })
+        .collect();
+        Cell::new(
+            Lattice::new(matrix![
+                4.603, 0.0, 0.0;
+                0.0, 4.603, 0.0;
+                0.0, 0.0, 2.969;
+            ]),
+            positions,
+            vec![0, 0, 1, 1, 1, 1],
+        )
+    };
+    let reference = rutile(0.3046, Vector3::zeros(), false);
+    let moved = rutile(0.3046, vector![0.1, 0.2, 0.3], true);
+    let distinct = rutile(0.32, Vector3::zeros(), false);
+    assert!(are_equivalent(&reference, &moved, symprec, angle_tolerance).unwrap());
+    assert!(!are_equivalent(&reference, &distinct, symprec, angle_tolerance).unwrap());
+
+    // Inverting a P4_1 structure gives its enantiomorph in P4_3
+    let p41 = |sign: f64| {
+        let (x, y, z) = (0.1, 0.2, 0.05);
+        let positions = [
+            Vector3::new(x, y, z),
+            Vector3::new(-x, -y, z + 0.5),
+            Vector3::new(-y, x, z + 0.25),
+            Vector3::new(y, -x, z + 0.75),
+        ]
+        .iter()
+        .map(|position| sign * position)
+        .collect();
+        Cell::new(
+            Lattice::new(matrix![
+                4.0, 0.0, 0.0;
+                0.0, 4.0, 0.0;
+                0.0, 0.0, 6.0;
+            ]),
+            positions,
+            vec![0, 0, 0, 0],
+        )
+    };
+    let left = p41(1.0);
+    let right = p41(-1.0);
+    let setting = Setting::Standard;
+    assert_eq!(
+        MoyoDataset::new(&left, symprec, angle_tolerance, setting)
+            .unwrap()
+            .number,
+        76
+    );
+    assert_eq!(
+        MoyoDataset::new(&right, symprec, angle_tolerance, setting)
+            .unwrap()
+            .number,
+        78
+    );
+    assert!(are_equivalent(&left, &right, symprec, angle_tolerance).unwrap());
+}
+
+#[test]
+fn test_irreducible_kpoints() {
+    let cell = Cell::new(
+        Lattice::new(3.0 * Matrix3::identity()),
+        vec![vector![0.0, 0.0, 0.0]],
+        vec![0],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+
+    // Gamma-centered mesh: coordinates are in {0, 1/4, 1/2} up to signs and permutations
+    let kpoints = dataset.irreducible_kpoints([4, 4, 4], [0.0, 0.0, 0.0]);
+    assert_eq!(kpoints.iter().map(|(_, weight)| weight).sum::<usize>(), 64);
+    assert_eq!(kpoints.len(), 10);
+    assert_relative_eq!(kpoints[0].0, Vector3::zeros());
+    assert_eq!(kpoints[0].1, 1);
+
+    // Shifted mesh
+    let kpoints = dataset.irreducible_kpoints([4, 4, 4], [0.5, 0.5, 0.5]);
+    assert_eq!(kpoints.iter().map(|(_, weight)| weight).sum::<usize>(), 64);
+    assert_eq!(kpoints.len(), 4);
+}
+
+#[test]
+fn test_check_operations() {
+    let a = 4.0;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],
+            vector![0.0, 0.5, 0.5],
+            vector![0.5, 0.0, 0.5],
+            vector![0.5, 0.5, 0.0],
+        ],
+        vec![0, 0, 0, 0],
+    );
+
+    let four_fold = matrix![
+        0, -1, 0;
+        1, 0, 0;
+        0, 0, 1;
+    ];
+    let shear = matrix![
+        1, 1, 0;
+        0, 1, 0;
+        0, 0, 1;
+    ];
+    let operations = vec![
+        // Valid: four-fold rotation and a centering translation
+        Operation::new(four_fold, vector![0.0, 0.0, 0.0]),
+        Operation::new(Rotation::identity(), vector![0.5, 0.5, 0.0]),
+        // Fabricated: translation not in the lattice, and shear maps sites onto sites but is not an isometry
+        Operation::new(four_fold, vector![0.25, 0.0, 0.0]),
+        Operation::new(shear, vector![0.0, 0.0, 0.0]),
+    ];
+    assert_eq!(
+        check_operations(&cell, &operations, 1e-4),
+        vec![true, true, false, false]
+    );
+
+    // All operations found by moyo pass
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert!(check_operations(&cell, &dataset.operations, 1e-4)
+        .iter()
+        .all(|&valid| valid));
+}
+
+#[test]
+fn test_rescale_length() {
+    // Rutile in angstrom with one O(4f) displaced by about 0.005 angstrom
+    let a = 4.603;
+    let c = 2.969;
+    let x_4f = 0.3046;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, c;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],
+            vector![0.5, 0.5, 0.5],
+            vector![x_4f + 0.001, x_4f, 0.0],
+            vector![-x_4f, -x_4f, 0.0],
+            vector![-x_4f + 0.5, x_4f + 0.5, 0.5],
+            vector![x_4f + 0.5, -x_4f + 0.5, 0.5],
+        ],
+        vec![0, 0, 1, 1, 1, 1],
+    );
+    let symprec = 1e-2;
+    let dataset =
+        MoyoDataset::new(&cell, symprec, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 136);
+
+    // Same structure in bohr needs symprec in bohr
+    let bohr = 0.529177;
+    let bohr_cell = cell.rescale_length(1.0 / bohr);
+    assert_relative_eq!(bohr_cell.lattice.basis, cell.lattice.basis / bohr);
+    assert_eq!(bohr_cell.positions, cell.positions);
+    let bohr_dataset = MoyoDataset::new(
+        &bohr_cell,
+        symprec / bohr,
+        AngleTolerance::Default,
+        Setting::Spglib,
+    )
+    .unwrap();
+    assert_eq!(bohr_dataset.number, dataset.number);
+
+    // Relative symprec does not depend on the length unit
+    for cell in [&cell, &bohr_cell] {
+        let dataset = MoyoDatasetBuilder::new()
+            .symprec(Symprec::Relative(symprec / c))
+            .build(cell)
+            .unwrap();
+        assert_eq!(dataset.number, 136);
+        assert_relative_eq!(
+            dataset.symprec,
+            Symprec::Relative(symprec / c).absolute(&cell.lattice)
+        );
+    }
+}
+
+#[test]
+fn test_holohedry_order() {
+    // Tetragonal P4mm with a pseudo-cubic lattice
+    let a = 4.0;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a + 1e-5;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.3]],
+        vec![0, 1],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 99); // P4mm
+    assert_eq!(dataset.num_operations(), 8);
+    assert_eq!(dataset.holohedry_order().unwrap(), 48);
+
+    // Holohedry of a genuinely tetragonal lattice
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, 1.5 * a;
+        ]),
+        cell.positions.clone(),
+        cell.numbers.clone(),
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.holohedry_order().unwrap(), 16);
+}
+
+#[test]
+fn test_inequivalent_pairs() {
+    // Rocksalt in the conventional cell
+    let a = 5.64;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![
+            // Na
+            vector![0.0, 0.0, 0.0],
+            vector![0.0, 0.5, 0.5],
+            vector![0.5, 0.0, 0.5],
+            vector![0.5, 0.5, 0.0],
+            // Cl
+            vector![0.5, 0.5, 0.5],
+            vector![0.5, 0.0, 0.0],
+            vector![0.0, 0.5, 0.0],
+            vector![0.0, 0.0, 0.5],
+        ],
+        vec![0, 0, 0, 0, 1, 1, 1, 1],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 225);
+
+    // All Na-Cl nearest neighbors are equivalent
+    let pairs = dataset.inequivalent_pairs(0.6 * a);
+    assert_eq!(pairs.len(), 1);
+    let (i, j, distance) = pairs[0];
+    assert_ne!(dataset.std_cell.numbers[i], dataset.std_cell.numbers[j]);
+    assert_relative_eq!(distance, a / 2.0, epsilon = 1e-8);
+
+    // Na-Na and Cl-Cl second neighbors are not equivalent to each other
+    let pairs = dataset.inequivalent_pairs(0.75 * a);
+    assert_eq!(pairs.len(), 3);
+    assert_relative_eq!(pairs[1].2, a / 2.0_f64.sqrt(), epsilon = 1e-8);
+    assert_relative_eq!(pairs[2].2, a / 2.0_f64.sqrt(), epsilon = 1e-8);
+}
+
+#[test]
+fn test_allowed_magnetic_orderings() {
+    // bcc in the conventional cell
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            2.87, 0.0, 0.0;
+            0.0, 2.87, 0.0;
+            0.0, 0.0, 2.87;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+        vec![0, 0],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 229);
+
+    // Ferromagnetic and antiferromagnetic orderings
+    let orderings = dataset.allowed_magnetic_orderings();
+    assert_eq!(orderings, vec![vec![1, 1], vec![1, -1]]);
+
+    // CsCl-type: the two sites are not related by symmetry
+    let cell = Cell::new(cell.lattice.clone(), cell.positions.clone(), vec![0, 1]);
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 221);
+    let orderings = dataset.allowed_magnetic_orderings();
+    assert_eq!(orderings, vec![vec![1, 1], vec![1, -1]]);
+    // Only the Cs sublattice is magnetic
+    let dataset =
+        sublattice_dataset(&cell, &[0], 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.allowed_magnetic_orderings(), vec![vec![1]]);
+
+    // Low-symmetry cell with more sites than the cap
+    let num_sites = 17;
+    let positions = (0..num_sites)
+        .map(|i| {
+            let t = i as f64;
+            vector![
+                (0.13 * t).fract(),
+                (0.37 * t * t).fract(),
+                (0.71 * t).fract()
+            ]
+        })
+        .collect::<Vec<_>>();
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            10.0, 0.0, 0.0;
+            1.0, 11.0, 0.0;
+            2.0, 3.0, 12.0;
+        ]),
+        positions,
+        vec![0; num_sites],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.std_cell.num_atoms(), num_sites);
+    assert!(dataset.allowed_magnetic_orderings().is_empty());
+}
+
+#[test]
+fn test_symmetry_mode_amplitudes() {
+    // Cubic perovskite with Ti slightly displaced along the c axis
+    let a = 3.9;
+    let delta = 0.005;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],         // Sr
+            vector![0.5, 0.5, 0.5 + delta], // Ti
+            vector![0.5, 0.5, 0.0],         // O
+            vector![0.5, 0.0, 0.5],         // O
+            vector![0.0, 0.5, 0.5],         // O
+        ],
+        vec![0, 1, 2, 2, 2],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-1, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 221);
+
+    // The symmetrized structure is shifted by the mean displacement, delta / 5
+    let amplitudes = dataset.symmetry_mode_amplitudes(&cell);
+    assert_eq!(amplitudes.len(), 3);
+    assert_relative_eq!(amplitudes[0], 0.2 * delta * a, epsilon = 1e-8);
+    assert_relative_eq!(amplitudes[1], 0.8 * delta * a, epsilon = 1e-8);
+    assert_relative_eq!(amplitudes[2], 0.2 * delta * a, epsilon = 1e-8);
+    let site_displacements = dataset.site_displacements(&cell);
+    for (i, expected) in [0.2, 0.8, 0.2, 0.2, 0.2].iter().enumerate() {
+        assert_relative_eq!(site_displacements[i], expected * delta * a, epsilon = 1e-8);
+    }
+
+    // Antipolar displacements of B sites along a keep the A site at the inversion center
+    let c = 2.0 * a;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, c;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],          // A
+            vector![0.5 + delta, 0.5, 0.25], // B
+            vector![0.5 - delta, 0.5, 0.75], // B
+        ],
+        vec![0, 1, 1],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-1, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 123);
+    let amplitudes = dataset.symmetry_mode_amplitudes(&cell);
+    assert_eq!(amplitudes.len(), 2);
+    assert_relative_eq!(amplitudes[0], 0.0, epsilon = 1e-8);
+    assert_relative_eq!(amplitudes[1], delta * a, epsilon = 1e-8);
+
+    // An acoustic translation of the whole structure does not change amplitudes
+    let shift = vector![0.01, 0.02, 0.03];
+    let shifted_cell = Cell::new(
+        cell.lattice.clone(),
+        cell.positions
+            .iter()
+            .map(|position| position + shift)
+            .collect(),
+        cell.numbers.clone(),
+    );
+    let shifted_dataset = MoyoDataset::new(
+        &shifted_cell,
+        1e-1,
+        AngleTolerance::Default,
+        Setting::Spglib,
+    )
+    .unwrap();
+    let shifted_amplitudes = shifted_dataset.symmetry_mode_amplitudes(&shifted_cell);
+    assert_relative_eq!(shifted_amplitudes[0], 0.0, epsilon = 1e-8);
+    assert_relative_eq!(shifted_amplitudes[1], delta * a, epsilon = 1e-8);
+}
+
+#[test]
+fn test_invalid_input() {
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let setting = Setting::Spglib;
+
+    // NaN in positions
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            1.0, 0.0, 0.0;
+            0.0, 1.0, 0.0;
+            0.0, 0.0, 1.0;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.5, f64::NAN, 0.5]],
+        vec![0, 0],
+    );
+    assert_eq!(
+        MoyoDataset::new(&cell, symprec, angle_tolerance, setting).unwrap_err(),
+        MoyoError::InvalidInputError
+    );
+
+    // Zero-volume lattice
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            1.0, 0.0, 0.0;
+            0.0, 1.0, 0.0;
+            1.0, 1.0, 0.0;
+        ]),
+        vec![vector![0.0, 0.0, 0.0]],
+        vec![0],
+    );
+    assert_eq!(
+        MoyoDataset::new(&cell, symprec, angle_tolerance, setting).unwrap_err(),
+        MoyoError::InvalidInputError
+    );
+}
+
+#[test]
+fn test_group_presentation() {
+    // P4 with a general position and a site on the four-fold axis
+    let (x, y, z) = (0.1, 0.3, 0.2);
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            4.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 5.0;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],
+            vector![x, y, z],
+            vector![-y, x, z],
+            vector![-x, -y, z],
+            vector![y, -x, z],
+        ],
+        vec![0, 1, 1, 1, 1],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 75);
+
+    let (generators, relations) = dataset.group_presentation().unwrap();
+    assert_eq!(generators.len(), 1);
+    let rotation = generators[0].rotation;
+    assert_ne!(rotation * rotation, Rotation::identity());
+    assert_eq!(rotation.pow(4), Rotation::identity());
+    assert_eq!(relations, vec![vec![1, 1, 1, 1]]);
+}
+
+#[test]
+fn test_refined_input_cell() {
+    // Slightly distorted conventional fcc
+    let a = 4.0;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a + 1e-3, 0.0, 0.0;
+            0.0, a - 1e-3, 2e-3;
+            0.0, 0.0, a;
+        ]),
+        vec![
+            vector![0.001, 0.0, 0.0],
+            vector![0.0, 0.5, 0.5 - 0.001],
+            vector![0.5, 0.0, 0.5],
+            vector![0.5, 0.5 + 0.002, 0.0],
+        ],
+        vec![0, 0, 0, 0],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-1, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 225);
+
+    let refined = dataset.refined_input_cell(&cell);
+    assert_eq!(refined.num_atoms(), 4);
+    assert_eq!(refined.numbers, cell.numbers);
+
+    // Perfect cubic metric in the original orientation
+    let metric_tensor = refined.lattice.metric_tensor();
+    assert_relative_eq!(
+        metric_tensor,
+        metric_tensor[(0, 0)] * Matrix3::identity(),
+        epsilon = 1e-8
+    );
+    assert_relative_eq!(refined.lattice.basis, cell.lattice.basis, epsilon = 1e-2);
+
+    // Sites form a perfect F-centered cubic lattice up to a common origin shift
+    let origin = refined.positions[0];
+    for (position, expected) in refined.positions.iter().zip([
+        vector![0.0, 0.0, 0.0],
+        vector![0.0, 0.5, 0.5],
+        vector![0.5, 0.0, 0.5],
+        vector![0.5, 0.5, 0.0],
+    ]) {
+        let diff = (position - origin - expected).map(|e| e - e.round());
+        assert_relative_eq!(diff, Vector3::zeros(), epsilon = 1e-8);
+    }
+    assert_eq!(
+        MoyoDataset::new(&refined, 1e-4, AngleTolerance::Default, Setting::Spglib)
+            .unwrap()
+            .number,
+        225
+    );
+}
+
+#[test]
+fn test_modulation_wavevector() {
+    // Alternating species along the c axis doubles the period of the average structure
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            3.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 10.0;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.0, 0.0, 0.5]],
+        vec![1, 2],
+    );
+    let symprec = 1e-4;
+    let (q, lattice) = modulation_wavevector(&cell, &[0, 0], symprec).unwrap();
+    assert_relative_eq!(q, vector![0.0, 0.0, 0.5], epsilon = 1e-8);
+    assert_relative_eq!(
+        lattice.volume(),
+        cell.lattice.volume() / 2.0,
+        epsilon = 1e-8
+    );
+    // Wavevector in cartesian coordinates, up to a reciprocal lattice vector of the average structure
+    let q_cartesian =
+        |q: &Vector3<f64>, lattice: &Lattice| lattice.basis.try_inverse().unwrap().transpose() * q;
+    assert_relative_eq!(
+        q_cartesian(&q, &lattice).abs(),
+        vector![0.0, 0.0, 0.1],
+        epsilon = 1e-8
+    );
+
+    // The wavevector refers to the returned lattice even for a skewed basis of the input cell
+    let skewed_cell = Cell::new(
+        Lattice::new(matrix![
+            3.0, 0.0, 3.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 10.0;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.0, 0.0, 0.5]],
+        vec![1, 2],
+    );
+    let (q, lattice) = modulation_wavevector(&skewed_cell, &[0, 0], symprec).unwrap();
+    let q_cartesian = q_cartesian(&q, &lattice);
+    // exp(2 pi i q.t) is -1 for the lattice vector of the average structure between the two sites
+    let t = skewed_cell
+        .lattice
+        .cartesian_coords(&vector![0.0, 0.0, 0.5]);
+    let phase = q_cartesian.dot(&t);
+    assert_relative_eq!(phase - phase.floor(), 0.5, epsilon = 1e-8);
+
+    // No modulation
+    assert!(modulation_wavevector(&cell, &[1, 2], symprec).is_none());
+    // Mismatched number of sites
+    assert!(modulation_wavevector(&cell, &[0], symprec).is_none());
+}
+
+#[test]
+fn test_max_operations() {
+    let cubic = Cell::new(
+        Lattice::new(matrix![
+            4.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 4.0;
+        ]),
+        vec![
+            vector![0.0, 0.0, 0.0],
+            vector![0.0, 0.5, 0.5],
+            vector![0.5, 0.0, 0.5],
+            vector![0.5, 0.5, 0.0],
+        ],
+        vec![0, 0, 0, 0],
+    );
+    let tetragonal = Cell::new(
+        Lattice::new(matrix![
+            4.0, 0.0, 0.0;
+            0.0, 4.0, 0.0;
+            0.0, 0.0, 5.0;
+        ]),
+        vec![vector![0.0, 0.0, 0.0]],
+        vec![0],
+    );
+    let builder = MoyoDatasetBuilder::new().max_operations(16);
+
+    // Fm-3m has 192 operations in the conventional cell, but the search stops once 5 of the 48 rotations, i.e. 5 * 4 operations, are found
+    assert_eq!(
+        builder.build(&cubic).unwrap_err(),
+        MoyoError::TooManyOperationsError { num_operations: 20 }
+    );
+    let dataset = builder.build(&tetragonal).unwrap();
+    assert_eq!(dataset.number, 123); // P4/mmm
+    assert_eq!(dataset.num_operations(), 16);
+}
+
+#[test]
+fn test_transformation_chain() {
+    // Face-centered orthorhombic cell given by a skewed supercell of a primitive cell
+    let (a, b, c) = (4.0, 5.0, 6.0);
+    let lattice = Lattice::new(matrix![
+        a, 0.0, 0.0;
+        0.0, b, 0.0;
+        0.0, 0.0, c;
+    ]);
+    let positions = vec![
+        vector![0.0, 0.0, 0.0],
+        vector![0.0, 0.5, 0.5],
+        vector![0.5, 0.0, 0.5],
+        vector![0.5, 0.5, 0.0],
+        vector![0.3, 0.0, 0.0],
+        vector![0.3, 0.5, 0.5],
+        vector![0.8, 0.0, 0.5],
+        vector![0.8, 0.5, 0.0],
+    ];
+    let numbers = vec![0, 0, 0, 0, 1, 1, 1, 1];
+    let cell = Cell::new(lattice, positions, numbers);
+    let skew = Transformation::new(
+        matrix![
+            1, 1, 0;
+            0, 1, 1;
+            0, 0, 1;
+        ],
+        vector![0.1, 0.2, 0.3],
+    );
+    let (cell, _) = skew.transform_cell(&cell);
+
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 42); // Fmm2
+    let chain = dataset.transformation_chain();
+    assert_relative_eq!(chain.primitive_linear.determinant(), 0.25, epsilon = 1e-8);
+    assert_relative_eq!(chain.centering_linear.determinant(), 4.0, epsilon = 1e-8);
+
+    let (std_linear, std_origin_shift) = chain.std_transformation();
+    assert_relative_eq!(std_linear, dataset.std_linear, epsilon = 1e-8);
+    assert_relative_eq!(std_origin_shift, dataset.std_origin_shift, epsilon = 1e-8);
+    let (prim_std_linear, prim_std_origin_shift) = chain.prim_std_transformation();
+    assert_relative_eq!(prim_std_linear, dataset.prim_std_linear, epsilon = 1e-8);
+    assert_relative_eq!(
+        prim_std_origin_shift,
+        dataset.prim_std_origin_shift,
+        epsilon = 1e-8
+    );
+}
+
+#[test]
+fn test_ita_transformation() {
+    // Primitive cell of C-centered monoclinic lattice (C2/m)
+    let (a, b, c) = (5.0, 6.0, 7.0);
+    let beta = 100.0_f64.to_radians();
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a / 2.0, -b / 2.0, 0.0;
+            a / 2.0, b / 2.0, 0.0;
+            c * beta.cos(), 0.0, c * beta.sin();
+        ]),
+        vec![vector![0.1, 0.1, 0.25]],
+        vec![0],
+    );
+    let dataset = MoyoDataset::new(&cell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert_eq!(dataset.number, 12);
+
+    let (linear, origin_shift) = dataset.ita_transformation().unwrap();
+    assert_relative_eq!(dataset.std_volume_ratio, 2.0, epsilon = 1e-8);
+    assert_eq!(linear.map(|e| e as f64).determinant().round(), 2.0);
+    assert_relative_eq!(linear.map(|e| e as f64), dataset.std_linear, epsilon = 1e-8);
+    for (p, expected) in origin_shift.iter().zip(dataset.std_origin_shift.iter()) {
+        assert_relative_eq!(p.to_f64(), *expected, epsilon = 1e-8);
+    }
+
+    // Origin shift is not close to fractions with small denominators
+    let shifted_cell = Cell::new(
+        cell.lattice.clone(),
+        vec![vector![0.1234567, 0.1234567, 0.25]],
+        vec![0],
+    );
+    let dataset = MoyoDataset::new(
+        &shifted_cell,
+        1e-4,
+        AngleTolerance::Default,
+        Setting::Spglib,
+    )
+    .unwrap();
+    assert!(dataset.ita_transformation().is_none());
+
+    // Non-integral std_linear for a supercell input
+    let supercell = cell
+        .make_supercell(&Matrix3::from_diagonal(&vector![2, 1, 1]))
+        .unwrap();
+    let dataset =
+        MoyoDataset::new(&supercell, 1e-4, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert!(!dataset.std_linear_is_integer());
+    assert!(dataset.ita_transformation().is_none());
 }
@@ -1,12 +1,15 @@
 #[macro_use]
 extern crate approx;
 
-use nalgebra::{matrix, vector, Matrix3};
+use nalgebra::{matrix, vector, Matrix3, Rotation3, Vector3};
+use std::f64::consts::PI;
 use test_log::test;
 
 use moyo::base::{
-    AngleTolerance, Collinear, Lattice, MagneticCell, MagneticMoment, RotationMagneticMomentAction,
+    AngleTolerance, Cell, Collinear, Lattice, MagSymprec, MagneticCell, MagneticMoment, MoyoError,
+    NonCollinear, RotationMagneticMomentAction,
 };
+use moyo::data::ConstructType;
 use moyo::MoyoMagneticDataset;
 
 /// Sanity-check MoyoMagneticDataset
@@ -163,6 +166,24 @@ fn test_with_rutile() {
 
         assert_eq!(dataset.uni_number, 1158);
         assert_eq!(dataset.num_magnetic_operations(), 16);
+
+        // Deterministic ordering of magnetic operations
+        let expect = format!("{:?}", dataset.magnetic_operations);
+        assert_eq!(
+            format!("{:?}", dataset.sorted_magnetic_operations()),
+            expect
+        );
+        for _ in 0..4 {
+            let other = MoyoMagneticDataset::new(
+                &magnetic_cell,
+                symprec,
+                angle_tolerance,
+                mag_symprec,
+                action,
+            )
+            .unwrap();
+            assert_eq!(format!("{:?}", other.magnetic_operations), expect);
+        }
         assert_eq!(dataset.orbits, vec![0, 0, 2, 2, 2, 2]);
         assert_eq!(dataset.std_mag_cell.num_atoms(), 6);
         assert_eq!(dataset.prim_std_mag_cell.num_atoms(), 6);
@@ -230,4 +251,606 @@ fn test_with_rutile_type4() {
     );
 
     assert_eq!(dataset.uni_number, 932);
+
+    let cosets = dataset.cosets();
+    assert_eq!(cosets.len(), 2);
+    assert_eq!(cosets[0].len(), cosets[1].len());
+    assert!(cosets[0].iter().all(|mops| !mops.time_reversal));
+    assert!(cosets[1].iter().all(|mops| mops.time_reversal));
+}
+
+#[test]
+fn test_with_paramagnetic_bcc() {
+    // Reference space group is body-centered, so its conventional and primitive cells differ
+    let lattice = Lattice::new(matrix![
+        3.0, 0.0, 0.0;
+        0.0, 3.0, 0.0;
+        0.0, 0.0, 3.0;
+    ]);
+    let positions = vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]];
+    let numbers = vec![0, 0];
+    let magmoms = vec![Collinear(0.0), Collinear(0.0)];
+    let magnetic_cell = MagneticCell::new(lattice, positions, numbers, magmoms);
+
+    let dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        1e-4,
+        AngleTolerance::Default,
+        None,
+        RotationMagneticMomentAction::Axial,
+    );
+    assert_eq!(dataset.prim_std_mag_cell.num_atoms(), 1);
+    assert_eq!(dataset.std_mag_cell.num_atoms(), 2);
+}
+
+#[test]
+fn test_magnetic_type() {
+    let lattice = Lattice::new(matrix![
+        3.0, 0.0, 0.0;
+        0.0, 3.0, 0.0;
+        0.0, 0.0, 3.0;
+    ]);
+    let positions = vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]];
+    let numbers = vec![0, 0];
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let action = RotationMagneticMomentAction::Axial;
+
+    // Antiferromagnetic bcc: body-centering translation is combined with time reversal
+    let afm_cell = MagneticCell::new(
+        lattice.clone(),
+        positions.clone(),
+        numbers.clone(),
+        vec![Collinear(1.0), Collinear(-1.0)],
+    );
+    let dataset = assert_magnetic_dataset(&afm_cell, symprec, angle_tolerance, None, action);
+    assert_eq!(dataset.magnetic_type(), ConstructType::Type4);
+    assert!(!dataset.is_gray_group());
+
+    // Paramagnetic bcc
+    let paramagnetic_cell = MagneticCell::new(
+        lattice,
+        positions,
+        numbers,
+        vec![Collinear(0.0), Collinear(0.0)],
+    );
+    let dataset =
+        assert_magnetic_dataset(&paramagnetic_cell, symprec, angle_tolerance, None, action);
+    assert_eq!(dataset.magnetic_type(), ConstructType::Type2);
+    assert!(dataset.is_gray_group());
+}
+
+#[test]
+fn test_symmetrize_axial_tensor() {
+    let lattice = Lattice::new(matrix![
+        3.0, 0.0, 0.0;
+        0.0, 3.0, 0.0;
+        0.0, 0.0, 4.0;
+    ]);
+    // Polar structure without inversion: P4mm
+    let positions = vec![vector![0.0, 0.0, 0.0], vector![0.0, 0.0, 0.3]];
+    let numbers = vec![0, 1];
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let mag_symprec = None;
+    let action = RotationMagneticMomentAction::Axial;
+
+    let tensor_zz = matrix![
+        0.0, 0.0, 0.0;
+        0.0, 0.0, 0.0;
+        0.0, 0.0, 1.0;
+    ];
+    let tensor_xx = matrix![
+        1.0, 0.0, 0.0;
+        0.0, 0.0, 0.0;
+        0.0, 0.0, 0.0;
+    ];
+
+    {
+        // Ferromagnet along z: P4m'm'
+        let magnetic_cell = MagneticCell::new(
+            lattice.clone(),
+            positions.clone(),
+            numbers.clone(),
+            vec![
+                NonCollinear(vector![0.0, 0.0, 1.0]),
+                NonCollinear(vector![0.0, 0.0, 0.0]),
+            ],
+        );
+        let dataset = assert_magnetic_dataset(
+            &magnetic_cell,
+            symprec,
+            angle_tolerance,
+            mag_symprec,
+            action,
+        );
+        assert_eq!(dataset.num_magnetic_operations(), 8);
+        // Component along the magnetization axis is allowed
+        assert_relative_eq!(
+            dataset.symmetrize_axial_tensor(tensor_zz),
+            tensor_zz,
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(
+            dataset.symmetrize_axial_tensor(tensor_xx),
+            matrix![
+                0.5, 0.0, 0.0;
+                0.0, 0.5, 0.0;
+                0.0, 0.0, 0.0;
+            ],
+            epsilon = 1e-8
+        );
+    }
+
+    {
+        // Paramagnet: gray group
+        let magnetic_cell = MagneticCell::new(
+            lattice.clone(),
+            positions.clone(),
+            numbers.clone(),
+            vec![
+                NonCollinear(vector![0.0, 0.0, 0.0]),
+                NonCollinear(vector![0.0, 0.0, 0.0]),
+            ],
+        );
+        let dataset = assert_magnetic_dataset(
+            &magnetic_cell,
+            symprec,
+            angle_tolerance,
+            mag_symprec,
+            action,
+        );
+        assert_relative_eq!(
+            dataset.symmetrize_axial_tensor(tensor_zz),
+            Matrix3::zeros(),
+            epsilon = 1e-8
+        );
+    }
+}
+
+#[test]
+fn test_from_mcif() {
+    // Antiferromagnetic MnF2 with moments along the c axis, listed in P1
+    let mcif = r#"
+data_MnF2
+_cell_length_a 4.8734(1)
+_cell_length_b 4.8734(1)
+_cell_length_c 3.3099(1)
+_cell_angle_alpha 90.0
+_cell_angle_beta 90.0
+_cell_angle_gamma 90.0
+_space_group_magn.name_BNS "P 1"
+
+loop_
+_space_group_symop_magn_operation.id
+_space_group_symop_magn_operation.xyz
+1 x,y,z,+1
+
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+Mn1 Mn2+ 0.00000 0.00000 0.00000
+Mn2 Mn2+ 0.50000 0.50000 0.50000
+F1 F1- 0.30500 0.30500 0.00000
+F2 F1- 0.69500 0.69500 0.00000
+F3 F1- 0.19500 0.80500 0.50000
+F4 F1- 0.80500 0.19500 0.50000
+
+loop_
+_atom_site_moment.label
+_atom_site_moment.crystalaxis_x
+_atom_site_moment.crystalaxis_y
+_atom_site_moment.crystalaxis_z
+Mn1 0.0 0.0 4.6
+Mn2 0.0 0.0 -4.6
+"#;
+    let magnetic_cell = MagneticCell::<NonCollinear>::from_mcif(mcif).unwrap();
+    assert_eq!(magnetic_cell.num_atoms(), 6);
+    assert_eq!(magnetic_cell.cell.numbers, vec![0, 0, 1, 1, 1, 1]);
+    assert_eq!(
+        magnetic_cell.cell.element_symbols().unwrap(),
+        &["Mn".to_string(), "F".to_string()]
+    );
+    assert_relative_eq!(
+        magnetic_cell.magnetic_moments[1].0,
+        vector![0.0, 0.0, -4.6],
+        epsilon = 1e-8
+    );
+
+    let dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        1e-4,
+        AngleTolerance::Default,
+        None,
+        RotationMagneticMomentAction::Axial,
+    );
+    assert_eq!(dataset.uni_number, 1159); // P4_2'/mnm' (136.499)
+}
+
+#[test]
+fn test_canonicalize_moments() {
+    // Ferromagnetic moment along c with a spurious in-plane component
+    let lattice = Lattice::new(matrix![
+        3.0, 0.0, 0.0;
+        0.0, 3.0, 0.0;
+        0.0, 0.0, 4.0;
+    ]);
+    let positions = vec![vector![0.0, 0.0, 0.0]];
+    let numbers = vec![0];
+    let magmoms = vec![NonCollinear(vector![1e-3, 0.0, 1.0])];
+    let magnetic_cell = MagneticCell::new(lattice, positions, numbers, magmoms);
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let mag_symprec = Some(1e-5);
+    let action = RotationMagneticMomentAction::Axial;
+
+    let raw_dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        symprec,
+        angle_tolerance,
+        mag_symprec,
+        action,
+    );
+
+    let canonicalized = magnetic_cell.canonicalize_moments(1e-2);
+    assert_relative_eq!(canonicalized.magnetic_moments[0].0, vector![0.0, 0.0, 1.0]);
+    let dataset = assert_magnetic_dataset(
+        &canonicalized,
+        symprec,
+        angle_tolerance,
+        mag_symprec,
+        action,
+    );
+
+    // P4/mm'm'
+    assert_eq!(dataset.num_magnetic_operations(), 16);
+    assert!(raw_dataset.num_magnetic_operations() < dataset.num_magnetic_operations());
+}
+
+#[test]
+fn test_symmetrized_moments() {
+    // Collinear antiferromagnet on a CsCl-type lattice with noisy moments
+    let lattice = Lattice::new(Matrix3::identity());
+    let positions = vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]];
+    let numbers = vec![0, 0];
+    let magmoms = vec![Collinear(1.01), Collinear(-0.99)];
+    let magnetic_cell = MagneticCell::new(lattice, positions, numbers, magmoms);
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let mag_symprec = Some(0.1);
+    let action = RotationMagneticMomentAction::Polar;
+
+    let dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        symprec,
+        angle_tolerance,
+        mag_symprec,
+        action,
+    );
+
+    let symmetrized_moments = dataset.symmetrized_moments();
+    assert_eq!(symmetrized_moments.len(), dataset.std_mag_cell.num_atoms());
+    for moment in symmetrized_moments.iter() {
+        assert_relative_eq!(moment.0.abs(), 1.0, epsilon = 1e-8);
+    }
+    assert_relative_eq!(
+        symmetrized_moments.iter().map(|m| m.0).sum::<f64>(),
+        0.0,
+        epsilon = 1e-8
+    );
+}
+
+#[test]
+fn test_relative_mag_symprec() {
+    // Collinear antiferromagnets on a CsCl-type lattice with 1% noise in moments
+    let lattice = Lattice::new(Matrix3::identity());
+    let positions = vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]];
+    let numbers = vec![0, 0];
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let action = RotationMagneticMomentAction::Polar;
+
+    let ideal_cell = MagneticCell::new(
+        lattice.clone(),
+        positions.clone(),
+        numbers.clone(),
+        vec![Collinear(1.0), Collinear(-1.0)],
+    );
+    let ideal_dataset =
+        MoyoMagneticDataset::new(&ideal_cell, symprec, angle_tolerance, None, action).unwrap();
+
+    for magnitude in [0.1, 5.0] {
+        let magmoms = vec![Collinear(1.01 * magnitude), Collinear(-0.99 * magnitude)];
+        let magnetic_cell =
+            MagneticCell::new(lattice.clone(), positions.clone(), numbers.clone(), magmoms);
+
+        let dataset = MoyoMagneticDataset::with_mag_symprec(
+            &magnetic_cell,
+            symprec,
+            angle_tolerance,
+            Some(MagSymprec::Relative(0.05)),
+            action,
+        )
+        .unwrap();
+        assert_eq!(dataset.uni_number, ideal_dataset.uni_number);
+        assert_eq!(
+            dataset.num_magnetic_operations(),
+            ideal_dataset.num_magnetic_operations()
+        );
+        assert_relative_eq!(dataset.mag_symprec, 0.05 * 1.01 * magnitude, epsilon = 1e-8);
+    }
+}
+
+#[test]
+fn test_with_rotated_rutile() {
+    // Rutile with ferromagnetic Ti moments along c, rotated about the x axis
+    let rotation = Rotation3::from_axis_angle(&Vector3::x_axis(), PI / 6.0);
+    let basis = Matrix3::from_diagonal(&vector![4.6, 4.6, 3.0]);
+    let lattice = Lattice::new((rotation * basis).transpose());
+    let positions = vec![
+        // Ti (2a)
+        vector![0.0, 0.0, 0.0],
+        vector![0.5, 0.5, 0.5],
+        // O (4f)
+        vector![0.3, 0.3, 0.0],
+        vector![0.7, 0.7, 0.0],
+        vector![0.2, 0.8, 0.5],
+        vector![0.8, 0.2, 0.5],
+    ];
+    let numbers = vec![0, 0, 1, 1, 1, 1];
+    let moment = rotation * vector![0.0, 0.0, 0.7];
+    let magmoms = vec![
+        NonCollinear(moment),
+        NonCollinear(moment),
+        NonCollinear(Vector3::zeros()),
+        NonCollinear(Vector3::zeros()),
+        NonCollinear(Vector3::zeros()),
+        NonCollinear(Vector3::zeros()),
+    ];
+    let magnetic_cell = MagneticCell::new(lattice, positions, numbers, magmoms);
+
+    let dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        1e-4,
+        AngleTolerance::Default,
+        None,
+        RotationMagneticMomentAction::Axial,
+    );
+    // Standardized moments keep their magnitude and are along the c axis of the standardized cell
+    let c_axis = dataset
+        .std_mag_cell
+        .cell
+        .lattice
+        .basis
+        .column(2)
+        .normalize();
+    for (moment, number) in dataset
+        .std_mag_cell
+        .magnetic_moments
+        .iter()
+        .zip(dataset.std_mag_cell.cell.numbers.iter())
+    {
+        if *number == 0 {
+            assert_relative_eq!(moment.0.norm(), 0.7, epsilon = 1e-8);
+            assert_relative_eq!(moment.0.cross(&c_axis).norm(), 0.0, epsilon = 1e-8);
+        }
+    }
+}
+
+#[test]
+fn test_transform_moments_to_std() {
+    // Rutile with ferromagnetic Ti moments along c, rotated about the x axis
+    let rotation = Rotation3::from_axis_angle(&Vector3::x_axis(), PI / 6.0);
+    let basis = Matrix3::from_diagonal(&vector![4.6, 4.6, 3.0]);
+    let lattice = Lattice::new((rotation * basis).transpose());
+    let positions = vec![
+        // Ti (2a)
+        vector![0.0, 0.0, 0.0],
+        vector![0.5, 0.5, 0.5],
+        // O (4f)
+        vector![0.3, 0.3, 0.0],
+        vector![0.7, 0.7, 0.0],
+        vector![0.2, 0.8, 0.5],
+        vector![0.8, 0.2, 0.5],
+    ];
+    let numbers = vec![0, 0, 1, 1, 1, 1];
+    let moment = rotation * vector![0.0, 0.0, 0.7];
+    let magmoms = vec![
+        NonCollinear(moment),
+        NonCollinear(moment),
+        NonCollinear(Vector3::zeros()),
+        NonCollinear(Vector3::zeros()),
+        NonCollinear(Vector3::zeros()),
+        NonCollinear(Vector3::zeros()),
+    ];
+    let magnetic_cell = MagneticCell::new(lattice, positions, numbers, magmoms.clone());
+
+    let dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        1e-4,
+        AngleTolerance::Default,
+        None,
+        RotationMagneticMomentAction::Axial,
+    );
+    let std_moments = dataset.transform_moments_to_std(&magmoms);
+    assert_eq!(std_moments.len(), dataset.std_mag_cell.num_atoms());
+    for (actual, expect) in std_moments
+        .iter()
+        .zip(dataset.std_mag_cell.magnetic_moments.iter())
+    {
+        assert_relative_eq!(actual.0, expect.0, epsilon = 1e-8);
+    }
+    // Moments are along the c axis of the standardized cell
+    assert_relative_eq!(std_moments[0].0.norm(), 0.7, epsilon = 1e-8);
+    assert_relative_eq!(
+        std_moments[0]
+            .0
+            .cross(&dataset.std_mag_cell.cell.lattice.basis.column(2))
+            .norm(),
+        0.0,
+        epsilon = 1e-8
+    );
+}
+
+#[test]
+fn test_with_nonmagnetic_sites() {
+    // Rocksalt FeO with type-I antiferromagnetic order of Fe along the c axis
+    let a = 4.3;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![
+            // Fe
+            vector![0.0, 0.0, 0.0],
+            vector![0.5, 0.5, 0.0],
+            vector![0.0, 0.5, 0.5],
+            vector![0.5, 0.0, 0.5],
+            // O
+            vector![0.5, 0.5, 0.5],
+            vector![0.0, 0.0, 0.5],
+            vector![0.5, 0.0, 0.0],
+            vector![0.0, 0.5, 0.0],
+        ],
+        vec![26, 26, 26, 26, 8, 8, 8, 8],
+    );
+    let magmoms = vec![
+        Some(Collinear(1.0)),
+        Some(Collinear(1.0)),
+        Some(Collinear(-1.0)),
+        Some(Collinear(-1.0)),
+        None,
+        None,
+        None,
+        None,
+    ];
+    let magnetic_cell = MagneticCell::from_optional_moments(cell.clone(), magmoms).unwrap();
+    assert_eq!(magnetic_cell.magnetic_sites(), vec![0, 1, 2, 3]);
+    // The zero magnetic moment cannot be determined without any magnetic site
+    assert_eq!(
+        MagneticCell::<Collinear>::from_optional_moments(cell.clone(), vec![None; 8]).err(),
+        Some(MoyoError::InvalidInputError)
+    );
+
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let mag_symprec = None;
+    let action = RotationMagneticMomentAction::Axial;
+    let dataset = assert_magnetic_dataset(
+        &magnetic_cell,
+        symprec,
+        angle_tolerance,
+        mag_symprec,
+        action,
+    );
+
+    // Same as explicitly giving zero moments to O
+    let explicit = MagneticCell::from_cell(
+        cell,
+        vec![
+            Collinear(1.0),
+            Collinear(1.0),
+            Collinear(-1.0),
+            Collinear(-1.0),
+            Collinear(0.0),
+            Collinear(0.0),
+            Collinear(0.0),
+            Collinear(0.0),
+        ],
+    );
+    let explicit_dataset =
+        assert_magnetic_dataset(&explicit, symprec, angle_tolerance, mag_symprec, action);
+    assert_eq!(dataset.uni_number, explicit_dataset.uni_number);
+    assert_eq!(
+        dataset.num_magnetic_operations(),
+        explicit_dataset.num_magnetic_operations()
+    );
+    assert_eq!(dataset.magnetic_type(), ConstructType::Type4);
+}
+
+/// Collinear magnetic moment implementing only the required methods of `MagneticMoment`
+#[derive(Debug, Clone)]
+struct MinimalCollinear(f64);
+
+impl MagneticMoment for MinimalCollinear {
+    fn act_rotation(
+        &self,
+        cartesian_rotation: &Matrix3<f64>,
+        action: RotationMagneticMomentAction,
+    ) -> Self {
+        match action {
+            RotationMagneticMomentAction::Polar => self.clone(),
+            RotationMagneticMomentAction::Axial => {
+                Self(cartesian_rotation.determinant().round() * self.0)
+            }
+        }
+    }
+
+    fn act_time_reversal(&self, time_reversal: bool) -> Self {
+        if time_reversal {
+            Self(-self.0)
+        } else {
+            self.clone()
+        }
+    }
+
+    fn is_close(&self, other: &Self, mag_symprec: f64) -> bool {
+        (self.0 - other.0).abs() < mag_symprec
+    }
+
+    fn average(magnetic_moments: &[Self]) -> Self {
+        Self(magnetic_moments.iter().map(|m| m.0).sum::<f64>() / magnetic_moments.len() as f64)
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.0.abs()
+    }
+}
+
+#[test]
+fn test_default_magnetic_moment_methods() {
+    // Tiny moments are kept by the default `canonicalize`
+    assert_eq!(MinimalCollinear(1e-9).canonicalize(1e-6).0, 1e-9);
+    assert_eq!(MinimalCollinear(-2.2).zero_like().0, 0.0);
+    assert_relative_eq!(
+        MagSymprec::Relative(0.1).absolute(&[MinimalCollinear(2.2), MinimalCollinear(-1.0)]),
+        0.22,
+        epsilon = 1e-12
+    );
+
+    // Antiferromagnetic bcc Fe
+    let a = 2.87;
+    let cell = Cell::new(
+        Lattice::new(matrix![
+            a, 0.0, 0.0;
+            0.0, a, 0.0;
+            0.0, 0.0, a;
+        ]),
+        vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+        vec![26, 26],
+    );
+    let symprec = 1e-4;
+    let angle_tolerance = AngleTolerance::Default;
+    let action = RotationMagneticMomentAction::Axial;
+    let minimal = MagneticCell::from_optional_moments(
+        cell.clone(),
+        vec![Some(MinimalCollinear(2.2)), Some(MinimalCollinear(-2.2))],
+    )
+    .unwrap()
+    .canonicalize_moments(1e-6);
+    let collinear = MagneticCell::from_cell(cell, vec![Collinear(2.2), Collinear(-2.2)]);
+
+    let dataset = assert_magnetic_dataset(&minimal, symprec, angle_tolerance, None, action);
+    let expect = assert_magnetic_dataset(&collinear, symprec, angle_tolerance, None, action);
+    assert_eq!(dataset.uni_number, expect.uni_number);
+    assert_eq!(minimal.magnetic_sites(), vec![0, 1]);
 }
@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use nalgebra::{matrix, vector};
+
+use moyo::base::{AngleTolerance, Cell, Lattice};
+use moyo::data::Setting;
+use moyo::MoyoDataset;
+
+/// Logger that keeps warning messages to inspect them in tests
+struct CapturingLogger {
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.warnings
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    warnings: Mutex::new(Vec::new()),
+};
+
+/// The global logger can be set only once, so this binary has a single test
+#[test]
+fn test_warn_too_close_atoms() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Warn);
+
+    let symprec = 1e-4;
+    let lattice = Lattice::new(matrix![
+        3.0, 0.0, 0.0;
+        0.0, 3.0, 0.0;
+        0.0, 0.0, 3.0;
+    ]);
+
+    // Well-separated atoms
+    let cell = Cell::new(
+        lattice.clone(),
+        vec![vector![0.0, 0.0, 0.0], vector![0.5, 0.5, 0.5]],
+        vec![0, 1],
+    );
+    MoyoDataset::new(&cell, symprec, AngleTolerance::Default, Setting::Spglib).unwrap();
+    assert!(LOGGER.warnings.lock().unwrap().is_empty());
+
+    // Two overlapping atoms within symprec are only warned, not errored
+    let cell = Cell::new(
+        lattice,
+        vec![vector![0.0, 0.0, 0.0], vector![0.99999, 0.0, 0.0]],
+        vec![0, 1],
+    );
+    MoyoDataset::new(&cell, symprec, AngleTolerance::Default, Setting::Spglib).unwrap();
+    let warnings = LOGGER.warnings.lock().unwrap();
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.contains("Sites 0 and 1 are unphysically close")));
+}
@@ -1,5 +1,10 @@
 from __future__ import annotations
 
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from ase import Atoms
+
 __version__: str
 
 ###############################################################################
@@ -22,6 +27,59 @@ class Cell:
     def serialize_json(self) -> str: ...
     @classmethod
     def deserialize_json(cls, json_str: str) -> Cell: ...
+    @classmethod
+    def from_ase(cls, atoms: "Atoms") -> Cell: ...
+    def to_ase(self) -> "Atoms": ...
+
+class CollinearMagneticCell:
+    def __init__(
+        self,
+        basis: list[list[float]],
+        positions: list[list[float]],
+        numbers: list[int],
+        magnetic_moments: list[float],
+    ): ...
+    @property
+    def basis(self) -> list[list[float]]: ...
+    @property
+    def positions(self) -> list[list[float]]: ...
+    @property
+    def numbers(self) -> list[int]: ...
+    @property
+    def magnetic_moments(self) -> list[float]: ...
+    @property
+    def num_atoms(self) -> int: ...
+    def serialize_json(self) -> str: ...
+    @classmethod
+    def deserialize_json(cls, json_str: str) -> CollinearMagneticCell: ...
+    @classmethod
+    def from_ase(cls, atoms: "Atoms") -> CollinearMagneticCell: ...
+    def to_ase(self) -> "Atoms": ...
+
+class NonCollinearMagneticCell:
+    def __init__(
+        self,
+        basis: list[list[float]],
+        positions: list[list[float]],
+        numbers: list[int],
+        magnetic_moments: list[list[float]],
+    ): ...
+    @property
+    def basis(self) -> list[list[float]]: ...
+    @property
+    def positions(self) -> list[list[float]]: ...
+    @property
+    def numbers(self) -> list[int]: ...
+    @property
+    def magnetic_moments(self) -> list[list[float]]: ...
+    @property
+    def num_atoms(self) -> int: ...
+    def serialize_json(self) -> str: ...
+    @classmethod
+    def deserialize_json(cls, json_str: str) -> NonCollinearMagneticCell: ...
+    @classmethod
+    def from_ase(cls, atoms: "Atoms") -> NonCollinearMagneticCell: ...
+    def to_ase(self) -> "Atoms": ...
 
 class Operations:
     @property
@@ -44,12 +102,14 @@ class Setting:
     @classmethod
     def standard(cls) -> Setting:
         """Unique axis b, cell choice 1 for monoclinic, hexagonal axes for rhombohedral,
-        and origin choice 2 for centrosymmetric space groups."""
+        and origin choice 2 for centrosymmetric space groups.
+        These are the ITA standard settings, which are also used by the Bilbao Crystallographic Server."""
     @classmethod
     def hall_number(cls, hall_number: int) -> Setting:
         """Specific Hall number from 1 to 530."""
 
 def operations_from_number(number: int, setting: Setting) -> Operations: ...
+def space_groups_for_arithmetic_class(arithmetic_number: int) -> list[int]: ...
 
 class Centering: ...
 
@@ -81,6 +141,33 @@ class HallSymbolEntry:
     def centering(self) -> Centering:
         """Centering."""
 
+###############################################################################
+# identify
+###############################################################################
+
+class PointGroup:
+    """Crystallographic point group with group-type information."""
+    def __init__(self, prim_rotations: list[list[list[int]]]):
+        """
+        Parameters
+        ----------
+        prim_rotations: list[list[list[int]]]
+            Rotations in the (reduced) primitive basis.
+        """
+    @property
+    def arithmetic_number(self) -> int:
+        """Number for arithmetic crystal classes (1 - 73)."""
+    @property
+    def prim_trans_mat(self) -> list[list[int]]:
+        """Transformation matrix to the representative for `arithmetic_number` in the primitive
+        basis."""
+    @property
+    def hermann_mauguin(self) -> str:
+        """Hermann-Mauguin symbol of the point group."""
+    @property
+    def order(self) -> int:
+        """Number of rotations in the point group."""
+
 ###############################################################################
 # lib
 ###############################################################################
@@ -113,6 +200,12 @@ class MoyoDataset:
     @property
     def hall_number(self) -> int:
         """Hall symbol number."""
+    @property
+    def arithmetic_number(self) -> int:
+        """Arithmetic crystal class number."""
+    @property
+    def arithmetic_symbol(self) -> str:
+        """Symbol of the arithmetic crystal class, e.g. "4/mmmP"."""
     # Symmetry operations in the input cell
     @property
     def operations(self) -> Operations:
@@ -0,0 +1,36 @@
+from __future__ import annotations
+
+import numpy as np
+import pytest
+
+import moyopy
+
+ase_build = pytest.importorskip("ase.build")
+
+
+def test_ase_round_trip():
+    atoms = ase_build.bulk("Si", "diamond", a=5.43)
+    cell = moyopy.Cell.from_ase(atoms)
+    assert cell.numbers == [14, 14]
+    dataset = moyopy.MoyoDataset(cell)
+    assert dataset.number == 227
+
+    new_atoms = cell.to_ase()
+    assert np.allclose(new_atoms.cell.array, atoms.cell.array)
+    assert np.allclose(new_atoms.get_scaled_positions(), atoms.get_scaled_positions())
+    assert np.all(new_atoms.get_atomic_numbers() == atoms.get_atomic_numbers())
+
+
+def test_ase_magnetic_round_trip():
+    atoms = ase_build.bulk("Fe", "bcc", a=2.87, cubic=True)
+    atoms.set_initial_magnetic_moments([1.0, -1.0])
+    magnetic_cell = moyopy.CollinearMagneticCell.from_ase(atoms)
+    assert magnetic_cell.magnetic_moments == [1.0, -1.0]
+    new_atoms = magnetic_cell.to_ase()
+    assert np.allclose(new_atoms.get_initial_magnetic_moments(), [1.0, -1.0])
+
+    atoms.set_initial_magnetic_moments([[0.0, 0.0, 1.0], [0.0, 0.0, -1.0]])
+    magnetic_cell = moyopy.NonCollinearMagneticCell.from_ase(atoms)
+    assert np.allclose(magnetic_cell.magnetic_moments, [[0.0, 0.0, 1.0], [0.0, 0.0, -1.0]])
+    new_atoms = magnetic_cell.to_ase()
+    assert np.allclose(new_atoms.get_initial_magnetic_moments(), magnetic_cell.magnetic_moments)
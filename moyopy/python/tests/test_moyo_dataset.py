@@ -7,9 +7,23 @@ def test_moyo_dataset(wurtzite: moyopy.Cell):
     dataset = moyopy.MoyoDataset(wurtzite)
     assert dataset.number == 186
     assert dataset.hall_number == 480
+    assert dataset.arithmetic_number == 55
+    assert dataset.arithmetic_symbol == "6mmP"
 
 
 def test_serialization(wurtzite: moyopy.Cell):
     serialized = wurtzite.serialize_json()
     deserialized = moyopy.Cell.deserialize_json(serialized)
     assert len(wurtzite.positions) == len(deserialized.positions)
+
+
+def test_magnetic_cell_serialization():
+    basis = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+    positions = [[0.0, 0.0, 0.0], [0.5, 0.5, 0.5]]
+    numbers = [1, 1]
+    magnetic_moments = [[0.0, 0.0, 1.5], [0.0, 0.0, -1.5]]
+    magnetic_cell = moyopy.NonCollinearMagneticCell(basis, positions, numbers, magnetic_moments)
+
+    serialized = magnetic_cell.serialize_json()
+    deserialized = moyopy.NonCollinearMagneticCell.deserialize_json(serialized)
+    assert deserialized.magnetic_moments == magnetic_moments
@@ -2,7 +2,7 @@ from __future__ import annotations
 
 import numpy as np
 
-from moyopy import Operations, operations_from_number
+from moyopy import Operations, operations_from_number, space_groups_for_arithmetic_class
 
 
 def _unique_sites_in_cell(position, operations: Operations) -> np.ndarray:
@@ -27,3 +27,8 @@ def test_operations_from_number():
     assert len(operations) == num_operations
 
     assert len(_unique_sites_in_cell([1 / 8, 1 / 8, 1 / 8], operations)) == 16
+
+
+def test_space_groups_for_arithmetic_class():
+    # 4/mmmP
+    assert space_groups_for_arithmetic_class(36) == list(range(123, 139))
@@ -1,12 +1,14 @@
 use nalgebra::{OMatrix, RowVector3, Vector3};
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyImportError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::types::{PyDict, PyType};
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_json;
 
-use moyo::base::{Cell, Lattice, MoyoError, Operations};
+use moyo::base::{
+    Cell, Collinear, Lattice, MagneticCell, MoyoError, NonCollinear, Operations, Position,
+};
 
 // Unfortunately, "PyCell" is already reversed by pyo3...
 #[derive(Debug, Clone)]
@@ -73,6 +75,18 @@ impl PyStructure {
         serde_json::from_str(s).map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    /// Create a cell from an ASE `Atoms` object with its cell matrix, scaled positions, and atomic numbers.
+    #[classmethod]
+    pub fn from_ase(_cls: &Bound<'_, PyType>, atoms: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (basis, positions, numbers) = cell_from_ase(atoms)?;
+        Self::new(basis, positions, numbers)
+    }
+
+    /// Convert to an ASE `Atoms` object with periodic boundary conditions.
+    pub fn to_ase<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        ase_atoms(py, self.basis(), self.positions(), self.numbers(), None)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Cell(basis={:?}, positions={:?}, numbers={:?})",
@@ -117,6 +131,344 @@ impl<'de> Deserialize<'de> for PyStructure {
     }
 }
 
+fn lattice_from_row_basis(basis: [[f64; 3]; 3]) -> Lattice {
+    Lattice::new(OMatrix::from_rows(&[
+        RowVector3::from(basis[0]),
+        RowVector3::from(basis[1]),
+        RowVector3::from(basis[2]),
+    ]))
+}
+
+fn positions_from_arrays(positions: &[[f64; 3]]) -> Vec<Position> {
+    positions
+        .iter()
+        .map(|x| Vector3::new(x[0], x[1], x[2]))
+        .collect()
+}
+
+fn import_ase(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+    PyModule::import_bound(py, "ase").map_err(|_| {
+        PyImportError::new_err("Try installing dependencies with `pip install moyopy[interface]`")
+    })
+}
+
+/// Return row-wise basis vectors, scaled positions, and atomic numbers of an ASE `Atoms` object
+#[allow(clippy::type_complexity)]
+fn cell_from_ase(atoms: &Bound<'_, PyAny>) -> PyResult<([[f64; 3]; 3], Vec<[f64; 3]>, Vec<i32>)> {
+    let basis = atoms
+        .getattr("cell")?
+        .getattr("array")?
+        .call_method0("tolist")?
+        .extract()?;
+    let positions = atoms
+        .call_method0("get_scaled_positions")?
+        .call_method0("tolist")?
+        .extract()?;
+    let numbers = atoms
+        .call_method0("get_atomic_numbers")?
+        .call_method0("tolist")?
+        .extract()?;
+    Ok((basis, positions, numbers))
+}
+
+fn ase_atoms<'py>(
+    py: Python<'py>,
+    basis: [[f64; 3]; 3],
+    positions: Vec<[f64; 3]>,
+    numbers: Vec<i32>,
+    magnetic_moments: Option<PyObject>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("cell", basis)?;
+    kwargs.set_item("scaled_positions", positions)?;
+    kwargs.set_item("numbers", numbers)?;
+    kwargs.set_item("pbc", true)?;
+    if let Some(magnetic_moments) = magnetic_moments {
+        kwargs.set_item("magmoms", magnetic_moments)?;
+    }
+    import_ase(py)?.getattr("Atoms")?.call((), Some(&kwargs))
+}
+
+#[derive(Debug, Clone)]
+#[pyclass(name = "CollinearMagneticCell", frozen)]
+#[pyo3(module = "moyopy")]
+pub struct PyCollinearMagneticCell(MagneticCell<Collinear>);
+
+#[pymethods]
+impl PyCollinearMagneticCell {
+    #[new]
+    /// basis: row-wise basis vectors
+    pub fn new(
+        basis: [[f64; 3]; 3],
+        positions: Vec<[f64; 3]>,
+        numbers: Vec<i32>,
+        magnetic_moments: Vec<f64>,
+    ) -> PyResult<Self> {
+        if positions.len() != numbers.len() || positions.len() != magnetic_moments.len() {
+            return Err(PyValueError::new_err(
+                "positions, numbers, and magnetic_moments should be the same length",
+            ));
+        }
+
+        let magnetic_moments = magnetic_moments.into_iter().map(Collinear).collect();
+        Ok(Self(MagneticCell::new(
+            lattice_from_row_basis(basis),
+            positions_from_arrays(&positions),
+            numbers,
+            magnetic_moments,
+        )))
+    }
+
+    #[getter]
+    pub fn basis(&self) -> [[f64; 3]; 3] {
+        *self.0.cell.lattice.basis.as_ref()
+    }
+
+    #[getter]
+    pub fn positions(&self) -> Vec<[f64; 3]> {
+        self.0
+            .cell
+            .positions
+            .iter()
+            .map(|x| [x.x, x.y, x.z])
+            .collect()
+    }
+
+    #[getter]
+    pub fn numbers(&self) -> Vec<i32> {
+        self.0.cell.numbers.clone()
+    }
+
+    #[getter]
+    pub fn magnetic_moments(&self) -> Vec<f64> {
+        self.0.magnetic_moments.iter().map(|m| m.0).collect()
+    }
+
+    #[getter]
+    pub fn num_atoms(&self) -> usize {
+        self.0.num_atoms()
+    }
+
+    pub fn serialize_json(&self) -> PyResult<String> {
+        serde_json::to_string(self).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[classmethod]
+    pub fn deserialize_json(_cls: &Bound<'_, PyType>, s: &str) -> PyResult<Self> {
+        serde_json::from_str(s).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Create a magnetic cell from an ASE `Atoms` object, whose initial magnetic moments are used as collinear magnetic moments.
+    #[classmethod]
+    pub fn from_ase(_cls: &Bound<'_, PyType>, atoms: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (basis, positions, numbers) = cell_from_ase(atoms)?;
+        let magnetic_moments = atoms
+            .call_method0("get_initial_magnetic_moments")?
+            .call_method0("tolist")?
+            .extract()?;
+        Self::new(basis, positions, numbers, magnetic_moments)
+    }
+
+    /// Convert to an ASE `Atoms` object with collinear initial magnetic moments.
+    pub fn to_ase<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        ase_atoms(
+            py,
+            self.basis(),
+            self.positions(),
+            self.numbers(),
+            Some(self.magnetic_moments().into_py(py)),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CollinearMagneticCell(basis={:?}, positions={:?}, numbers={:?}, magnetic_moments={:?})",
+            self.basis(),
+            self.positions(),
+            self.numbers(),
+            self.magnetic_moments()
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl From<PyCollinearMagneticCell> for MagneticCell<Collinear> {
+    fn from(magnetic_cell: PyCollinearMagneticCell) -> Self {
+        magnetic_cell.0
+    }
+}
+
+impl From<MagneticCell<Collinear>> for PyCollinearMagneticCell {
+    fn from(magnetic_cell: MagneticCell<Collinear>) -> Self {
+        PyCollinearMagneticCell(magnetic_cell)
+    }
+}
+
+impl Serialize for PyCollinearMagneticCell {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PyCollinearMagneticCell {
+    fn deserialize<D>(deserializer: D) -> Result<PyCollinearMagneticCell, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        MagneticCell::<Collinear>::deserialize(deserializer).map(PyCollinearMagneticCell::from)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[pyclass(name = "NonCollinearMagneticCell", frozen)]
+#[pyo3(module = "moyopy")]
+pub struct PyNonCollinearMagneticCell(MagneticCell<NonCollinear>);
+
+#[pymethods]
+impl PyNonCollinearMagneticCell {
+    #[new]
+    /// basis: row-wise basis vectors
+    pub fn new(
+        basis: [[f64; 3]; 3],
+        positions: Vec<[f64; 3]>,
+        numbers: Vec<i32>,
+        magnetic_moments: Vec<[f64; 3]>,
+    ) -> PyResult<Self> {
+        if positions.len() != numbers.len() || positions.len() != magnetic_moments.len() {
+            return Err(PyValueError::new_err(
+                "positions, numbers, and magnetic_moments should be the same length",
+            ));
+        }
+
+        let magnetic_moments = magnetic_moments
+            .iter()
+            .map(|m| NonCollinear(Vector3::new(m[0], m[1], m[2])))
+            .collect();
+        Ok(Self(MagneticCell::new(
+            lattice_from_row_basis(basis),
+            positions_from_arrays(&positions),
+            numbers,
+            magnetic_moments,
+        )))
+    }
+
+    #[getter]
+    pub fn basis(&self) -> [[f64; 3]; 3] {
+        *self.0.cell.lattice.basis.as_ref()
+    }
+
+    #[getter]
+    pub fn positions(&self) -> Vec<[f64; 3]> {
+        self.0
+            .cell
+            .positions
+            .iter()
+            .map(|x| [x.x, x.y, x.z])
+            .collect()
+    }
+
+    #[getter]
+    pub fn numbers(&self) -> Vec<i32> {
+        self.0.cell.numbers.clone()
+    }
+
+    #[getter]
+    pub fn magnetic_moments(&self) -> Vec<[f64; 3]> {
+        self.0
+            .magnetic_moments
+            .iter()
+            .map(|m| [m.0.x, m.0.y, m.0.z])
+            .collect()
+    }
+
+    #[getter]
+    pub fn num_atoms(&self) -> usize {
+        self.0.num_atoms()
+    }
+
+    pub fn serialize_json(&self) -> PyResult<String> {
+        serde_json::to_string(self).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[classmethod]
+    pub fn deserialize_json(_cls: &Bound<'_, PyType>, s: &str) -> PyResult<Self> {
+        serde_json::from_str(s).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Create a magnetic cell from an ASE `Atoms` object, whose initial magnetic moments are used as non-collinear magnetic moments in cartesian coordinates.
+    #[classmethod]
+    pub fn from_ase(_cls: &Bound<'_, PyType>, atoms: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (basis, positions, numbers) = cell_from_ase(atoms)?;
+        let magnetic_moments = atoms
+            .call_method0("get_initial_magnetic_moments")?
+            .call_method0("tolist")?
+            .extract()?;
+        Self::new(basis, positions, numbers, magnetic_moments)
+    }
+
+    /// Convert to an ASE `Atoms` object with non-collinear initial magnetic moments.
+    pub fn to_ase<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        ase_atoms(
+            py,
+            self.basis(),
+            self.positions(),
+            self.numbers(),
+            Some(self.magnetic_moments().into_py(py)),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NonCollinearMagneticCell(basis={:?}, positions={:?}, numbers={:?}, magnetic_moments={:?})",
+            self.basis(),
+            self.positions(),
+            self.numbers(),
+            self.magnetic_moments()
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl From<PyNonCollinearMagneticCell> for MagneticCell<NonCollinear> {
+    fn from(magnetic_cell: PyNonCollinearMagneticCell) -> Self {
+        magnetic_cell.0
+    }
+}
+
+impl From<MagneticCell<NonCollinear>> for PyNonCollinearMagneticCell {
+    fn from(magnetic_cell: MagneticCell<NonCollinear>) -> Self {
+        PyNonCollinearMagneticCell(magnetic_cell)
+    }
+}
+
+impl Serialize for PyNonCollinearMagneticCell {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PyNonCollinearMagneticCell {
+    fn deserialize<D>(deserializer: D) -> Result<PyNonCollinearMagneticCell, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        MagneticCell::<NonCollinear>::deserialize(deserializer)
+            .map(PyNonCollinearMagneticCell::from)
+    }
+}
+
 #[derive(Debug)]
 #[pyclass(name = "MoyoError", frozen)]
 #[pyo3(module = "moyopy")]
@@ -181,7 +533,7 @@ impl From<Operations> for PyOperations {
 mod tests {
     extern crate approx;
 
-    use super::PyStructure;
+    use super::{PyNonCollinearMagneticCell, PyStructure};
     use approx::assert_relative_eq;
     use serde_json;
 
@@ -210,4 +562,29 @@ mod tests {
         }
         assert_eq!(structure.numbers(), deserialized.numbers());
     }
+
+    #[test]
+    fn test_noncollinear_magnetic_cell_serialization() {
+        let magnetic_cell = PyNonCollinearMagneticCell::new(
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            vec![[0.0, 0.0, 0.0], [0.5, 0.5, 0.5]],
+            vec![1, 1],
+            vec![[0.0, 0.0, 1.5], [0.3, -0.4, -1.5]],
+        )
+        .unwrap();
+
+        let serialized = serde_json::to_string(&magnetic_cell).unwrap();
+        let deserialized: PyNonCollinearMagneticCell = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(magnetic_cell.numbers(), deserialized.numbers());
+        for (expected, actual) in magnetic_cell
+            .magnetic_moments()
+            .iter()
+            .zip(deserialized.magnetic_moments().iter())
+        {
+            for j in 0..3 {
+                assert_relative_eq!(expected[j], actual[j]);
+            }
+        }
+    }
 }
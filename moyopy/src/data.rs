@@ -8,7 +8,7 @@ use pyo3::prelude::*;
 
 use super::base::{PyMoyoError, PyOperations};
 use moyo::base::{MoyoError, Operation};
-use moyo::data::{hall_symbol_entry, HallSymbol, Setting};
+use moyo::data::{hall_symbol_entry, space_groups_for_arithmetic_class, HallSymbol, Setting};
 
 #[pyfunction]
 pub fn operations_from_number(
@@ -46,6 +46,12 @@ pub fn operations_from_number(
     Ok(PyOperations::from(operations))
 }
 
+#[pyfunction]
+#[pyo3(name = "space_groups_for_arithmetic_class")]
+pub fn py_space_groups_for_arithmetic_class(arithmetic_number: i32) -> Vec<i32> {
+    space_groups_for_arithmetic_class(arithmetic_number)
+}
+
 #[cfg(test)]
 mod tests {
     use nalgebra::vector;
@@ -0,0 +1,52 @@
+use nalgebra::Matrix3;
+use pyo3::prelude::*;
+
+use super::base::PyMoyoError;
+use moyo::identify::PointGroup;
+
+#[derive(Debug)]
+#[pyclass(name = "PointGroup", frozen)]
+#[pyo3(module = "moyopy")]
+pub struct PyPointGroup(PointGroup);
+
+#[pymethods]
+impl PyPointGroup {
+    #[new]
+    /// prim_rotations: rotations in the (reduced) primitive basis
+    pub fn new(prim_rotations: Vec<[[i32; 3]; 3]>) -> Result<Self, PyMoyoError> {
+        // Since nalgebra stores matrices in column-major order, we need to transpose them
+        let prim_rotations = prim_rotations
+            .iter()
+            .map(|r| Matrix3::<i32>::from(*r).transpose())
+            .collect::<Vec<_>>();
+        let point_group = PointGroup::new(&prim_rotations)?;
+        Ok(Self(point_group))
+    }
+
+    #[getter]
+    pub fn arithmetic_number(&self) -> i32 {
+        self.0.arithmetic_number
+    }
+
+    #[getter]
+    pub fn prim_trans_mat(&self) -> [[i32; 3]; 3] {
+        // Since nalgebra stores matrices in column-major order, we need to transpose them
+        self.0.prim_trans_mat.transpose().into()
+    }
+
+    #[getter]
+    pub fn hermann_mauguin(&self) -> String {
+        self.0.hermann_mauguin()
+    }
+
+    #[getter]
+    pub fn order(&self) -> usize {
+        self.0.order()
+    }
+}
+
+impl From<PointGroup> for PyPointGroup {
+    fn from(point_group: PointGroup) -> Self {
+        PyPointGroup(point_group)
+    }
+}
@@ -3,13 +3,19 @@ use std::sync::OnceLock;
 
 pub mod base;
 pub mod data;
+pub mod identify;
 
 use moyo::base::AngleTolerance;
 use moyo::data::Setting;
 use moyo::MoyoDataset;
 
-use crate::base::{PyMoyoError, PyOperations, PyStructure};
-use crate::data::{operations_from_number, PyHallSymbolEntry, PySetting};
+use crate::base::{
+    PyCollinearMagneticCell, PyMoyoError, PyNonCollinearMagneticCell, PyOperations, PyStructure,
+};
+use crate::data::{
+    operations_from_number, py_space_groups_for_arithmetic_class, PyHallSymbolEntry, PySetting,
+};
+use crate::identify::PyPointGroup;
 
 #[derive(Debug)]
 #[pyclass(name = "MoyoDataset", frozen)]
@@ -52,6 +58,16 @@ impl PyMoyoDataset {
         self.0.hall_number
     }
 
+    #[getter]
+    pub fn arithmetic_number(&self) -> i32 {
+        self.0.arithmetic_number
+    }
+
+    #[getter]
+    pub fn arithmetic_symbol(&self) -> String {
+        self.0.arithmetic_symbol.clone()
+    }
+
     #[getter]
     pub fn operations(&self) -> PyOperations {
         self.0.operations.clone().into()
@@ -158,6 +174,8 @@ fn moyopy(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     // base
     m.add_class::<PyStructure>()?;
+    m.add_class::<PyCollinearMagneticCell>()?;
+    m.add_class::<PyNonCollinearMagneticCell>()?;
     m.add_class::<PyMoyoError>()?;
     m.add_class::<PyOperations>()?;
 
@@ -165,6 +183,10 @@ fn moyopy(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyHallSymbolEntry>()?;
     m.add_class::<PySetting>()?;
     m.add_wrapped(wrap_pyfunction!(operations_from_number))?;
+    m.add_wrapped(wrap_pyfunction!(py_space_groups_for_arithmetic_class))?;
+
+    // identify
+    m.add_class::<PyPointGroup>()?;
 
     Ok(())
 }